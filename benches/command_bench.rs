@@ -1,17 +1,32 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::sync::Arc;
+
+use groundhog::cli::{ExplainArgs, GlobalOptions};
 use groundhog::cli::commands::explain;
+use groundhog::core::services::AIService;
+use groundhog::infrastructure::Config;
 
 fn bench_explain_command(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let service = Arc::new(AIService::new());
+    let config = Config::default();
+    let options = GlobalOptions::default();
+
     c.bench_function("explain_command_no_topic", |b| {
         b.iter(|| {
-            let result = explain::execute(black_box(None));
+            let args = black_box(ExplainArgs::default());
+            let result = runtime.block_on(explain::execute(args, service.clone(), &config, &options));
             black_box(result)
         })
     });
 
     c.bench_function("explain_command_with_topic", |b| {
         b.iter(|| {
-            let result = explain::execute(black_box(Some("rust".to_string())));
+            let args = black_box(ExplainArgs {
+                topic: Some("rust".to_string()),
+                ..ExplainArgs::default()
+            });
+            let result = runtime.block_on(explain::execute(args, service.clone(), &config, &options));
             black_box(result)
         })
     });
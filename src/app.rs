@@ -0,0 +1,204 @@
+use std::ffi::OsString;
+use std::sync::Arc;
+
+use clap::error::ErrorKind;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, error, warn};
+
+use crate::cli::{Cli, Commands, GlobalOptions, execute_command, output::OutputFormat, progress::ProgressAwareStderr, writer::StdoutOutput};
+use crate::infrastructure::error::{CommandError, GroundhogError, InternalError};
+use crate::infrastructure::{Config, dotenv, log_buffer::LogBuffer, logging::init_tracing_with_writer, report};
+
+/// Log lines kept for the TUI debug overlay and `--report`
+const DEBUG_LOG_CAPACITY: usize = 200;
+
+/// Parse `args`, load configuration, initialize logging and run the command
+///
+/// `args` includes the binary name, as with `std::env::args_os()`. Help and
+/// version requests are printed and return `Ok`; other parse failures return
+/// `CommandError::Usage`. Logging is left alone if a global subscriber is
+/// already installed. With `--report`, any other failure is also written to
+/// a bug report.
+pub async fn run<I, T>(args: I) -> Result<(), GroundhogError>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let mut cli = match Cli::try_parse_styled(args) {
+        Ok(cli) => cli,
+        Err(e) => return usage_error(e),
+    };
+
+    let Some(report_path) = cli.report.take() else {
+        return run_cli(cli, &mut None, None).await;
+    };
+    let logs = LogBuffer::new(DEBUG_LOG_CAPACITY);
+    let mut config = None;
+    let result = run_cli(cli, &mut config, Some(logs.clone())).await;
+    if let Err(e) = &result
+        && !matches!(e, GroundhogError::Command(CommandError::Usage(_)))
+    {
+        match report::write(&report_path, e, config.as_ref(), &logs.recent(DEBUG_LOG_CAPACITY)) {
+            Ok(()) => eprintln!("Bug report written to {}", report_path.display()),
+            Err(report_error) => eprintln!(
+                "error: Failed to write bug report '{}': {}",
+                report_path.display(),
+                report_error.user_message()
+            ),
+        }
+    }
+    result
+}
+
+/// Run the parsed command line, keeping the configuration in `loaded` once it is read
+///
+/// `logs` records log lines at the current verbosity, for `--report`.
+async fn run_cli(mut cli: Cli, loaded: &mut Option<Config>, logs: Option<LogBuffer>) -> Result<(), GroundhogError> {
+    // Variables from --env-file must be set before configuration is resolved
+    if let Some(path) = &cli.env_file {
+        dotenv::load(path)?;
+    }
+
+    // Load configuration, skipping every config file with --no-config
+    let mut config = if cli.no_config {
+        Config::default()
+    } else {
+        Config::load_hierarchical(cli.config.clone(), cli.profile.clone())?
+    };
+    *loaded = Some(config.clone());
+
+    // Without a subcommand, run the configured `commands.default`
+    let command = match cli.command_or_default(config.commands.default.as_deref()) {
+        Ok(command) => command,
+        Err(e) => return usage_error(e),
+    };
+
+    // Inspection commands must work with a broken configuration, and `config validate` and
+    // `doctor` report every problem themselves instead of stopping at the first
+    if !matches!(command, Commands::Config { .. } | Commands::Cache { .. } | Commands::Providers { .. } | Commands::Doctor) {
+        config.validate()?;
+    }
+
+    if cli.no_color {
+        config.output.color = false;
+    }
+    if let Some(path) = cli.json_logs_to.clone() {
+        config.logging.json_file = Some(path);
+    }
+    if let Some(path) = cli.trace_file.clone() {
+        config.logging.trace_file = Some(path);
+    }
+
+    // The TUI debug overlay shows log lines at the current verbosity
+    let log_buffer = logs.or_else(|| {
+        matches!(command, Commands::Tui { debug: true, .. }).then(|| LogBuffer::new(DEBUG_LOG_CAPACITY))
+    });
+
+    // --silent logs like --quiet and additionally hides progress indicators
+    let quiet = cli.quiet || cli.silent;
+
+    // Initialize tracing based on verbosity, unless the embedding program already installed a subscriber
+    let handles = if tracing::dispatcher::has_been_set() {
+        None
+    } else {
+        Some(
+            init_tracing_with_writer(cli.verbose, quiet, &config.logging, ProgressAwareStderr, log_buffer.clone())
+                .map_err(|source| InternalError::InitializationFailed { component: "logging".to_string(), source })?,
+        )
+    };
+    let log_filter = handles.as_ref().map(|handles| handles.log_filter.clone());
+    let trace_writer = handles.and_then(|handles| handles.trace_writer);
+
+    info!(
+        command = ?command,
+        verbose = cli.verbose,
+        quiet,
+        silent = cli.silent,
+        config_path = ?cli.config,
+        config_loaded = true,
+        "Starting groundhog application"
+    );
+    
+    // --profile conflicts with --no-config, but the variable can be left over from another run
+    if cli.no_config
+        && let Ok(profile) = std::env::var("GROUNDHOG_PROFILE")
+    {
+        warn!(profile = %profile, "Ignoring GROUNDHOG_PROFILE because --no-config skips every config file");
+    }
+
+    let output = match cli.output {
+        Some(output) => output,
+        None => OutputFormat::from_config(&config.output.format)?,
+    };
+
+    let options = GlobalOptions {
+        config_path: cli.config,
+        profile: cli.profile,
+        no_config: cli.no_config,
+        quiet,
+        silent: cli.silent,
+        output,
+        max_output_lines: cli.max_output_lines.map(usize::from).or(config.output.max_lines),
+        log_buffer,
+        log_filter,
+        cancel: CancellationToken::new(),
+        writer: Arc::new(StdoutOutput),
+    };
+    let result = match execute_command(command, &config, &options).await {
+        // Readers such as `head` close the pipe once they have what they need
+        Err(e) if e.is_output_closed() => {
+            info!("Output closed by the reader");
+            Ok(())
+        }
+        result => result,
+    };
+
+    // Spans are only complete once the command has returned
+    if let Some(trace_writer) = trace_writer
+        && let Err(e) = trace_writer.flush()
+    {
+        eprintln!("error: Failed to write trace file '{}': {}", trace_writer.path().display(), e.user_message());
+    }
+
+    match &result {
+        Ok(()) => info!("Command completed successfully"),
+        Err(e) => error!(error = %e, "Command failed"),
+    }
+    result
+}
+
+/// Print help and version output, turning real parse failures into errors
+fn usage_error(error: clap::Error) -> Result<(), GroundhogError> {
+    match error.kind() {
+        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
+            // Like `clap::Error::exit`, a closed stdout is not worth failing over
+            let _ = error.print();
+            Ok(())
+        }
+        _ => Err(CommandError::Usage(error).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keep `run` from installing a stderr subscriber for the whole test binary
+    fn silence_logging() {
+        let _ = tracing::subscriber::set_global_default(tracing::subscriber::NoSubscriber::default());
+    }
+
+    #[tokio::test]
+    async fn test_run_explain_without_topic() {
+        silence_logging();
+        assert!(run(["groundhog", "--no-config", "explain"]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_usage_errors() {
+        silence_logging();
+        let error = run(["groundhog", "--no-config", "--bogus"]).await.unwrap_err();
+        assert!(matches!(error, GroundhogError::Command(CommandError::Usage(_))));
+        assert_eq!(error.exit_code(), 2);
+    }
+}
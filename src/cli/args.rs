@@ -1,42 +1,429 @@
-use clap::{Parser, Subcommand};
+use clap::builder::styling::{AnsiColor, Styles};
+use clap::{ArgMatches, Args, ColorChoice, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum, error::ErrorKind};
+use std::ffi::OsString;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::cli::output::OutputFormat;
+use crate::core::request::{ExplainDepth, ExplainFormat};
+use crate::infrastructure::config::AiProvider;
+
+/// Colors for help and usage errors
+const STYLES: Styles = Styles::styled()
+    .header(AnsiColor::Yellow.on_default().bold())
+    .usage(AnsiColor::Yellow.on_default().bold())
+    .literal(AnsiColor::Green.on_default().bold())
+    .placeholder(AnsiColor::Cyan.on_default())
+    .error(AnsiColor::Red.on_default().bold())
+    .valid(AnsiColor::Green.on_default())
+    .invalid(AnsiColor::Yellow.on_default());
 
 #[derive(Debug, Parser)]
 #[command(name = "groundhog")]
 #[command(about = "An AI coding assistant command line application")]
 #[command(version = "0.1.0")]
 #[command(author = "Groundhog Team")]
-#[command(subcommand_required = true)]
 #[command(arg_required_else_help = false)]
+#[command(styles = STYLES)]
 pub struct Cli {
     /// Increase logging verbosity (can be repeated)
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
-    /// Suppress non-error output
+    /// Only log errors; spinners and progress bars are still shown
     #[arg(short, long)]
     pub quiet: bool,
 
-    /// Path to configuration file
+    /// Like --quiet, and also hide spinners and progress bars
+    #[arg(long)]
+    pub silent: bool,
+
+    /// Path to configuration file, or `-` to read TOML from stdin
     #[arg(short, long, value_name = "FILE")]
     pub config: Option<PathBuf>,
 
+    /// Configuration profile to apply (defaults to GROUNDHOG_PROFILE)
+    #[arg(long, value_name = "NAME", conflicts_with = "no_config")]
+    pub profile: Option<String>,
+
+    /// Load environment variables such as API keys from this file, e.g. `.env`
+    #[arg(long, value_name = "FILE")]
+    pub env_file: Option<PathBuf>,
+
+    /// Ignore all configuration files and use the built-in defaults
+    #[arg(long, conflicts_with = "config")]
+    pub no_config: bool,
+
+    /// Output format (defaults to output.format from config)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub output: Option<OutputFormat>,
+
+    /// Truncate printed output to N lines (defaults to output.max_lines)
+    #[arg(long, value_name = "N")]
+    pub max_output_lines: Option<std::num::NonZeroUsize>,
+
+    /// Also write logs as JSON lines to this file (overrides logging.json_file)
+    #[arg(long, value_name = "FILE")]
+    pub json_logs_to: Option<PathBuf>,
+
+    /// Write span timings to this file in Chrome trace format
+    #[arg(long, value_name = "FILE")]
+    pub trace_file: Option<PathBuf>,
+
+    /// Disable colors in help, usage errors and highlighted code (also honors NO_COLOR)
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// If the command fails, write a bug report with the error, the redacted configuration,
+    /// platform details and recent log lines to FILE (default: groundhog-report.md)
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "groundhog-report.md")]
+    pub report: Option<PathBuf>,
+
+    /// Falls back to `commands.default` from the configuration when omitted
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
+}
+
+impl Cli {
+    /// Parse `args`, coloring help and usage errors unless `--no-color` is among them
+    ///
+    /// The flag is checked before parsing so it also applies to `--help` and
+    /// to the error for a bad argument.
+    pub fn try_parse_styled<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        let color = if args.iter().any(|arg| arg == "--no-color") {
+            ColorChoice::Never
+        } else {
+            ColorChoice::Auto
+        };
+
+        let mut command = Cli::command().color(color);
+        let mut matches: ArgMatches = command.try_get_matches_from_mut(args)?;
+        Cli::from_arg_matches_mut(&mut matches).map_err(|e| e.format(&mut command))
+    }
+
+    /// Subcommand to run, parsing `default` (e.g. "explain") when none was given
+    pub fn command_or_default(&mut self, default: Option<&str>) -> Result<Commands, clap::Error> {
+        if let Some(command) = self.command.take() {
+            return Ok(command);
+        }
+
+        let Some(default) = default else {
+            return Err(Cli::command().error(
+                ErrorKind::MissingSubcommand,
+                "a subcommand is required; set commands.default in the configuration to run one without arguments",
+            ));
+        };
+
+        let args = std::iter::once("groundhog").chain(default.split_whitespace());
+        DefaultCommand::try_parse_from(args).map(|parsed| parsed.command)
+    }
+}
+
+/// Parse a provider name such as `openai`, ignoring case
+fn parse_provider(value: &str) -> Result<AiProvider, String> {
+    AiProvider::ALL
+        .into_iter()
+        .find(|provider| provider.to_string().eq_ignore_ascii_case(value.trim()))
+        .ok_or_else(|| format!("unknown provider '{}', expected openai, anthropic or local", value))
+}
+
+/// Parse a duration such as `30s`, `500ms` or `2m`; a bare number is seconds
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a duration such as 30s, 500ms or 2m", value))?;
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(number)),
+        "" | "s" => Ok(Duration::from_secs(number)),
+        "m" => Ok(Duration::from_secs(number.saturating_mul(60))),
+        other => Err(format!("unknown duration unit '{}', expected ms, s or m", other)),
+    }
+}
+
+/// Parser for the `commands.default` subcommand line
+#[derive(Debug, Parser)]
+#[command(name = "groundhog")]
+struct DefaultCommand {
+    #[command(subcommand)]
+    command: Commands,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Provides explanations and demonstrations
-    Explain {
-        /// Future: example topics
-        #[arg(long)]
-        topic: Option<String>,
-    },
+    ///
+    /// With `--topic`, asks the configured AI provider to explain the topic.
+    /// With one or more `--file` options, explains each file, using the topic
+    /// as the focus; files are explained in parallel up to
+    /// `performance.threads` and large files are split into chunks.
+    /// `--explain-format annotated` prints the file with per-line notes
+    /// instead of prose, and `--explain-format patch` prints a unified diff
+    /// making the change the topic describes, which `--apply` writes to the
+    /// file after backing it up as `<file>.orig`. Topic explanations are
+    /// printed as they arrive when stdout is a terminal; `--stream` and
+    /// `--no-stream` override that.
+    ///
+    /// Without an `[ai]` section in the configuration, a placeholder
+    /// explanation is printed instead of calling a provider.
+    Explain(Box<ExplainArgs>),
     /// Launch the TUI (Terminal User Interface)
+    ///
+    /// Opens an interactive terminal interface for asking for explanations.
+    /// Key bindings come from `[tui.keys]`; press the help key to list them.
+    /// `--debug` adds an overlay with recent log lines and frame statistics.
+    /// `--prompt` sends a first prompt as soon as the interface opens.
     Tui {
-        /// Enable TUI debug mode
+        /// Show a debug overlay with recent log lines and frame statistics
         #[arg(long)]
         debug: bool,
+        /// Prompt to submit when the TUI starts
+        #[arg(long, value_name = "PROMPT")]
+        prompt: Option<String>,
+    },
+    /// Answer prompts read from stdin, one per line
+    ///
+    /// A lighter alternative to the TUI: each line is sent as a prompt, with
+    /// the earlier turns as context, and the answer is printed. Ends at the
+    /// end of input or on a `:quit` line.
+    Repl,
+    /// Check connectivity and authentication with the AI provider
+    ///
+    /// Sends a minimal request to the provider configured under `[ai]` and
+    /// prints the provider, model and round-trip latency. Fails if no `[ai]`
+    /// section is configured or the provider rejects the request.
+    /// `--wait` keeps retrying while the provider is unreachable, e.g. while
+    /// a local model server starts, printing a dot per retry. With
+    /// `--output json` the provider, model and `latency_ms` are reported as data.
+    Ping {
+        /// Retry until the provider answers or this much time has passed, e.g. 30s, 500ms or 2m
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        wait: Option<Duration>,
+    },
+    /// Inspect and manage configuration
+    ///
+    /// `paths` lists the files configuration is loaded from, highest
+    /// precedence first. `diff` shows the settings that differ from the
+    /// built-in defaults after all files and the profile are merged.
+    /// `schema` prints a JSON Schema of the configuration file, which TOML
+    /// editors can use for validation and completion. `show --effective`
+    /// prints every setting as commands see it, with secrets masked.
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+    /// Inspect supported AI providers
+    ///
+    /// `list` prints every supported provider, marking the configured one,
+    /// and queries the models it offers when an API key is available or the
+    /// provider is local.
+    Providers {
+        #[command(subcommand)]
+        action: ProvidersCommands,
+    },
+    /// Diagnose the environment groundhog runs in
+    ///
+    /// Checks that the configuration loads and is valid, the API key
+    /// resolves, the provider answers a ping, the log and metrics directories
+    /// are writable and the terminal can run the TUI. Prints a checklist and
+    /// fails if any check fails; warnings do not fail the command. With
+    /// `--output json` each check's name, status and detail are reported in a
+    /// `checks` array instead, and failures still set the exit code.
+    Doctor,
+    /// Inspect and clear the explain response cache
+    ///
+    /// `stats` prints the number and total size of cached responses and
+    /// `clear` removes them. The cache is enabled with
+    /// `performance.cache.enabled`.
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommands,
+    },
+}
+
+/// Level of detail accepted by `--depth`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DepthArg {
+    /// One sentence
+    Brief,
+    /// A paragraph
+    Normal,
+    /// A thorough walkthrough
+    Deep,
+}
+
+impl From<DepthArg> for ExplainDepth {
+    fn from(depth: DepthArg) -> Self {
+        match depth {
+            DepthArg::Brief => ExplainDepth::Brief,
+            DepthArg::Normal => ExplainDepth::Normal,
+            DepthArg::Deep => ExplainDepth::Deep,
+        }
+    }
+}
+
+/// Layout of file explanations accepted by `--explain-format`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FormatArg {
+    /// A prose explanation of the whole file
+    #[default]
+    Prose,
+    /// The file's lines with per-line notes interleaved, like an annotated diff
+    Annotated,
+    /// A unified diff that makes the change described by `--topic`, checked to apply cleanly
+    Patch,
+}
+
+impl From<FormatArg> for ExplainFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Prose => ExplainFormat::Prose,
+            FormatArg::Annotated => ExplainFormat::Annotated,
+            FormatArg::Patch => ExplainFormat::Patch,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ExplainArgs {
+    /// Topic to explain, or the focus when explaining files
+    #[arg(long)]
+    pub topic: Option<String>,
+
+    /// Language code to answer in (defaults to output.language or LANG)
+    #[arg(long, value_name = "CODE")]
+    pub lang: Option<String>,
+
+    /// How detailed the explanation should be (defaults to commands.explain.depth)
+    #[arg(long, value_enum)]
+    pub depth: Option<DepthArg>,
+
+    /// Ask for references to sources and gather them into a footnotes section at the end
+    #[arg(long, conflicts_with_all = ["raw", "json_schema", "compare"])]
+    pub cite: bool,
+
+    /// Sampling seed for reproducible output (defaults to ai.seed)
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+
+    /// Sampling temperature between 0 and 2 (defaults to ai.params.temperature)
+    #[arg(long, value_name = "T")]
+    pub temperature: Option<f64>,
+
+    /// Maximum tokens to generate (defaults to ai.params.max_tokens)
+    #[arg(long, value_name = "N")]
+    pub max_tokens: Option<u32>,
+
+    /// Include files matching this glob as context, e.g. 'src/**/*.rs' (can be repeated)
+    #[arg(long = "context", value_name = "GLOB")]
+    pub context: Vec<String>,
+
+    /// Prompt template for the topic, with `{input}` standing for the topic
+    #[arg(long, value_name = "TEMPLATE", requires = "topic")]
+    pub template: Option<String>,
+
+    /// Send the topic exactly as given, with no template, language or depth
+    /// instruction or context; takes precedence over --template, --lang,
+    /// --depth and --context
+    #[arg(long, requires = "topic", conflicts_with = "files")]
+    pub raw: bool,
+
+    /// Request N independent explanations of the topic and print them numbered
+    #[arg(long, value_name = "N", requires = "topic", conflicts_with = "files")]
+    pub count: Option<std::num::NonZeroUsize>,
+
+    /// File to explain, or `-` for standard input (can be repeated)
+    #[arg(long = "file", value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// How file explanations are laid out
+    #[arg(long, value_enum, default_value_t = FormatArg::Prose)]
+    pub explain_format: FormatArg,
+
+    /// Apply the `--explain-format patch` diffs to the files, keeping each original as `<file>.orig`
+    #[arg(long, requires = "files")]
+    pub apply: bool,
+
+    /// Write the explanation to a file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub out: Option<PathBuf>,
+
+    /// Overwrite the `--out` file if it already exists
+    #[arg(long, requires = "out")]
+    pub force: bool,
+
+    /// Append the explanation to a file, with a timestamped separator, instead of printing it
+    #[arg(long, value_name = "PATH", conflicts_with = "out")]
+    pub append: Option<PathBuf>,
+
+    /// Also copy the explanation to the system clipboard
+    #[arg(long)]
+    pub clipboard: bool,
+
+    /// Print the explanation as it arrives (the default on a terminal)
+    #[arg(long, overrides_with = "no_stream")]
+    pub stream: bool,
+
+    /// Wait for the whole explanation before printing it (the default when piped)
+    #[arg(long, overrides_with = "stream")]
+    pub no_stream: bool,
+
+    /// Ask for a JSON reply matching the schema in FILE, failing if the reply does not match
+    #[arg(long, value_name = "FILE")]
+    pub json_schema: Option<PathBuf>,
+
+    /// Keep explaining the remaining files after a failure and report all failures at the end
+    #[arg(long)]
+    pub keep_going: bool,
+
+    /// Send the topic to each of these providers at once and print the answers side by side, e.g. openai,anthropic
+    #[arg(
+        long,
+        value_name = "PROVIDERS",
+        value_delimiter = ',',
+        value_parser = parse_provider,
+        requires = "topic",
+        conflicts_with_all = ["files", "count", "out", "append", "clipboard"]
+    )]
+    pub compare: Vec<AiProvider>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProvidersCommands {
+    /// List supported providers and the models of the configured one
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommands {
+    /// Print the number and size of cached responses
+    Stats,
+    /// Remove every cached response
+    Clear,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    /// Print configuration search paths in precedence order
+    Paths,
+    /// Show settings that differ from the built-in defaults
+    Diff,
+    /// Check the configuration and report every problem found
+    Validate,
+    /// Print a JSON Schema of the configuration file for editor validation and completion
+    Schema,
+    /// Print the configuration as TOML, with API keys and proxy passwords masked
+    Show {
+        /// Resolve everything commands use: merged files and profile, environment variables and global flags
+        #[arg(long)]
+        effective: bool,
     },
 } 
\ No newline at end of file
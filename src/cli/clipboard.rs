@@ -0,0 +1,13 @@
+/// Copy `text` to the system clipboard
+///
+/// Fails when no clipboard is available, as on headless machines. On X11 the
+/// text stays available after groundhog exits only if a clipboard manager
+/// takes it over.
+pub fn copy(text: &str) -> Result<(), arboard::Error> {
+    arboard::Clipboard::new()?.set_text(text)
+}
+
+/// Whether a system clipboard can be opened
+pub fn is_available() -> bool {
+    arboard::Clipboard::new().is_ok()
+}
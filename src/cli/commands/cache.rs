@@ -0,0 +1,48 @@
+use serde_json::json;
+use tracing::{info, instrument};
+
+use crate::cli::CacheCommands;
+use crate::cli::commands::GlobalOptions;
+use crate::core::cache::ResponseCache;
+use crate::core::models::CommandResult;
+use crate::infrastructure::Config;
+use crate::infrastructure::error::{ConfigError, GroundhogError};
+
+/// Execute a cache subcommand
+///
+/// Both subcommands work on the configured cache directory whether or not
+/// caching is enabled.
+#[instrument(name = "command.cache", fields(command = "cache", action = ?action), skip(config, options))]
+pub fn execute(action: CacheCommands, config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    let dir = config.performance.cache.directory().ok_or_else(|| ConfigError::MissingKey {
+        key: "performance.cache.dir".to_string(),
+    })?;
+    let cache = ResponseCache::new(dir);
+    let print = !options.output.is_json();
+
+    match action {
+        CacheCommands::Stats => {
+            info!(dir = %cache.dir().display(), "Reading cache statistics");
+            let stats = cache.stats()?;
+            if print {
+                options.writer.write_line(&format!("Directory: {}", cache.dir().display()))?;
+                options.writer.write_line(&format!("Entries: {}", stats.entries))?;
+                options.writer.write_line(&format!("Size: {} bytes", stats.bytes))?;
+            }
+            Ok(CommandResult::success().with_data(json!({
+                "dir": cache.dir(),
+                "enabled": config.performance.cache.enabled,
+                "entries": stats.entries,
+                "bytes": stats.bytes,
+            })))
+        }
+        CacheCommands::Clear => {
+            info!(dir = %cache.dir().display(), "Clearing cache");
+            let removed = cache.clear()?;
+            if print {
+                options.writer.write_line(&format!("Removed {} cached responses", removed))?;
+            }
+            Ok(CommandResult::success().with_data(json!({ "removed": removed })))
+        }
+    }
+}
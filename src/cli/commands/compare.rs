@@ -0,0 +1,259 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tokio::task::JoinSet;
+use tracing::{info, instrument};
+
+use crate::cli::ExplainArgs;
+use crate::cli::commands::GlobalOptions;
+use crate::cli::commands::explain::{self, explain_topic};
+use crate::cli::progress::with_spinner;
+use crate::core::models::CommandResult;
+use crate::core::request::ExplainRequest;
+use crate::core::services::{Explainer, Usage};
+use crate::infrastructure::Config;
+use crate::infrastructure::error::{CommandError, ConfigError, GroundhogError, InternalError};
+
+/// Width assumed for the columns when output is not a terminal
+const DEFAULT_WIDTH: usize = 100;
+
+/// Narrowest column, below which columns overflow the width instead
+const MIN_COLUMN_WIDTH: usize = 20;
+
+/// Spaces between columns
+const GAP: &str = "  ";
+
+/// Answer of one provider to a compared prompt
+#[derive(Debug)]
+pub struct Answer {
+    /// Provider and model, e.g. "OpenAI (gpt-4o-mini)"
+    pub label: String,
+    pub latency: Duration,
+    pub result: Result<(String, Usage), GroundhogError>,
+}
+
+/// Explain `--topic` with every `--compare` provider at once and print the answers side by side
+///
+/// Each provider uses the `[ai]` section, switched to it as described by
+/// [`AiConfig::for_provider`](crate::infrastructure::config::AiConfig::for_provider).
+/// Every answer is shown even when some providers fail, and the command then
+/// fails.
+#[instrument(name = "command.compare", fields(command = "explain", providers = ?args.compare), skip(args, config, options))]
+pub async fn execute(args: ExplainArgs, config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    let ai = config.ai.as_ref().ok_or_else(|| ConfigError::MissingKey {
+        key: "ai".to_string(),
+    })?;
+    let request = explain::requests(&args, config)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| InternalError::UnexpectedState {
+            message: "--compare needs a topic".to_string(),
+        })?;
+
+    let mut contenders = Vec::new();
+    let mut unusable = Vec::new();
+    for provider in &args.compare {
+        let ai = ai.for_provider(provider);
+        let label = format!("{} ({})", ai.provider, ai.model);
+        if let Some(env_name) = ai.provider.api_key_env()
+            && ai.resolve_api_key().is_none()
+        {
+            let error = ConfigError::MissingKey {
+                key: format!("ai.api_key (or {})", env_name),
+            };
+            unusable.push((contenders.len() + unusable.len(), label, error.into()));
+            continue;
+        }
+        let mut config = config.clone();
+        config.ai = Some(ai);
+        contenders.push((label, Arc::new(super::explain_service(&args, &config)?) as Arc<dyn Explainer>));
+    }
+
+    info!(providers = contenders.len(), "Comparing providers");
+    let mut answers = with_spinner("Waiting for AI responses", !options.silent, compare(&request, contenders)).await;
+    for (index, label, error) in unusable {
+        let answer = Answer {
+            label,
+            latency: Duration::ZERO,
+            result: Err(error),
+        };
+        answers.insert(index, answer);
+    }
+
+    if !options.output.is_json() {
+        let width = match options.writer.is_tty() {
+            true => ratatui::crossterm::terminal::size().map_or(DEFAULT_WIDTH, |(columns, _)| usize::from(columns)),
+            false => DEFAULT_WIDTH,
+        };
+        options.writer.write_raw(&render_columns(&answers, width))?;
+    }
+
+    let data = json!({
+        "answers": answers
+            .iter()
+            .map(|answer| json!({
+                "provider": answer.label,
+                "latency_ms": answer.latency.as_millis() as u64,
+                "text": answer.result.as_ref().ok().map(|(text, _)| text),
+                "usage": answer.result.as_ref().ok().map(|(_, usage)| usage),
+                "error": answer.result.as_ref().err().map(|e| e.user_message()),
+            }))
+            .collect::<Vec<_>>(),
+    });
+    let failed = answers.iter().filter(|answer| answer.result.is_err()).count();
+    if failed > 0 {
+        return Err(GroundhogError::WithData {
+            data,
+            source: Box::new(
+                CommandError::PartialFailure {
+                    command: "explain".to_string(),
+                    failed,
+                    total: answers.len(),
+                }
+                .into(),
+            ),
+        });
+    }
+    Ok(CommandResult::success().with_data(data))
+}
+
+/// Send `request` to every contender concurrently, returning the answers in contender order
+pub async fn compare(request: &ExplainRequest, contenders: Vec<(String, Arc<dyn Explainer>)>) -> Vec<Answer> {
+    let mut tasks = JoinSet::new();
+    for (index, (label, service)) in contenders.into_iter().enumerate() {
+        let request = request.clone();
+        tasks.spawn(async move {
+            let start = Instant::now();
+            let result = explain_topic(&request, service.as_ref())
+                .await
+                .map(|response| (response.completion.text, response.usage));
+            let answer = Answer {
+                label,
+                latency: start.elapsed(),
+                result,
+            };
+            (index, answer)
+        });
+    }
+
+    let mut answers = tasks.join_all().await;
+    answers.sort_by_key(|(index, _)| *index);
+    answers.into_iter().map(|(_, answer)| answer).collect()
+}
+
+/// Lay the answers out in columns that share `width` characters
+///
+/// Each column starts with the label, the latency and token usage, and a
+/// rule, followed by the answer wrapped to the column width.
+pub fn render_columns(answers: &[Answer], width: usize) -> String {
+    let count = answers.len().max(1);
+    let column_width = (width.saturating_sub(GAP.len() * (count - 1)) / count).max(MIN_COLUMN_WIDTH);
+
+    let columns: Vec<Vec<String>> = answers
+        .iter()
+        .map(|answer| {
+            let (stats, body) = match &answer.result {
+                Ok((text, usage)) => (
+                    format!(
+                        "{} ms, {} + {} tokens",
+                        answer.latency.as_millis(),
+                        usage.prompt_tokens,
+                        usage.completion_tokens
+                    ),
+                    text.clone(),
+                ),
+                Err(e) => ("failed".to_string(), format!("error: {}", e.user_message())),
+            };
+            let mut lines = wrap(&answer.label, column_width);
+            lines.extend(wrap(&stats, column_width));
+            lines.push("-".repeat(column_width));
+            lines.extend(wrap(&body, column_width));
+            lines
+        })
+        .collect();
+
+    let height = columns.iter().map(Vec::len).max().unwrap_or_default();
+    let mut output = String::new();
+    for row in 0..height {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let cell = column.get(row).map(String::as_str).unwrap_or_default();
+                format!("{}{}", cell, " ".repeat(column_width.saturating_sub(cell.chars().count())))
+            })
+            .collect();
+        output.push_str(cells.join(GAP).trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// Split `text` into lines of at most `width` characters, breaking between words where possible
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            // Words longer than a whole line are split across lines
+            while word.len() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..width).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::request::ExplainInput;
+    use crate::core::services::{AIService, MockProvider};
+
+    #[tokio::test]
+    async fn test_answers_are_labeled_side_by_side() {
+        let contenders: Vec<(String, Arc<dyn Explainer>)> = ["OpenAI (gpt-4o-mini)", "Anthropic (claude-3-5-haiku-latest)"]
+            .into_iter()
+            .zip(["Closures capture variables.", "A closure is an anonymous function."])
+            .map(|(label, reply)| {
+                let service = AIService::new().with_provider(Arc::new(MockProvider::new(reply)));
+                (label.to_string(), Arc::new(service) as Arc<dyn Explainer>)
+            })
+            .collect();
+        let request = ExplainRequest::new(ExplainInput::Topic("closures".to_string()));
+
+        let answers = compare(&request, contenders).await;
+        let rendered = render_columns(&answers, 80);
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(answers.len(), 2);
+        assert!(lines[0].starts_with("OpenAI (gpt-4o-mini)"));
+        assert!(lines[0].ends_with("Anthropic (claude-3-5-haiku-latest)"));
+        assert!(lines[3].starts_with("Closures capture variables."));
+        assert!(lines[3].contains("A closure is an anonymous"));
+        // Stats lines of both columns line up under their labels
+        let offset = lines[0].find("Anthropic").unwrap();
+        assert!(lines[1][..offset].contains(" ms, "));
+        assert!(lines[1][offset..].contains(" ms, "));
+    }
+
+    #[test]
+    fn test_wrap_breaks_between_words() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("first\n\nsecond", 10), vec!["first", "", "second"]);
+    }
+}
@@ -0,0 +1,148 @@
+use clap::ValueEnum;
+use serde_json::json;
+use tracing::{info, instrument};
+
+use crate::cli::ConfigCommands;
+use crate::cli::commands::GlobalOptions;
+use crate::core::models::CommandResult;
+use crate::infrastructure::{Config, ConfigSource};
+use crate::infrastructure::error::{GroundhogError, InternalError};
+
+/// Execute a config subcommand
+#[instrument(name = "command.config", fields(command = "config", action = ?action), skip(config, options))]
+pub fn execute(action: ConfigCommands, config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    let print = !options.output.is_json();
+    match action {
+        ConfigCommands::Paths => execute_paths(options, print),
+        ConfigCommands::Diff => execute_diff(config, options, print),
+        ConfigCommands::Validate => execute_validate(config, options, print),
+        ConfigCommands::Schema => execute_schema(options, print),
+        ConfigCommands::Show { effective } => execute_show(config, effective, options, print),
+    }
+}
+
+/// Print every configuration search path, marking existing and active files
+fn execute_paths(options: &GlobalOptions, print: bool) -> Result<CommandResult, GroundhogError> {
+    info!("Resolving configuration sources");
+
+    let sources = Config::resolve_sources(options.config_path.clone());
+    if print {
+        for (index, source) in sources.iter().enumerate() {
+            options.writer.write_line(&format_source(index + 1, source))?;
+        }
+
+        if !sources.iter().any(|s| s.active) {
+            options.writer.write_line("No configuration file found, using defaults")?;
+        }
+    }
+
+    Ok(CommandResult::success().with_data(json!(sources)))
+}
+
+/// Print every setting of the loaded configuration that differs from the defaults
+fn execute_diff(config: &Config, options: &GlobalOptions, print: bool) -> Result<CommandResult, GroundhogError> {
+    info!("Comparing configuration against defaults");
+
+    let diff = config.diff(&Config::default());
+    if print {
+        if diff.is_empty() {
+            options.writer.write_line("Configuration matches the defaults")?;
+        }
+        for (key, value, default) in &diff {
+            options.writer.write_line(&format!("{} = {} (default: {})", key, value, default))?;
+        }
+    }
+
+    let data = diff
+        .into_iter()
+        .map(|(key, value, default)| json!({ "key": key, "value": value, "default": default }))
+        .collect();
+    Ok(CommandResult::success().with_data(data))
+}
+
+/// Check the loaded configuration, failing with every problem found
+fn execute_validate(config: &Config, options: &GlobalOptions, print: bool) -> Result<CommandResult, GroundhogError> {
+    info!("Validating configuration");
+
+    config.validate_all()?;
+    if print {
+        options.writer.write_line("Configuration is valid")?;
+    }
+
+    Ok(CommandResult::success_with_message("Configuration is valid"))
+}
+
+/// Print the JSON Schema of the configuration file
+///
+/// TOML editors pick it up from a `#:schema <path>` comment at the top of
+/// `groundhog.toml`.
+fn execute_schema(options: &GlobalOptions, print: bool) -> Result<CommandResult, GroundhogError> {
+    info!("Generating configuration schema");
+
+    let schema = Config::json_schema();
+    if print {
+        options.writer.write_line(&format!("{:#}", schema))?;
+    }
+
+    Ok(CommandResult::success().with_data(schema))
+}
+
+/// Print the loaded configuration with secrets masked
+///
+/// With `effective`, the API key and language are resolved from the
+/// environment and `--output` and `--max-output-lines` are applied, as they
+/// are for every command.
+fn execute_show(config: &Config, effective: bool, options: &GlobalOptions, print: bool) -> Result<CommandResult, GroundhogError> {
+    info!(effective, "Showing configuration");
+
+    let mut config = config.clone();
+    if effective {
+        config = config.effective();
+        if let Some(format) = options.output.to_possible_value() {
+            config.output.format = format.get_name().to_string();
+        }
+        config.output.max_lines = options.max_output_lines;
+    }
+    if print {
+        options.writer.write_raw(&config.to_string())?;
+    }
+
+    let data = serde_json::to_value(config.to_redacted()).map_err(|e| InternalError::UnexpectedState {
+        message: format!("configuration is not serializable: {}", e),
+    })?;
+    Ok(CommandResult::success().with_data(data))
+}
+
+/// Format a single configuration source line
+fn format_source(position: usize, source: &ConfigSource) -> String {
+    let status = if source.active {
+        "active"
+    } else if source.exists {
+        "exists"
+    } else {
+        "missing"
+    };
+    format!("{}. {} [{}]", position, source.path.display(), status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_source_statuses() {
+        let mut source = ConfigSource {
+            path: PathBuf::from("groundhog.toml"),
+            exists: false,
+            active: false,
+        };
+        assert_eq!(format_source(1, &source), "1. groundhog.toml [missing]");
+
+        source.exists = true;
+        assert_eq!(format_source(2, &source), "2. groundhog.toml [exists]");
+
+        source.active = true;
+        assert_eq!(format_source(3, &source), "3. groundhog.toml [active]");
+    }
+}
@@ -0,0 +1,366 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::json;
+use tracing::{info, instrument};
+
+use crate::cli::commands::GlobalOptions;
+use crate::core::models::CommandResult;
+use crate::core::providers::HttpProvider;
+use crate::core::services::AIService;
+use crate::infrastructure::{Config, ConfigSource};
+use crate::infrastructure::config::AiConfig;
+use crate::infrastructure::error::{ConfigError, FileSystemError, GroundhogError};
+use crate::tui::ui;
+
+/// Smallest terminal the TUI lays out in, as columns and rows
+const MIN_TERMINAL_SIZE: (u16, u16) = (ui::MIN_WIDTH, ui::MIN_HEIGHT);
+
+const PROVIDER: &str = "Provider";
+
+/// Outcome of a single diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// Something groundhog can work around, such as a missing optional setting
+    Warn,
+    /// Something that keeps groundhog from working
+    Fail,
+}
+
+/// A line of the `doctor` checklist
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// Why a failed check failed, returned from the command
+    #[serde(skip)]
+    pub error: Option<GroundhogError>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Pass, detail: detail.into(), error: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Warn, detail: detail.into(), error: None }
+    }
+
+    fn fail(name: &'static str, error: impl Into<GroundhogError>) -> Self {
+        let error = error.into();
+        Self { name, status: CheckStatus::Fail, detail: error.user_message(), error: Some(error) }
+    }
+}
+
+/// Check the configuration, provider, writable directories and terminal
+///
+/// Every check runs and is printed before the command fails with the error
+/// of the first failed check. Warnings never fail the command. With
+/// `--output json` the checks are reported under `data.checks` whether or not
+/// any failed.
+#[instrument(name = "command.doctor", fields(command = "doctor"), skip(config, options))]
+pub async fn execute(config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    info!("Running environment diagnostics");
+
+    let sources = if options.no_config { Vec::new() } else { Config::resolve_sources(options.config_path.clone()) };
+    // A missing API key is reported by its own check
+    let problems = config
+        .validation_errors()
+        .into_iter()
+        .filter(|e| !matches!(e, ConfigError::MissingKey { key } if key.starts_with("ai.api_key")))
+        .collect();
+    let mut checks = vec![
+        check_config(&sources, problems, config.warnings()),
+        check_api_key(config.ai.as_ref(), |name| std::env::var(name).ok()),
+    ];
+    let provider = match &config.ai {
+        Some(_) if checks[1].status == CheckStatus::Fail => Check::warn(PROVIDER, "Skipped until an API key is set"),
+        Some(ai) => check_provider(ping(ai, config).await),
+        None => Check::warn(PROVIDER, "Skipped, no [ai] section is configured"),
+    };
+    checks.push(provider);
+    checks.extend(writable_dirs(config).into_iter().map(|(name, dir)| check_writable(name, &dir)));
+    let term = std::env::var("TERM").ok();
+    checks.push(check_terminal(
+        std::io::stdout().is_terminal(),
+        term.as_deref(),
+        ratatui::crossterm::terminal::size().ok(),
+    ));
+
+    if !options.output.is_json() {
+        for check in &checks {
+            options.writer.write_line(&format_check(check))?;
+        }
+    }
+
+    let data = json!({ "checks": checks });
+    match checks.into_iter().find_map(|check| check.error) {
+        Some(error) => Err(GroundhogError::WithData {
+            data,
+            source: Box::new(error),
+        }),
+        None => Ok(CommandResult::success().with_data(data)),
+    }
+}
+
+/// Whether a configuration file was found and the loaded configuration has none of `problems`
+///
+/// `warnings` about settings that are allowed but suspicious only warn.
+pub fn check_config(sources: &[ConfigSource], mut problems: Vec<ConfigError>, warnings: Vec<String>) -> Check {
+    const NAME: &str = "Configuration";
+    match problems.len() {
+        0 => {}
+        1 => return Check::fail(NAME, problems.remove(0)),
+        _ => return Check::fail(NAME, ConfigError::Multiple { errors: problems }),
+    }
+    if !warnings.is_empty() {
+        return Check::warn(NAME, warnings.join("\n"));
+    }
+    match sources.iter().find(|source| source.active) {
+        Some(source) => Check::pass(NAME, format!("Loaded {}", source.path.display())),
+        None => Check::warn(NAME, "No configuration file found, using defaults"),
+    }
+}
+
+/// Whether the configured provider's API key resolves, looking up environment variables with `env`
+pub fn check_api_key(ai: Option<&AiConfig>, env: impl Fn(&str) -> Option<String>) -> Check {
+    const NAME: &str = "API key";
+    let Some(ai) = ai else {
+        return Check::warn(NAME, "No [ai] section is configured; explanations are placeholders");
+    };
+    let Some(env_name) = ai.provider.api_key_env() else {
+        return Check::pass(NAME, format!("Not needed for the {} provider", ai.provider));
+    };
+
+    if ai.api_key.as_deref().is_some_and(|key| !key.trim().is_empty()) {
+        Check::pass(NAME, "Set by ai.api_key")
+    } else if ai.resolve_api_key_with(env).is_some() {
+        Check::pass(NAME, format!("Set by {}", env_name))
+    } else {
+        Check::fail(NAME, ConfigError::MissingKey {
+            key: format!("ai.api_key (or {})", env_name),
+        })
+    }
+}
+
+/// Whether a ping of the provider succeeded
+pub fn check_provider(ping: Result<Duration, GroundhogError>) -> Check {
+    match ping {
+        Ok(latency) => Check::pass(PROVIDER, format!("Reachable in {} ms", latency.as_millis())),
+        Err(e) => Check::fail(PROVIDER, e),
+    }
+}
+
+/// Whether groundhog can create files in `dir`
+///
+/// A directory that does not exist yet passes when the closest existing
+/// parent is writable, since it is created on first use.
+pub fn check_writable(name: &'static str, dir: &Path) -> Check {
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.exists()) else {
+        return Check::fail(name, FileSystemError::DirectoryNotAccessible { path: dir.to_path_buf() });
+    };
+    if !existing.is_dir() {
+        return Check::fail(name, FileSystemError::DirectoryNotAccessible { path: existing.to_path_buf() });
+    }
+
+    // Creating a file is the only reliable test, since permissions alone miss read-only mounts and ACLs
+    let probe = existing.join(format!(".groundhog-doctor-{}", std::process::id()));
+    match std::fs::OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            if existing == dir {
+                Check::pass(name, format!("{} is writable", dir.display()))
+            } else {
+                Check::pass(name, format!("{} will be created", dir.display()))
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied || e.kind() == std::io::ErrorKind::ReadOnlyFilesystem => {
+            Check::fail(name, FileSystemError::NotWritable { path: existing.to_path_buf() })
+        }
+        Err(e) => Check::fail(name, FileSystemError::Io(e)),
+    }
+}
+
+/// Whether the terminal can run the TUI
+///
+/// Only warns, since every other command works without a terminal.
+pub fn check_terminal(is_terminal: bool, term: Option<&str>, size: Option<(u16, u16)>) -> Check {
+    const NAME: &str = "Terminal";
+    let (min_columns, min_rows) = MIN_TERMINAL_SIZE;
+    if !is_terminal {
+        return Check::warn(NAME, "Output is not a terminal; the TUI needs an interactive terminal");
+    }
+    if term == Some("dumb") {
+        return Check::warn(NAME, "TERM is 'dumb'; the TUI needs cursor movement and colors");
+    }
+    match size {
+        Some((columns, rows)) if columns < min_columns || rows < min_rows => Check::warn(
+            NAME,
+            format!("{}x{} is smaller than the {}x{} the TUI needs", columns, rows, min_columns, min_rows),
+        ),
+        Some((columns, rows)) => Check::pass(NAME, format!("{}, {}x{}", term.unwrap_or("unknown"), columns, rows)),
+        None => Check::warn(NAME, "Could not read the terminal size"),
+    }
+}
+
+/// Send a single ping, without retries, so an unreachable provider is reported quickly
+async fn ping(ai: &AiConfig, config: &Config) -> Result<Duration, GroundhogError> {
+    let provider = HttpProvider::from_config(ai, Duration::from_secs(config.performance.timeout))?;
+    AIService::new().with_provider(Arc::new(provider)).ping().await
+}
+
+/// Directories groundhog writes to under the current configuration
+fn writable_dirs(config: &Config) -> Vec<(&'static str, PathBuf)> {
+    let files = [
+        ("Metrics directory", config.performance.metrics.resolve_path()),
+        ("Log directory", config.logging.file.clone()),
+        ("JSON log directory", config.logging.json_file.clone()),
+        ("Trace directory", config.logging.trace_file.clone()),
+    ];
+
+    let mut dirs: Vec<(&'static str, PathBuf)> = Vec::new();
+    for (name, file) in files {
+        let Some(file) = file else { continue };
+        let dir = match file.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        if !dirs.iter().any(|(_, known)| *known == dir) {
+            dirs.push((name, dir));
+        }
+    }
+    // The cache setting names the directory itself rather than a file in it
+    if let Some(dir) = config.performance.cache.resolve_dir()
+        && !dirs.iter().any(|(_, known)| *known == dir)
+    {
+        dirs.push(("Cache directory", dir));
+    }
+    dirs
+}
+
+/// Format a checklist entry, indenting the continuation lines of multi-line details
+fn format_check(check: &Check) -> String {
+    let status = match check.status {
+        CheckStatus::Pass => "pass",
+        CheckStatus::Warn => "warn",
+        CheckStatus::Fail => "FAIL",
+    };
+    format!("[{}] {}: {}", status, check.name, check.detail.replace('\n', "\n    "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::AiProvider;
+    use crate::infrastructure::error::NetworkError;
+    use tempfile::TempDir;
+
+    fn ai(provider: AiProvider, api_key: Option<&str>) -> AiConfig {
+        AiConfig {
+            api_key: api_key.map(String::from),
+            ..AiConfig::new(provider, "test-model")
+        }
+    }
+
+    #[test]
+    fn test_config_check() {
+        let source = |active| ConfigSource { path: PathBuf::from("groundhog.toml"), exists: active, active };
+
+        let check = check_config(&[source(false), source(true)], Vec::new(), Vec::new());
+        assert_eq!((check.status, check.detail.as_str()), (CheckStatus::Pass, "Loaded groundhog.toml"));
+        assert_eq!(check_config(&[source(false)], Vec::new(), Vec::new()).status, CheckStatus::Warn);
+        let check = check_config(&[source(true)], Vec::new(), vec!["Unknown model".to_string()]);
+        assert_eq!((check.status, check.detail.as_str()), (CheckStatus::Warn, "Unknown model"));
+
+        let invalid = || ConfigError::MissingKey { key: "ai.model".to_string() };
+        let check = check_config(&[source(true)], vec![invalid()], Vec::new());
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(matches!(check.error, Some(GroundhogError::Config(ConfigError::MissingKey { .. }))));
+
+        let check = check_config(&[source(true)], vec![invalid(), invalid()], Vec::new());
+        assert!(matches!(check.error, Some(GroundhogError::Config(ConfigError::Multiple { .. }))));
+        assert!(format_check(&check).contains("\n    "));
+    }
+
+    #[test]
+    fn test_api_key_check() {
+        let no_env = |_: &str| None;
+        let env = |name: &str| (name == "OPENAI_API_KEY").then(|| "sk-test".to_string());
+
+        assert_eq!(check_api_key(None, no_env).status, CheckStatus::Warn);
+        assert_eq!(check_api_key(Some(&ai(AiProvider::Local, None)), no_env).status, CheckStatus::Pass);
+        assert_eq!(check_api_key(Some(&ai(AiProvider::OpenAI, Some("key"))), no_env).detail, "Set by ai.api_key");
+        assert_eq!(check_api_key(Some(&ai(AiProvider::OpenAI, None)), env).detail, "Set by OPENAI_API_KEY");
+
+        let check = check_api_key(Some(&ai(AiProvider::Anthropic, Some("  "))), env);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(format_check(&check).starts_with("[FAIL] API key: Missing required configuration key 'ai.api_key (or ANTHROPIC_API_KEY)'"));
+    }
+
+    #[test]
+    fn test_provider_check() {
+        assert_eq!(check_provider(Ok(Duration::from_millis(42))).detail, "Reachable in 42 ms");
+
+        let check = check_provider(Err(NetworkError::Timeout { timeout_ms: 100 }.into()));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(matches!(check.error, Some(GroundhogError::Network(NetworkError::Timeout { .. }))));
+    }
+
+    #[test]
+    fn test_writable_check() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(check_writable("Log directory", temp_dir.path()).status, CheckStatus::Pass);
+
+        let missing = temp_dir.path().join("logs").join("groundhog");
+        let check = check_writable("Log directory", &missing);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.detail.ends_with("will be created"));
+        assert!(!missing.exists());
+
+        // A file where the directory should be
+        let file = temp_dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        let check = check_writable("Log directory", &file.join("logs"));
+        assert!(matches!(check.error, Some(GroundhogError::FileSystem(FileSystemError::DirectoryNotAccessible { .. }))));
+    }
+
+    #[test]
+    fn test_terminal_check_only_warns() {
+        assert_eq!(check_terminal(true, Some("xterm-256color"), Some((120, 40))).detail, "xterm-256color, 120x40");
+        for check in [
+            check_terminal(false, Some("xterm"), Some((120, 40))),
+            check_terminal(true, Some("dumb"), Some((120, 40))),
+            check_terminal(true, Some("xterm"), Some((30, 40))),
+            check_terminal(true, Some("xterm"), None),
+        ] {
+            assert_eq!(check.status, CheckStatus::Warn);
+            assert!(check.error.is_none());
+        }
+    }
+
+    #[test]
+    fn test_writable_dirs_are_deduplicated() {
+        let mut config = Config::default();
+        config.performance.metrics.enabled = false;
+        config.logging.file = Some(PathBuf::from("/var/log/groundhog/app.log"));
+        config.logging.json_file = Some(PathBuf::from("/var/log/groundhog/app.jsonl"));
+        config.logging.trace_file = Some(PathBuf::from("trace.json"));
+        config.performance.cache.enabled = true;
+        config.performance.cache.dir = Some(PathBuf::from("/var/cache/groundhog"));
+
+        assert_eq!(
+            writable_dirs(&config),
+            vec![
+                ("Log directory", PathBuf::from("/var/log/groundhog")),
+                ("Trace directory", PathBuf::from(".")),
+                ("Cache directory", PathBuf::from("/var/cache/groundhog")),
+            ]
+        );
+    }
+}
@@ -1,56 +1,1642 @@
-use tracing::{info, instrument};
-use crate::infrastructure::error::GroundhogError;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, instrument, warn};
+
+use crate::cli::ExplainArgs;
+use crate::cli::args::FormatArg;
+use crate::cli::clipboard;
+use crate::cli::commands::GlobalOptions;
+use crate::cli::highlight::Highlighter;
+use crate::cli::output::{self, ExplanationOutput, JsonLinesWriter, OutputFormat, append_output_file, write_output_file};
+use crate::cli::progress::{ProgressReporter, with_spinner};
+use crate::cli::writer::OutputWrite;
+use crate::core::annotate::{self, LineNotes};
+use crate::core::chunk;
+use crate::core::citations;
+use crate::core::context;
+use crate::core::models::CommandResult;
+use crate::core::patch;
+use crate::core::request::{ExplainDepth, ExplainFormat, ExplainInput, ExplainRequest, ExplainResponse, InputText};
+use crate::core::services::{Explainer, Usage, UsageTracker};
+use crate::core::tokens::estimate_tokens;
+use crate::infrastructure::config::{AiParams, Config, PerformanceConfig, STDIN_PATH};
+use crate::infrastructure::error::{CommandError, ConfigError, GroundhogError, InternalError};
+use crate::infrastructure::fs;
+
+/// Explanation produced for a single file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileExplanation {
+    pub path: PathBuf,
+    pub explanation: String,
+    /// Estimated usage of every prompt sent for the file
+    pub usage: UsageTracker,
+}
+
+/// A file that could not be explained
+#[derive(Debug)]
+pub struct FileFailure {
+    pub path: PathBuf,
+    pub error: GroundhogError,
+}
+
+/// Outcome of explaining several files, all lists in input order
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub succeeded: Vec<FileExplanation>,
+    pub failed: Vec<FileFailure>,
+    /// Files skipped or interrupted because the batch was cancelled
+    pub cancelled: Vec<PathBuf>,
+}
+
+impl BatchSummary {
+    /// Number of files requested
+    pub fn total(&self) -> usize {
+        self.succeeded.len() + self.failed.len() + self.cancelled.len()
+    }
+}
+
+/// Independent explanations of the same topic
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SampleSet {
+    /// Explanations in request order
+    pub texts: Vec<String>,
+    /// Token usage summed over every sample
+    pub usage: Usage,
+}
+
+/// How a batch of files is explained
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Collect failures instead of stopping at the first one
+    pub keep_going: bool,
+    /// Stops starting new files and abandons in-flight ones when triggered
+    pub cancel: CancellationToken,
+}
 
 /// Execute the explain command
 #[instrument(
     name = "command.explain",
     fields(
         command = "explain",
-        topic = ?topic,
+        topic = ?args.topic,
+        files = args.files.len(),
+        language = tracing::field::Empty,
         duration_ms = tracing::field::Empty,
-    )
+    ),
+    skip(args, service, config, options)
 )]
-pub fn execute(topic: Option<String>) -> Result<(), GroundhogError> {
+pub async fn execute(
+    args: ExplainArgs,
+    service: Arc<dyn Explainer>,
+    config: &Config,
+    options: &GlobalOptions,
+) -> Result<CommandResult, GroundhogError> {
     let start = std::time::Instant::now();
-    
+
     info!("Starting explain command");
-    
-    // Current implementation: simple hello world
-    // Future: implement actual explanation functionality based on topic
-    match topic {
-        Some(topic_str) => {
-            info!(topic = %topic_str, "Explaining topic");
-            println!("hello world - explaining: {}", topic_str);
+
+    check_format_args(&args, service.is_available())?;
+    let requests = requests(&args, config)?;
+    // Every request answers in the same language
+    let language = requests.first().and_then(|request| request.lang.clone()).or_else(|| service.language().map(str::to_string));
+    if let Some(language) = &language {
+        tracing::Span::current().record("language", language.as_str());
+    }
+    // JSON lines exist to be consumed as they arrive, so they stream even when piped
+    let stream = resolve_stream(&args, config, options.writer.is_tty() || options.output == OutputFormat::Jsonl);
+    let ExplainArgs { cite, count, apply, out, force, append, clipboard, keep_going, .. } = args;
+    let count = count.map_or(1, usize::from);
+    let mut cancelled = Vec::new();
+    let mut usage = None;
+    let mut batch_usage = None;
+
+    let (topics, files): (Vec<_>, Vec<_>) = requests.into_iter().partition(|request| request.topic().is_some());
+    let topic_request = topics.first();
+
+    // A single topic explanation can be printed chunk by chunk as it arrives, unless footnotes need the whole text
+    let streamable = stream && files.is_empty() && out.is_none() && append.is_none() && !clipboard && !cite && count == 1;
+    if streamable && options.output == OutputFormat::Jsonl {
+        let mut writer = JsonLinesWriter::new(OutputWrite(options.writer.as_ref()));
+        let usage = stream_topic(topic_request, service.as_ref(), &mut |delta| writer.delta(delta)).await?;
+        writer.finish()?;
+        info!("Explain command completed successfully");
+        return Ok(CommandResult::success().with_data(serde_json::json!({ "usage": usage })));
+    }
+    // Highlighting and truncation need the whole text, so they rule out streaming
+    if streamable && options.output == OutputFormat::Text && options.max_output_lines.is_none() {
+        let mut write_error = None;
+        let usage = stream_topic(topic_request, service.as_ref(), &mut |delta| {
+            if let Err(e) = options.writer.write_raw(delta)
+                && write_error.is_none()
+            {
+                write_error = Some(e);
+            }
+        })
+        .await?;
+        if let Some(e) = write_error {
+            return Err(e);
         }
-        None => {
-            info!("Explaining default topic");
-            println!("hello world");
+        options.writer.write_raw("\n")?;
+        info!("Explain command completed successfully");
+        return Ok(CommandResult::success().with_data(serde_json::json!({ "usage": usage })));
+    }
+
+    let mut failures = Vec::new();
+    let mut explanations = if !files.is_empty() {
+        // A single file gets a spinner instead of a one-step progress bar
+        let show_spinner = files.len() == 1 && service.is_available() && !options.silent;
+        let progress = ProgressReporter::new(files.len() as u64, files.len() > 1 && !options.silent);
+        let batch = BatchOptions {
+            keep_going,
+            cancel: options.cancel.clone(),
+        };
+        let results = with_spinner("Waiting for AI response", show_spinner, async {
+            let results = execute_many(files, service.clone(), &config.performance, &progress, &batch).await;
+            progress.finish();
+            results
+        })
+        .await;
+
+        let summary = results?;
+        let total = summary.total();
+        failures = summary.failed;
+        cancelled = summary.cancelled;
+        if !failures.is_empty() {
+            warn!(failed = failures.len(), total, "Some files could not be explained");
+        }
+        if apply {
+            for result in &summary.succeeded {
+                let backup = apply_patch_file(&result.path, &result.explanation, config.performance.max_file_size_bytes())?;
+                info!(path = %result.path.display(), backup = %backup.display(), "Applied patch");
+                if !options.output.is_json() && !options.quiet {
+                    eprintln!("Patched {} (original saved as {})", result.path.display(), backup.display());
+                }
+            }
+        }
+        if service.is_available() {
+            let mut tracker = UsageTracker::new().with_model(config.ai.as_ref().map(|ai| ai.model.clone()));
+            for result in &summary.succeeded {
+                tracker.merge(&result.usage);
+            }
+            batch_usage = Some(tracker);
+        }
+
+        summary
+            .succeeded
+            .into_iter()
+            .map(|result| {
+                ExplanationOutput::new(result.path.display().to_string(), result.explanation)
+                    .with_metadata("Source", result.path.display().to_string())
+            })
+            .collect()
+    } else if count > 1
+        && let Some(request) = topic_request
+        && let Some(topic_str) = request.topic()
+    {
+        // Without an AI backend every sample is the hello world demo
+        let texts = if service.is_available() {
+            info!(topic = %topic_str, count, "Sampling AI explanations");
+            let samples = with_spinner(
+                "Waiting for AI responses",
+                !options.silent,
+                sample_topic(request, count, service.clone(), &config.performance),
+            )
+            .await?;
+            usage = Some(samples.usage);
+            samples.texts
+        } else {
+            info!(topic = %topic_str, count, "Explaining topic");
+            vec![format!("hello world - explaining: {}", topic_str); count]
+        };
+        texts
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| ExplanationOutput::new(format!("{} ({}/{})", topic_str, index + 1, count), text))
+            .collect()
+    } else {
+        // Without an AI backend the command falls back to the hello world demo
+        let explanation = match topic_request {
+            Some(request) if service.is_available() => {
+                info!(topic = %request.input, "Generating AI explanation");
+                let response =
+                    with_spinner("Waiting for AI response", !options.silent, explain_topic(request, service.as_ref())).await?;
+                ExplanationOutput::new(response.source.to_string(), response.completion.text)
+            }
+            Some(request) => {
+                let topic_str = request.input.to_string();
+                info!(topic = %topic_str, "Explaining topic");
+                let body = format!("hello world - explaining: {}", topic_str);
+                ExplanationOutput::new(topic_str, body)
+            }
+            None => {
+                info!("Explaining default topic");
+                ExplanationOutput::new("Explanation", "hello world")
+            }
+        };
+        vec![explanation]
+    };
+
+    if cite {
+        for explanation in &mut explanations {
+            explanation.body = citations::with_footnotes(&explanation.body);
+        }
+    }
+
+    if let Some(language) = &language {
+        for explanation in &mut explanations {
+            explanation.metadata.push(("Language".to_string(), language.to_string()));
+        }
+    }
+
+    let mut result = CommandResult::success();
+    match (&out, &append) {
+        (Some(path), _) => {
+            write_output_file(path, &output::render(options.output, &explanations)?, force)?;
+            info!(path = %path.display(), "Wrote explanation to file");
+            result = result.with_message(format!("Wrote {}", path.display()));
+        }
+        (None, Some(path)) => {
+            append_output_file(path, &output::render(options.output, &explanations)?)?;
+            info!(path = %path.display(), "Appended explanation to file");
+            result = result.with_message(format!("Appended to {}", path.display()));
+        }
+        // JSON output is reported through the command result instead
+        _ if options.output == OutputFormat::Json => {
+            let data = output::to_json(&explanations);
+            result = result.with_data(match usage {
+                Some(usage) => serde_json::json!({ "explanations": data, "usage": usage }),
+                None => data,
+            });
+        }
+        _ if options.output == OutputFormat::Jsonl => {
+            let mut writer = JsonLinesWriter::new(OutputWrite(options.writer.as_ref()));
+            for explanation in &explanations {
+                writer.delta(&explanation.body);
+            }
+            writer.finish()?;
+            if let Some(usage) = usage {
+                result = result.with_data(serde_json::json!({ "usage": usage }));
+            }
+        }
+        _ if options.output == OutputFormat::Table => {
+            let color = output::color_enabled(&config.output, options.writer.is_tty());
+            let rendered = output::explanation_table(&explanations).with_color(color).render();
+            options.writer.write_raw(&output::truncate_lines(rendered, options.max_output_lines))?;
+        }
+        _ => {
+            let rendered = output::truncate_lines(output::render(options.output, &explanations)?, options.max_output_lines);
+            let highlighter = Highlighter::for_terminal(&config.output, options.writer.is_tty());
+            options.writer.write_raw(&highlighter.to_ansi(&rendered))?;
+        }
+    }
+
+    // A missing clipboard is not worth failing over once the explanation is printed
+    if clipboard {
+        match clipboard::copy(&output::render(options.output, &explanations)?) {
+            Ok(()) => info!("Copied explanation to the clipboard"),
+            Err(e) => warn!(error = %e, "Could not copy the explanation to the clipboard"),
+        }
+    }
+
+    if let Some(usage) = usage {
+        info!(prompt_tokens = usage.prompt_tokens, completion_tokens = usage.completion_tokens, "Total sample usage");
+        if !options.output.is_json() && !options.quiet {
+            eprintln!(
+                "Usage: {} prompt + {} completion tokens over {} samples",
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                explanations.len()
+            );
+        }
+    }
+
+    if let Some(tracker) = batch_usage.filter(|tracker| tracker.requests() > 0) {
+        let total = tracker.total();
+        info!(
+            prompt_tokens = total.prompt_tokens,
+            completion_tokens = total.completion_tokens,
+            cost = ?tracker.estimated_cost(),
+            "Total batch usage"
+        );
+        if !options.output.is_json() && !options.quiet {
+            eprintln!("Usage: {}", tracker);
+        }
+    }
+
+    if !cancelled.is_empty() {
+        let total = explanations.len() + failures.len() + cancelled.len();
+        eprintln!("Interrupted: explained {} of {} files.", explanations.len(), total);
+        return Err(InternalError::Cancelled {
+            operation: "explain".to_string(),
         }
+        .into());
     }
-    
+
+    if !failures.is_empty() {
+        let total = explanations.len() + failures.len();
+        eprintln!("Explained {} of {} files. Failed:", explanations.len(), total);
+        for failure in &failures {
+            let message = failure.error.user_message();
+            eprintln!("  {}: {}", failure.path.display(), message.lines().next().unwrap_or_default());
+        }
+        return Err(CommandError::PartialFailure {
+            command: "explain".to_string(),
+            failed: failures.len(),
+            total,
+        }
+        .into());
+    }
+
     let duration = start.elapsed();
     tracing::Span::current().record("duration_ms", duration.as_millis());
-    
+
     info!("Explain command completed successfully");
+    Ok(result)
+}
+
+/// Share of the model context limit that `--context` files may use
+const CONTEXT_BUDGET_DIVISOR: usize = 4;
+
+/// Check the options that only make sense with some `--explain-format` layouts
+///
+/// Citations are only asked for in prose, and the other layouts need a file
+/// to lay out. A patch also needs a topic describing the change and an AI
+/// provider to make it, and `--apply` can only write to files given by path.
+fn check_format_args(args: &ExplainArgs, available: bool) -> Result<(), GroundhogError> {
+    let invalid = |message: &str| {
+        Err(CommandError::InvalidArguments {
+            command: "explain".to_string(),
+            message: message.to_string(),
+        }
+        .into())
+    };
+    if args.cite && args.explain_format != FormatArg::Prose {
+        return invalid("--cite needs --explain-format prose");
+    }
+    if args.files.is_empty() {
+        match args.explain_format {
+            FormatArg::Prose => {}
+            FormatArg::Annotated => return invalid("--explain-format annotated needs --file"),
+            FormatArg::Patch => return invalid("--explain-format patch needs --file"),
+        }
+    }
+    if args.explain_format != FormatArg::Patch {
+        return match args.apply {
+            true => invalid("--apply needs --explain-format patch"),
+            false => Ok(()),
+        };
+    }
+    if args.topic.is_none() {
+        return invalid("--explain-format patch needs --topic describing the change");
+    }
+    if args.apply && args.files.iter().any(|path| path == Path::new(STDIN_PATH)) {
+        return invalid("--apply cannot change standard input");
+    }
+    if !available {
+        return Err(ConfigError::MissingKey { key: "ai".to_string() }.into());
+    }
     Ok(())
 }
 
+/// Resolve the sampling parameters for `explain`, with flags taking precedence over the config
+pub fn resolve_params(args: &ExplainArgs, config: &Config) -> Result<AiParams, GroundhogError> {
+    let defaults = config.ai_params();
+    let params = AiParams {
+        temperature: args.temperature.or(defaults.temperature),
+        max_tokens: args.max_tokens.or(defaults.max_tokens),
+        ..defaults
+    };
+    params.validate()?;
+    Ok(params)
+}
+
+/// Resolve whether `explain` streams, with `--stream`/`--no-stream` taking precedence over `output.stream`
+///
+/// Without either, `default` decides, which is whether stdout is a terminal.
+pub fn resolve_stream(args: &ExplainArgs, config: &Config, default: bool) -> bool {
+    if args.stream {
+        true
+    } else if args.no_stream {
+        false
+    } else {
+        config.output.stream.unwrap_or(default)
+    }
+}
+
+/// Resolve the answer language for `explain`, with `--lang` taking precedence over the config
+///
+/// `--raw` requests get no language, so no language instruction is sent.
+pub fn resolve_language(args: &ExplainArgs, config: &Config) -> Option<String> {
+    if args.raw {
+        return None;
+    }
+    args.lang.clone().or_else(|| config.output.resolve_language())
+}
+
+/// Resolve the explanation depth, with `--depth` taking precedence over `commands.explain.depth`
+///
+/// `--raw` requests get no depth, so no depth instruction is sent.
+pub fn resolve_depth(args: &ExplainArgs, config: &Config) -> Option<ExplainDepth> {
+    if args.raw {
+        return None;
+    }
+    args.depth.map(ExplainDepth::from).or_else(|| config.commands.explain.as_ref().and_then(|explain| explain.depth))
+}
+
+/// Requests `explain` was asked for
+///
+/// Each file is its own request, with the topic used as their focus rather
+/// than as an input. Without files the topic, if any, is the only request.
+pub fn requests(args: &ExplainArgs, config: &Config) -> Result<Vec<ExplainRequest>, GroundhogError> {
+    if args.raw {
+        let ignored: Vec<&str> = [
+            ("--template", args.template.is_some()),
+            ("--lang", args.lang.is_some()),
+            ("--depth", args.depth.is_some()),
+            ("--context", !args.context.is_empty()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, given)| given.then_some(flag))
+        .collect();
+        if !ignored.is_empty() {
+            warn!(ignored = %ignored.join(", "), "--raw sends the topic as is, ignoring other prompt options");
+        }
+    }
+    let related = match args.context.as_slice() {
+        [] => None,
+        _ if args.raw => None,
+        patterns => {
+            let paths = context::expand(patterns)?;
+            if paths.is_empty() {
+                warn!(patterns = ?patterns, "No files matched --context");
+            }
+            // Leave most of the model context to the input itself
+            let budget = config.context_limit() / CONTEXT_BUDGET_DIVISOR;
+            Some(context::build(&paths, config.performance.max_file_size_bytes(), budget)?).filter(|text| !text.is_empty())
+        }
+    };
+
+    let lang = resolve_language(args, config);
+    let params = resolve_params(args, config)?;
+    let requests: Vec<ExplainRequest> = if args.files.is_empty() {
+        args.topic.iter().cloned().map(|topic| ExplainRequest::new(ExplainInput::Topic(topic))).collect()
+    } else {
+        args.files
+            .iter()
+            .cloned()
+            .map(|path| {
+                ExplainRequest::new(ExplainInput::from_path(path))
+                    .with_focus(args.topic.clone())
+                    .with_format(args.explain_format.into())
+            })
+            .collect()
+    };
+
+    Ok(requests
+        .into_iter()
+        .map(|request| {
+            request
+                .with_lang(lang.clone())
+                .with_params(params.clone())
+                .with_template(args.template.clone())
+                .with_context(related.clone())
+                .with_raw(args.raw)
+        })
+        .collect())
+}
+
+/// Explain a topic request, applying its template
+pub async fn explain_topic(request: &ExplainRequest, service: &dyn Explainer) -> Result<ExplainResponse, GroundhogError> {
+    if request.topic().is_none() {
+        return Err(InternalError::UnexpectedState {
+            message: format!("'{}' is not a topic", request.input),
+        }
+        .into());
+    }
+    let (completion, usage) = service.explain_with_usage(request.clone()).await?;
+    Ok(ExplainResponse {
+        completion,
+        source: request.input.clone(),
+        usage,
+    })
+}
+
+/// Stream an explanation of a topic request to `on_delta`, falling back to the hello world demo without an AI backend
+pub async fn stream_topic(
+    request: Option<&ExplainRequest>,
+    service: &dyn Explainer,
+    on_delta: &mut (dyn FnMut(&str) + Send),
+) -> Result<Usage, GroundhogError> {
+    match request.and_then(|request| Some((request.topic()?, request))) {
+        Some((topic_str, request)) if service.is_available() => {
+            info!(topic = %topic_str, "Streaming AI explanation");
+            service.explain_streaming(request.clone(), on_delta).await
+        }
+        Some((topic_str, _)) => {
+            let body = format!("hello world - explaining: {}", topic_str);
+            on_delta(&body);
+            Ok(Usage::estimate(topic_str, &body))
+        }
+        None => {
+            on_delta("hello world");
+            Ok(Usage::estimate("", "hello world"))
+        }
+    }
+}
+
+/// Request `count` independent explanations of a topic request, bounded by `performance.threads`
+///
+/// Samples are returned in request order and the first failure fails the
+/// whole set. Usage reported for each sample is summed.
+#[instrument(name = "explain.sample_topic", fields(count, threads = cfg.threads), skip(request, service, cfg))]
+pub async fn sample_topic(
+    request: &ExplainRequest,
+    count: usize,
+    service: Arc<dyn Explainer>,
+    cfg: &PerformanceConfig,
+) -> Result<SampleSet, GroundhogError> {
+    let semaphore = Arc::new(Semaphore::new(cfg.threads.max(1)));
+
+    let handles: Vec<_> = (0..count)
+        .map(|_| {
+            let semaphore = Arc::clone(&semaphore);
+            let service = Arc::clone(&service);
+            let request = request.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.map_err(|e| InternalError::UnexpectedState {
+                    message: format!("Sample semaphore closed: {}", e),
+                })?;
+                explain_topic(&request, service.as_ref()).await
+            })
+        })
+        .collect();
+
+    let mut samples = SampleSet::default();
+    let mut handles = handles.into_iter();
+    while let Some(handle) = handles.next() {
+        let response = handle
+            .await
+            .map_err(|e| {
+                GroundhogError::from(InternalError::UnexpectedState {
+                    message: format!("Sample task failed: {}", e),
+                })
+            })
+            .and_then(|result| result);
+        match response {
+            Ok(ExplainResponse { completion, usage, .. }) => {
+                samples.usage += usage;
+                samples.texts.push(completion.text);
+            }
+            Err(e) => {
+                handles.for_each(|remaining| remaining.abort());
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Explain the files of several requests concurrently, bounded by `performance.threads`
+///
+/// Each file is explained with its request's related files; topic requests
+/// are skipped. Results are returned in input order regardless of
+/// completion order. The first failure aborts the remaining files unless
+/// `keep_going` is set, in which case failures are collected into the
+/// summary. Once `cancel` is triggered, files that have not finished are
+/// reported as cancelled.
+#[instrument(
+    name = "explain.execute_many",
+    fields(files = requests.len(), threads = cfg.threads),
+    skip(requests, service, cfg, progress, batch)
+)]
+pub async fn execute_many(
+    requests: Vec<ExplainRequest>,
+    service: Arc<dyn Explainer>,
+    cfg: &PerformanceConfig,
+    progress: &ProgressReporter,
+    batch: &BatchOptions,
+) -> Result<BatchSummary, GroundhogError> {
+    let semaphore = Arc::new(Semaphore::new(cfg.threads.max(1)));
+    let max_bytes = cfg.max_file_size_bytes();
+
+    let handles: Vec<_> = requests
+        .into_iter()
+        .filter_map(|request| Some((request.input.path()?, request)))
+        .map(|(path, request)| {
+            let semaphore = Arc::clone(&semaphore);
+            let service = Arc::clone(&service);
+            let cancel = batch.cancel.clone();
+            let progress = progress.clone();
+            let handle = tokio::spawn(async move {
+                let work = async {
+                    let _permit = semaphore.acquire_owned().await.map_err(|e| InternalError::UnexpectedState {
+                        message: format!("Explain semaphore closed: {}", e),
+                    })?;
+                    explain_file(&request, service.as_ref(), max_bytes).await
+                };
+                tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => None,
+                    result = work => {
+                        progress.advance();
+                        Some(result)
+                    }
+                }
+            });
+            (path, handle)
+        })
+        .collect();
+
+    let mut summary = BatchSummary::default();
+    let mut handles = handles.into_iter();
+    while let Some((path, handle)) = handles.next() {
+        let outcome = handle.await.map_err(|e| {
+            GroundhogError::from(InternalError::UnexpectedState {
+                message: format!("Explain task failed: {}", e),
+            })
+        });
+        let result = match outcome {
+            Ok(Some(result)) => result,
+            Ok(None) => {
+                summary.cancelled.push(path);
+                continue;
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(explanation) => summary.succeeded.push(explanation),
+            Err(error) if batch.keep_going => summary.failed.push(FileFailure { path, error }),
+            Err(e) => {
+                handles.for_each(|(_, remaining)| remaining.abort());
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Read and explain the file or stdin input of `request`
+///
+/// Related files are only included when the file fits in a single prose prompt.
+#[instrument(name = "explain.file", fields(path = %request.input, format = ?request.format), skip(request, service))]
+async fn explain_file(
+    request: &ExplainRequest,
+    service: &dyn Explainer,
+    max_bytes: u64,
+) -> Result<FileExplanation, GroundhogError> {
+    let path = request.input.path().ok_or_else(|| InternalError::UnexpectedState {
+        message: format!("'{}' is not a file", request.input),
+    })?;
+    let content = read_input_file(&path, max_bytes)?;
+    let estimated_tokens = estimate_tokens(&content);
+    debug!(size_bytes = content.len(), estimated_tokens, "Read input file");
+
+    let mut usage = UsageTracker::new();
+    let related = request.context.as_deref();
+    let explanation = match request.format {
+        ExplainFormat::Annotated => annotate_file(request, &content, service, &mut usage).await?,
+        ExplainFormat::Patch => patch_file(request, &path, content, service, &mut usage).await?,
+        ExplainFormat::Prose if estimated_tokens + related.map_or(0, estimate_tokens) > service.context_limit() => {
+            explain_chunks(request, &content, service, &mut usage).await?
+        }
+        ExplainFormat::Prose => {
+            let request = request.clone().with_text(InputText::whole(content));
+            explain_tracked(service, request, &mut usage).await?
+        }
+    };
+
+    Ok(FileExplanation {
+        path,
+        explanation,
+        usage,
+    })
+}
+
+/// Explain `request`, recording the usage the service reports in `usage`
+async fn explain_tracked(
+    service: &dyn Explainer,
+    request: ExplainRequest,
+    usage: &mut UsageTracker,
+) -> Result<String, GroundhogError> {
+    let (completion, prompt_usage) = service.explain_with_usage(request).await?;
+    usage.record(prompt_usage);
+    Ok(completion.text)
+}
+
+/// Annotate a file line by line and interleave the notes with its lines
+///
+/// Files too large for the model context are numbered and sent in chunks
+/// of whole lines, so each line is annotated in exactly one chunk.
+async fn annotate_file(
+    request: &ExplainRequest,
+    content: &str,
+    service: &dyn Explainer,
+    usage: &mut UsageTracker,
+) -> Result<String, GroundhogError> {
+    let ranges = if estimate_tokens(content) > service.context_limit() {
+        chunk::split_lines(content, service.context_limit() * 3 / 4)
+    } else {
+        std::iter::once(0..content.len()).collect()
+    };
+
+    let mut notes = LineNotes::new();
+    for range in ranges {
+        let (first_line, _) = chunk::line_span(content, &range);
+        let text = InputText {
+            text: content[range].to_string(),
+            first_line,
+        };
+        let reply = explain_tracked(service, request.clone().with_text(text), usage).await?;
+        notes.extend(annotate::parse_notes(&reply));
+    }
+    debug!(notes = notes.len(), "Parsed line annotations");
+
+    Ok(annotate::interleave(content, &notes))
+}
+
+/// Ask for a diff of the file at `path` making the change the request's focus describes, checking that it applies
+///
+/// The diff is returned with normalized headers and hunk line counts.
+async fn patch_file(
+    request: &ExplainRequest,
+    path: &Path,
+    content: String,
+    service: &dyn Explainer,
+    usage: &mut UsageTracker,
+) -> Result<String, GroundhogError> {
+    if request.focus.is_none() {
+        return Err(CommandError::InvalidArguments {
+            command: "explain".to_string(),
+            message: "--explain-format patch needs --topic describing the change".to_string(),
+        }
+        .into());
+    }
+    let request = request.clone().with_text(InputText::whole(content.clone()));
+    let reply = explain_tracked(service, request, usage).await?;
+    let patch = patch::parse(&reply)?;
+    patch::apply(path, &content, &patch)?;
+    debug!(hunks = patch.hunks.len(), "Parsed patch that applies cleanly");
+
+    Ok(patch.to_unified(&path.display().to_string()))
+}
+
+/// Apply a diff from [`patch_file`] to `path`, first copying the file to `<path>.orig`
+///
+/// The file is read again, so a change made since the diff was checked
+/// fails the patch before anything is written. An existing backup is never
+/// replaced; the patch fails instead. Returns the backup path.
+pub fn apply_patch_file(path: &Path, diff: &str, max_bytes: u64) -> Result<PathBuf, GroundhogError> {
+    let content = fs::read_text(path, max_bytes)?;
+    let patched = patch::apply(path, &content, &patch::parse(diff)?)?;
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".orig");
+    let backup = PathBuf::from(backup);
+    let mut backup_file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&backup)
+        .map_err(|e| fs::io_error(&backup, e))?;
+    backup_file.write_all(content.as_bytes()).map_err(|e| fs::io_error(&backup, e))?;
+    std::fs::write(path, patched).map_err(|e| fs::io_error(path, e))?;
+    Ok(backup)
+}
+
+/// Explain a file too large for the model context in overlapping chunks
+///
+/// Each chunk leaves room in the context for the prompt around it, and the
+/// per-chunk explanations are labeled with the lines they cover.
+async fn explain_chunks(
+    request: &ExplainRequest,
+    content: &str,
+    service: &dyn Explainer,
+    usage: &mut UsageTracker,
+) -> Result<String, GroundhogError> {
+    let context_limit = service.context_limit();
+    let max_tokens = context_limit * 3 / 4;
+    let overlap = context_limit / 16;
+    let chunks = chunk::split(content, max_tokens, overlap);
+
+    warn!(
+        context_limit,
+        chunks = chunks.len(),
+        "Input file exceeds the model context limit, explaining in chunks"
+    );
+
+    let mut sections = Vec::with_capacity(chunks.len());
+    for (index, range) in chunks.iter().enumerate() {
+        let (first_line, last_line) = chunk::line_span(content, range);
+        let text = InputText {
+            text: content[range.clone()].to_string(),
+            first_line,
+        };
+        let request = request.clone().with_context(None).with_text(text);
+        let explanation = explain_tracked(service, request, usage).await?;
+        sections.push(format!(
+            "Part {}/{} (lines {}-{}):\n{}",
+            index + 1,
+            chunks.len(),
+            first_line,
+            last_line,
+            explanation
+        ));
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+/// Read an input file of at most `max_bytes` as text
+///
+/// The path `-` reads standard input instead.
+fn read_input_file(path: &Path, max_bytes: u64) -> Result<String, GroundhogError> {
+    if path == Path::new(STDIN_PATH) {
+        return fs::read_text_from(std::io::stdin().lock(), path, max_bytes);
+    }
+    fs::read_text(path, max_bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::cli::writer::MemoryOutput;
+    use crate::core::retry::RetryPolicy;
+    use crate::core::services::testing::RecordingProvider;
+    use crate::core::services::{AIService, Completion, CompletionRequest, MockProvider, Provider};
+    use crate::infrastructure::error::{FileSystemError, NetworkError, ParseError};
+    use crate::infrastructure::log_buffer::LogBuffer;
     use crate::infrastructure::logging::init_test_tracing;
 
-    #[test]
-    fn test_explain_command_no_topic() {
+    /// Provider tracking how many requests are in flight at once
+    #[derive(Default)]
+    struct ConcurrencyProvider {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for ConcurrencyProvider {
+        async fn complete(&self, request: CompletionRequest) -> Result<Completion, GroundhogError> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+
+            // Earlier files take longer so completions arrive out of order
+            let delay = if request.prompt.contains("file0") { 40 } else { 5 };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            let name = request.prompt.lines().next().unwrap_or_default().to_string();
+            Ok(Completion { text: name })
+        }
+    }
+
+    /// Provider failing for prompts about one file
+    struct FailingProvider(&'static str);
+
+    #[async_trait]
+    impl Provider for FailingProvider {
+        async fn complete(&self, request: CompletionRequest) -> Result<Completion, GroundhogError> {
+            if request.prompt.contains(self.0) {
+                return Err(NetworkError::Http { status: 400, message: "rejected".to_string(), source: None }.into());
+            }
+            Ok(Completion { text: "explained".to_string() })
+        }
+    }
+
+    fn closures() -> ExplainRequest {
+        ExplainRequest::new(ExplainInput::Topic("closures".to_string()))
+    }
+
+    fn write_files(dir: &TempDir, count: usize) -> Vec<PathBuf> {
+        (0..count)
+            .map(|i| {
+                let path = dir.path().join(format!("file{}.rs", i));
+                std::fs::write(&path, format!("fn f{}() {{}}", i)).unwrap();
+                path
+            })
+            .collect()
+    }
+
+    fn file_requests(files: &[PathBuf]) -> Vec<ExplainRequest> {
+        files.iter().cloned().map(|path| ExplainRequest::new(ExplainInput::File(path))).collect()
+    }
+
+    fn file_request(path: &Path, format: ExplainFormat, focus: Option<&str>) -> ExplainRequest {
+        ExplainRequest::new(ExplainInput::File(path.to_path_buf()))
+            .with_format(format)
+            .with_focus(focus.map(str::to_string))
+    }
+
+    #[tokio::test]
+    async fn test_explain_command_no_topic() {
         init_test_tracing();
-        let result = execute(None);
+        let result = execute(ExplainArgs::default(), Arc::new(AIService::new()), &Config::default(), &GlobalOptions::default()).await;
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_explain_command_with_topic() {
+    #[tokio::test]
+    async fn test_explain_command_with_topic() {
         init_test_tracing();
-        let result = execute(Some("rust".to_string()));
+        let args = ExplainArgs {
+            topic: Some("rust".to_string()),
+            ..ExplainArgs::default()
+        };
+        let result = execute(args, Arc::new(AIService::new()), &Config::default(), &GlobalOptions::default()).await;
         assert!(result.is_ok());
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_explain_command_uses_available_service() {
+        init_test_tracing();
+        let provider = Arc::new(RecordingProvider::new("explanation"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_language(Some("de".to_string()));
+        let args = ExplainArgs {
+            topic: Some("rust".to_string()),
+            ..ExplainArgs::default()
+        };
+
+        let result = execute(args, Arc::new(service), &Config::default(), &GlobalOptions::default()).await;
+        assert!(result.is_ok());
+        assert!(provider.requests()[0].prompt.contains("'de'"));
+    }
+
+    #[tokio::test]
+    async fn test_explain_command_writes_to_output() {
+        init_test_tracing();
+        let output = MemoryOutput::new();
+        let options = GlobalOptions {
+            writer: Arc::new(output.clone()),
+            ..GlobalOptions::default()
+        };
+        let args = ExplainArgs {
+            topic: Some("rust".to_string()),
+            ..ExplainArgs::default()
+        };
+
+        execute(args, Arc::new(AIService::new()), &Config::default(), &options).await.unwrap();
+
+        let written = output.contents();
+        assert!(written.contains("hello world - explaining: rust"));
+        // Memory output is not a terminal, so nothing is highlighted
+        assert!(!written.contains('\x1b'));
+    }
+
+    #[tokio::test]
+    async fn test_clipboard_warns_when_unavailable() {
+        // Only the fallback is tested, so a real clipboard is left untouched
+        if clipboard::is_available() {
+            return;
+        }
+        let buffer = LogBuffer::new(10);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let output = MemoryOutput::new();
+        let options = GlobalOptions {
+            writer: Arc::new(output.clone()),
+            ..GlobalOptions::default()
+        };
+        let args = ExplainArgs {
+            topic: Some("rust".to_string()),
+            clipboard: true,
+            ..ExplainArgs::default()
+        };
+
+        execute(args, Arc::new(AIService::new()), &Config::default(), &options).await.unwrap();
+
+        assert!(output.contents().contains("hello world - explaining: rust"));
+        let logs = buffer.recent(10);
+        assert!(logs.iter().any(|line| line.contains("WARN") && line.contains("Could not copy the explanation to the clipboard")));
+    }
+
+    /// Explainer with a canned reply that records its requests
+    #[derive(Default)]
+    struct CannedExplainer {
+        requests: std::sync::Mutex<Vec<ExplainRequest>>,
+    }
+
+    #[async_trait]
+    impl Explainer for CannedExplainer {
+        async fn explain(&self, request: ExplainRequest) -> Result<Completion, GroundhogError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(Completion {
+                text: "canned explanation".to_string(),
+            })
+        }
+
+        fn language(&self) -> Option<&str> {
+            Some("en")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_explain_command_uses_custom_explainer() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "pub fn f() {}\n").unwrap();
+
+        let explainer = Arc::new(CannedExplainer::default());
+        let args = ExplainArgs {
+            topic: Some("visibility".to_string()),
+            files: vec![path.clone()],
+            lang: Some("pt".to_string()),
+            ..ExplainArgs::default()
+        };
+        let options = GlobalOptions {
+            output: OutputFormat::Json,
+            ..GlobalOptions::default()
+        };
+
+        let result = execute(args, explainer.clone(), &Config::default(), &options).await.unwrap();
+
+        let data = result.data.unwrap();
+        assert_eq!(data[0]["body"], "canned explanation");
+        // The request's language wins over the explainer's own
+        assert_eq!(data[0]["metadata"]["Language"], "pt");
+        assert_eq!(
+            *explainer.requests.lock().unwrap(),
+            vec![
+                ExplainRequest::new(ExplainInput::File(path))
+                    .with_lang(Some("pt".to_string()))
+                    .with_params(AiParams::default())
+                    .with_focus(Some("visibility".to_string()))
+                    .with_text(InputText::whole("pub fn f() {}\n".to_string()))
+            ]
+        );
+    }
+
+    /// Explainer sending its reply in two deltas and recording whether it streamed
+    #[derive(Default)]
+    struct ChunkedExplainer {
+        streamed: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Explainer for ChunkedExplainer {
+        async fn explain(&self, _request: ExplainRequest) -> Result<Completion, GroundhogError> {
+            Ok(Completion {
+                text: "whole reply".to_string(),
+            })
+        }
+
+        async fn explain_streaming(
+            &self,
+            request: ExplainRequest,
+            on_delta: &mut (dyn for<'a> FnMut(&'a str) + Send),
+        ) -> Result<Usage, GroundhogError> {
+            self.streamed.store(true, Ordering::SeqCst);
+            on_delta("first ");
+            on_delta("second");
+            Ok(Usage::estimate(request.subject(), "first second"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_terminal_output_streams_and_piped_output_waits() {
+        for (tty, streamed, expected) in [(true, true, "first second\n"), (false, false, "whole reply\n")] {
+            let explainer = Arc::new(ChunkedExplainer::default());
+            let output = MemoryOutput::new().with_tty(tty);
+            let options = GlobalOptions {
+                silent: true,
+                writer: Arc::new(output.clone()),
+                ..GlobalOptions::default()
+            };
+            let args = ExplainArgs {
+                topic: Some("rust".to_string()),
+                ..ExplainArgs::default()
+            };
+
+            execute(args, explainer.clone(), &Config::default(), &options).await.unwrap();
+
+            assert_eq!(explainer.streamed.load(Ordering::SeqCst), streamed, "tty: {}", tty);
+            assert_eq!(output.contents(), expected);
+        }
+    }
+
+    #[test]
+    fn test_stream_flags_override_config_and_terminal() {
+        let mut config = Config::default();
+        let args = |stream, no_stream| ExplainArgs { stream, no_stream, ..ExplainArgs::default() };
+
+        assert!(resolve_stream(&args(false, false), &config, true));
+        assert!(!resolve_stream(&args(false, false), &config, false));
+
+        config.output.stream = Some(false);
+        assert!(!resolve_stream(&args(false, false), &config, true));
+        assert!(resolve_stream(&args(true, false), &config, false));
+
+        config.output.stream = Some(true);
+        assert!(!resolve_stream(&args(false, true), &config, true));
+    }
+
+    #[test]
+    fn test_read_input_file_enforces_size_limit() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("input.rs");
+        std::fs::write(&path, "0123456789").unwrap();
+
+        assert_eq!(read_input_file(&path, 10).unwrap(), "0123456789");
+
+        match read_input_file(&path, 9) {
+            Err(GroundhogError::FileSystem(FileSystemError::TooLarge { size, limit, .. })) => {
+                assert_eq!(size, 10);
+                assert_eq!(limit, 9);
+            }
+            other => panic!("Expected FileSystemError::TooLarge, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_many_respects_concurrency_cap() {
+        let dir = TempDir::new().unwrap();
+        let files = write_files(&dir, 8);
+        let provider = Arc::new(ConcurrencyProvider::default());
+        let service = Arc::new(AIService::new().with_provider(provider.clone()));
+        let cfg = PerformanceConfig {
+            threads: 2,
+            ..PerformanceConfig::default()
+        };
+
+        let progress = ProgressReporter::hidden(8);
+        let summary = execute_many(file_requests(&files), service, &cfg, &progress, &BatchOptions::default()).await.unwrap();
+
+        assert_eq!(summary.succeeded.len(), 8);
+        assert_eq!(progress.done(), 8);
+        assert!(summary.succeeded.iter().all(|result| result.usage.requests() == 1 && result.usage.total_tokens() > 0));
+        assert!(provider.max_in_flight.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_many_preserves_input_order() {
+        let dir = TempDir::new().unwrap();
+        let files = write_files(&dir, 4);
+        let provider = Arc::new(ConcurrencyProvider::default());
+        let service = Arc::new(AIService::new().with_provider(provider));
+
+        let progress = ProgressReporter::hidden(4);
+        let results = execute_many(file_requests(&files), service, &PerformanceConfig::default(), &progress, &BatchOptions::default())
+            .await
+            .unwrap()
+            .succeeded;
+
+        let paths: Vec<_> = results.iter().map(|r| r.path.clone()).collect();
+        assert_eq!(paths, files);
+        for (i, result) in results.iter().enumerate() {
+            assert!(result.explanation.contains(&format!("file{}.rs", i)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_file_is_explained_in_chunks() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("big.rs");
+        let content: String = (0..20).map(|i| format!("let v{} = {};\n", i, i)).collect();
+        std::fs::write(&path, &content).unwrap();
+
+        let provider = Arc::new(RecordingProvider::new("chunk explained"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_context_limit(40);
+
+        let result = explain_file(&file_request(&path, ExplainFormat::Prose, None), &service, u64::MAX).await.unwrap();
+
+        let requests = provider.requests();
+        assert!(requests.len() > 1);
+        assert!(result.explanation.starts_with("Part 1/"));
+        assert!(result.explanation.contains(&format!("Part {}/{}", requests.len(), requests.len())));
+        assert!(requests[0].prompt.contains("let v0 = 0;"));
+        assert!(!requests[0].prompt.contains("let v19 = 19;"));
+        assert!(requests.last().unwrap().prompt.contains("let v19 = 19;"));
+    }
+
+    #[tokio::test]
+    async fn test_annotated_file_interleaves_notes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("main.rs");
+        std::fs::write(&path, "fn main() {\n    run();\n}\n").unwrap();
+
+        let provider = Arc::new(RecordingProvider::new("2: Starts the app"));
+        let service = AIService::new().with_provider(provider.clone());
+
+        let result = explain_file(&file_request(&path, ExplainFormat::Annotated, None), &service, u64::MAX).await.unwrap();
+
+        assert!(provider.requests()[0].prompt.contains("1: fn main() {\n2:     run();\n"));
+        assert_eq!(result.explanation, "1 | fn main() {\n2 |     run();\n  + Starts the app\n3 | }\n");
+    }
+
+    #[tokio::test]
+    async fn test_patch_file_checks_diff_and_applies_it_to_a_copy() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("main.rs");
+        std::fs::write(&path, "fn main() {\n    run();\n}\n").unwrap();
+
+        let reply = "```diff\n@@ -1,3 +1,5 @@\n fn main() {\n-    run();\n+    if let Err(e) = run() {\n+        eprintln!(\"{}\", e);\n+    }\n }\n```";
+        let provider = Arc::new(RecordingProvider::new(reply));
+        let service = AIService::new().with_provider(provider.clone());
+
+        let result = explain_file(&file_request(&path, ExplainFormat::Patch, Some("add error handling")), &service, u64::MAX)
+            .await
+            .unwrap();
+
+        assert!(provider.requests()[0].prompt.contains("to: add error handling"));
+        assert!(result.explanation.contains("@@ -1,3 +1,5 @@\n fn main() {\n-    run();\n"));
+        // Only printed until applied
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {\n    run();\n}\n");
+
+        let backup = apply_patch_file(&path, &result.explanation, u64::MAX).unwrap();
+        assert_eq!(backup, dir.path().join("main.rs.orig"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "fn main() {\n    run();\n}\n");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "fn main() {\n    if let Err(e) = run() {\n        eprintln!(\"{}\", e);\n    }\n}\n"
+        );
+
+        // Patching again keeps the first backup rather than overwriting it
+        std::fs::write(&path, "fn main() {\n    run();\n}\n").unwrap();
+        match apply_patch_file(&path, &result.explanation, u64::MAX) {
+            Err(GroundhogError::FileSystem(FileSystemError::AlreadyExists { path })) => assert_eq!(path, backup),
+            other => panic!("Expected FileSystemError::AlreadyExists, got {:?}", other),
+        }
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "fn main() {\n    run();\n}\n");
+    }
+
+    #[tokio::test]
+    async fn test_patch_that_does_not_apply_fails_the_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("main.rs");
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+
+        let provider = Arc::new(RecordingProvider::new("@@ -1 +1 @@\n-fn start() {}\n+fn start() -> Result<(), Error> {}\n"));
+        let service = AIService::new().with_provider(provider);
+
+        let result = explain_file(&file_request(&path, ExplainFormat::Patch, Some("return a result")), &service, u64::MAX).await;
+        match result {
+            Err(GroundhogError::Parse(ParseError::PatchConflict { hunk, .. })) => assert_eq!(hunk, 1),
+            other => panic!("Expected ParseError::PatchConflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_patch_args_need_a_topic_and_patch_format() {
+        let args = ExplainArgs {
+            files: vec![PathBuf::from("main.rs")],
+            apply: true,
+            ..ExplainArgs::default()
+        };
+        assert!(matches!(check_format_args(&args, true), Err(GroundhogError::Command(CommandError::InvalidArguments { .. }))));
+
+        let args = ExplainArgs { explain_format: FormatArg::Patch, ..args };
+        assert!(matches!(check_format_args(&args, true), Err(GroundhogError::Command(CommandError::InvalidArguments { .. }))));
+
+        let args = ExplainArgs { topic: Some("add logging".to_string()), ..args };
+        assert!(check_format_args(&args, true).is_ok());
+        assert!(matches!(check_format_args(&args, false), Err(GroundhogError::Config(ConfigError::MissingKey { .. }))));
+
+        let args = ExplainArgs { cite: true, apply: false, ..args };
+        assert!(matches!(check_format_args(&args, true), Err(GroundhogError::Command(CommandError::InvalidArguments { .. }))));
+    }
+
+    #[test]
+    fn test_file_formats_need_a_file() {
+        let args = ExplainArgs {
+            topic: Some("add error handling".to_string()),
+            explain_format: FormatArg::Patch,
+            ..ExplainArgs::default()
+        };
+        match check_format_args(&args, true) {
+            Err(GroundhogError::Command(CommandError::InvalidArguments { message, .. })) => {
+                assert_eq!(message, "--explain-format patch needs --file");
+            }
+            other => panic!("Expected CommandError::InvalidArguments, got {:?}", other),
+        }
+
+        let args = ExplainArgs { explain_format: FormatArg::Annotated, ..args };
+        match check_format_args(&args, true) {
+            Err(GroundhogError::Command(CommandError::InvalidArguments { message, .. })) => {
+                assert_eq!(message, "--explain-format annotated needs --file");
+            }
+            other => panic!("Expected CommandError::InvalidArguments, got {:?}", other),
+        }
+
+        let args = ExplainArgs { explain_format: FormatArg::Prose, ..args };
+        assert!(check_format_args(&args, true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_many_missing_file() {
+        let provider = Arc::new(RecordingProvider::new("ok"));
+        let service = Arc::new(AIService::new().with_provider(provider));
+        let files = vec![PathBuf::from("/nonexistent/file.rs")];
+
+        let progress = ProgressReporter::hidden(1);
+        let result = execute_many(file_requests(&files), service, &PerformanceConfig::default(), &progress, &BatchOptions::default()).await;
+        assert!(matches!(
+            result,
+            Err(GroundhogError::FileSystem(FileSystemError::NotFound { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_many_stops_at_first_error() {
+        let dir = TempDir::new().unwrap();
+        let files = write_files(&dir, 3);
+        let service = Arc::new(AIService::new().with_provider(Arc::new(FailingProvider("file1.rs"))));
+
+        let progress = ProgressReporter::hidden(3);
+        let result = execute_many(file_requests(&files), service, &PerformanceConfig::default(), &progress, &BatchOptions::default()).await;
+
+        assert!(matches!(result, Err(GroundhogError::Network(NetworkError::Http { status: 400, .. }))));
+    }
+
+    #[tokio::test]
+    async fn test_execute_many_keep_going_collects_failures() {
+        let dir = TempDir::new().unwrap();
+        let files = write_files(&dir, 3);
+        let service = Arc::new(AIService::new().with_provider(Arc::new(FailingProvider("file1.rs"))));
+
+        let progress = ProgressReporter::hidden(3);
+        let keep_going = BatchOptions {
+            keep_going: true,
+            ..BatchOptions::default()
+        };
+        let summary = execute_many(file_requests(&files), service, &PerformanceConfig::default(), &progress, &keep_going)
+            .await
+            .unwrap();
+
+        let succeeded: Vec<_> = summary.succeeded.iter().map(|r| r.path.clone()).collect();
+        assert_eq!(succeeded, vec![files[0].clone(), files[2].clone()]);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].path, files[1]);
+        assert_eq!(summary.total(), 3);
+    }
+
+    /// Provider that cancels the batch while answering its first request and never answers later ones
+    struct CancellingProvider {
+        cancel: CancellationToken,
+        answered: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Provider for CancellingProvider {
+        async fn complete(&self, _request: CompletionRequest) -> Result<Completion, GroundhogError> {
+            if self.answered.swap(true, Ordering::SeqCst) {
+                return std::future::pending().await;
+            }
+            self.cancel.cancel();
+            Ok(Completion {
+                text: "done before cancel".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_many_cancel_returns_completed_files() {
+        let dir = TempDir::new().unwrap();
+        let files = write_files(&dir, 3);
+        let batch = BatchOptions::default();
+        let provider = CancellingProvider {
+            cancel: batch.cancel.clone(),
+            answered: Default::default(),
+        };
+        let service = Arc::new(AIService::new().with_provider(Arc::new(provider)));
+        let cfg = PerformanceConfig {
+            threads: 1,
+            ..PerformanceConfig::default()
+        };
+
+        let progress = ProgressReporter::hidden(3);
+        let summary = tokio::time::timeout(
+            Duration::from_secs(5),
+            execute_many(file_requests(&files), service, &cfg, &progress, &batch),
+        )
+        .await
+        .expect("cancelled batch should not hang")
+        .unwrap();
+
+        assert_eq!(summary.succeeded.len(), 1);
+        assert_eq!(summary.succeeded[0].explanation, "done before cancel");
+        assert!(summary.failed.is_empty());
+        assert_eq!(summary.cancelled.len(), 2);
+        assert_eq!(summary.total(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sample_topic_returns_count_samples_and_sums_usage() {
+        let provider = Arc::new(MockProvider::new("a closure captures its environment").with_reply("short"));
+        let service = Arc::new(AIService::new().with_provider(provider.clone()));
+
+        let samples = sample_topic(&closures(), 3, service, &PerformanceConfig::default()).await.unwrap();
+
+        assert_eq!(samples.texts.len(), 3);
+        assert_eq!(provider.requests().len(), 3);
+        let mut expected = Usage::default();
+        for text in &samples.texts {
+            expected += Usage::estimate("closures", text);
+        }
+        assert_eq!(samples.usage, expected);
+        assert!(samples.texts.contains(&"short".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_count_without_provider_numbers_placeholders() {
+        let args = ExplainArgs {
+            topic: Some("ownership".to_string()),
+            count: std::num::NonZeroUsize::new(3),
+            ..ExplainArgs::default()
+        };
+        let options = GlobalOptions {
+            output: OutputFormat::Json,
+            ..GlobalOptions::default()
+        };
+
+        let result = execute(args, Arc::new(AIService::new()), &Config::default(), &options).await.unwrap();
+
+        let data = result.data.unwrap();
+        let titles: Vec<&str> = data.as_array().unwrap().iter().map(|output| output["title"].as_str().unwrap()).collect();
+        assert_eq!(titles, vec!["ownership (1/3)", "ownership (2/3)", "ownership (3/3)"]);
+        assert_eq!(data[2]["body"], "hello world - explaining: ownership");
+    }
+
+    #[tokio::test]
+    async fn test_sample_topic_respects_concurrency_cap() {
+        let provider = Arc::new(ConcurrencyProvider::default());
+        let service = Arc::new(AIService::new().with_provider(provider.clone()));
+        let cfg = PerformanceConfig {
+            threads: 2,
+            ..PerformanceConfig::default()
+        };
+
+        let samples = sample_topic(&closures(), 6, service, &cfg).await.unwrap();
+
+        assert_eq!(samples.texts.len(), 6);
+        assert!(provider.max_in_flight.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_sample_topic_fails_on_first_error() {
+        let provider = Arc::new(MockProvider::new("ok").with_failure(FileSystemError::Io(std::io::Error::other("boom"))));
+        let service = Arc::new(AIService::new().with_provider(provider).with_retry_policy(RetryPolicy::none()));
+
+        assert!(sample_topic(&closures(), 2, service, &PerformanceConfig::default()).await.is_err());
+    }
+
+    #[test]
+    fn test_stdin_input_rejects_oversized_input() {
+        let path = Path::new(STDIN_PATH);
+        assert_eq!(fs::read_text_from("fn main() {}".as_bytes(), path, 64).unwrap(), "fn main() {}");
+
+        match fs::read_text_from("0123456789".as_bytes(), path, 4) {
+            Err(GroundhogError::FileSystem(FileSystemError::TooLarge { size, limit, .. })) => {
+                assert_eq!(size, 5);
+                assert_eq!(limit, 4);
+            }
+            other => panic!("Expected FileSystemError::TooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_file_is_rejected_as_invalid_format() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("image.png");
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n\xff\xfe").unwrap();
+
+        match read_input_file(&path, 1024) {
+            Err(GroundhogError::FileSystem(FileSystemError::InvalidFormat { path: reported, expected, .. })) => {
+                assert_eq!(reported, path);
+                assert_eq!(expected, "UTF-8 text");
+            }
+            other => panic!("Expected FileSystemError::InvalidFormat, got {:?}", other),
+        }
+        assert!(matches!(
+            fs::read_text_from(&b"ok \xc3"[..], Path::new(STDIN_PATH), 64),
+            Err(GroundhogError::FileSystem(FileSystemError::InvalidFormat { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_requests_resolve_inputs_and_settings() {
+        let args = ExplainArgs {
+            topic: Some("errors".to_string()),
+            files: vec![PathBuf::from("a.rs"), PathBuf::from("-")],
+            template: Some("Explain {input}".to_string()),
+            ..ExplainArgs::default()
+        };
+
+        let resolved = requests(&args, &Config::default()).unwrap();
+        let inputs: Vec<_> = resolved.iter().map(|request| request.input.clone()).collect();
+        assert_eq!(inputs, vec![ExplainInput::File(PathBuf::from("a.rs")), ExplainInput::Stdin]);
+        assert!(resolved.iter().all(|request| request.template.as_deref() == Some("Explain {input}")));
+
+        let topic_only = ExplainArgs { files: Vec::new(), ..args };
+        assert_eq!(requests(&topic_only, &Config::default()).unwrap()[0].input, ExplainInput::Topic("errors".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_explain_topic_applies_template() {
+        let provider = Arc::new(MockProvider::new("answer"));
+        let service = AIService::new().with_provider(provider.clone());
+        let request = closures().with_template(Some("Explain {input} briefly".to_string()));
+
+        let response = explain_topic(&request, &service).await.unwrap();
+
+        assert_eq!(response.completion.text, "answer");
+        assert_eq!(response.source, ExplainInput::Topic("closures".to_string()));
+        assert_eq!(provider.requests()[0].prompt, "Explain closures briefly");
+    }
+
+    #[tokio::test]
+    async fn test_prompt_framing_surrounds_templated_content() {
+        let provider = Arc::new(MockProvider::new("answer"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_language(Some("de".to_string()))
+            .with_prompt_framing(Some("Be concise.".to_string()), Some("Answer with citations.".to_string()));
+        let request = closures().with_template(Some("Explain {input} briefly".to_string()));
+
+        explain_topic(&request, &service).await.unwrap();
+
+        assert_eq!(
+            provider.requests()[0].prompt,
+            "Respond in the language with code 'de'.\n\nBe concise.\n\nExplain closures briefly\n\nAnswer with citations."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lang_and_sampling_flags_reach_the_provider() {
+        let args = ExplainArgs {
+            topic: Some("closures".to_string()),
+            lang: Some("it".to_string()),
+            temperature: Some(0.3),
+            max_tokens: Some(200),
+            ..ExplainArgs::default()
+        };
+        let provider = Arc::new(MockProvider::new("ok"));
+        let service = AIService::new().with_provider(provider.clone());
+
+        execute(args, Arc::new(service), &Config::default(), &GlobalOptions::default()).await.unwrap();
+
+        let sent = provider.requests();
+        assert!(sent[0].prompt.starts_with("Respond in the language with code 'it'."));
+        assert_eq!(sent[0].params.temperature, Some(0.3));
+        assert_eq!(sent[0].params.max_tokens, Some(200));
+    }
+
+    #[tokio::test]
+    async fn test_raw_sends_only_the_topic() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("helper.txt"), "shared helper").unwrap();
+        let args = ExplainArgs {
+            topic: Some("  exactly this, please ".to_string()),
+            raw: true,
+            template: Some("Explain {input} briefly".to_string()),
+            lang: Some("fr".to_string()),
+            context: vec![format!("{}/*.txt", dir.path().display())],
+            ..ExplainArgs::default()
+        };
+        let config = Config::default();
+        let provider = Arc::new(MockProvider::new("ok"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_language(resolve_language(&args, &config))
+            .with_prompt_framing(Some("Be concise.".to_string()), Some("Answer with citations.".to_string()));
+
+        execute(args, Arc::new(service), &config, &GlobalOptions::default()).await.unwrap();
+
+        let sent = provider.requests();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].prompt, "  exactly this, please ");
+    }
+
+    #[tokio::test]
+    async fn test_context_files_reach_the_file_prompt() {
+        let dir = TempDir::new().unwrap();
+        let files = write_files(&dir, 1);
+        std::fs::write(dir.path().join("helper.txt"), "shared helper").unwrap();
+        let provider = Arc::new(MockProvider::new("ok"));
+        let service = Arc::new(AIService::new().with_provider(provider.clone()));
+        let args = ExplainArgs {
+            files,
+            context: vec![format!("{}/*.txt", dir.path().display())],
+            ..ExplainArgs::default()
+        };
+
+        execute(args, service, &Config::default(), &GlobalOptions::default()).await.unwrap();
+
+        let prompt = &provider.requests()[0].prompt;
+        assert!(prompt.starts_with("Related files for context:\n\n--- "), "{}", prompt);
+        assert!(prompt.contains("helper.txt ---\nshared helper\n"), "{}", prompt);
+    }
+}
@@ -1,21 +1,190 @@
+pub mod cache;
+pub mod compare;
+pub mod config;
+pub mod doctor;
 pub mod explain;
+pub mod ping;
+pub mod providers;
+pub mod repl;
 pub mod tui;
 
-use crate::cli::Commands;
-use crate::infrastructure::error::GroundhogError;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::cli::{Commands, ExplainArgs};
+use crate::cli::output::{self, OutputFormat};
+use crate::cli::writer::{Output, StdoutOutput};
+use crate::core::cache::ResponseCache;
+use crate::core::models::CommandResult;
+use crate::core::schema;
+use crate::core::services::AIService;
+use crate::infrastructure::Config;
+use crate::infrastructure::error::{GroundhogError, InternalError};
+use crate::infrastructure::log_buffer::LogBuffer;
+use crate::infrastructure::logging::LogFilterHandle;
+use crate::infrastructure::metrics;
+
+/// Global command-line options shared by all commands
+#[derive(Debug, Clone)]
+pub struct GlobalOptions {
+    /// Explicit configuration file path from `--config`
+    pub config_path: Option<PathBuf>,
+    /// Configuration profile from `--profile`
+    pub profile: Option<String>,
+    /// Whether configuration files were skipped with `--no-config`
+    pub no_config: bool,
+    /// Suppress non-error output
+    pub quiet: bool,
+    /// Suppress spinners and progress bars as well
+    pub silent: bool,
+    /// Format used to render command output
+    pub output: OutputFormat,
+    /// Printed output is truncated to this many lines
+    pub max_output_lines: Option<usize>,
+    /// Recent log lines, captured for the TUI debug overlay
+    pub log_buffer: Option<LogBuffer>,
+    /// Changes the log level at runtime, unless logging was set up by the embedding program
+    pub log_filter: Option<LogFilterHandle>,
+    /// Triggered on Ctrl-C while a batch of files is being explained
+    pub cancel: CancellationToken,
+    /// Where command output is written, stdout by default
+    pub writer: Arc<dyn Output>,
+}
+
+impl Default for GlobalOptions {
+    fn default() -> Self {
+        Self {
+            config_path: None,
+            profile: None,
+            no_config: false,
+            quiet: false,
+            silent: false,
+            output: OutputFormat::default(),
+            max_output_lines: None,
+            log_buffer: None,
+            log_filter: None,
+            cancel: CancellationToken::new(),
+            writer: Arc::new(StdoutOutput),
+        }
+    }
+}
 
 /// Execute a command based on the provided command enum
-#[tracing::instrument(name = "command.execute", fields(command = %get_command_name(&command)))]
-pub async fn execute_command(command: Commands) -> Result<(), GroundhogError> {
-    match command {
-        Commands::Explain { topic } => explain::execute(topic),
-        Commands::Tui { debug } => tui::handle_tui(debug).await,
+///
+/// With `--output json` the command result, including error details on
+/// failure, is written to `options.writer` as a single JSON document. When
+/// `performance.metrics` is enabled the command's timing is appended to the
+/// metrics log.
+#[tracing::instrument(name = "command.execute", skip(config), fields(command = %get_command_name(&command)))]
+pub async fn execute_command(command: Commands, config: &Config, options: &GlobalOptions) -> Result<(), GroundhogError> {
+    let start = std::time::Instant::now();
+    let command_name = get_command_name(&command);
+
+    // Batches stop early on Ctrl-C and still report the files already explained
+    let signal_handler = matches!(&command, Commands::Explain(args) if !args.files.is_empty())
+        .then(|| cancel_on_ctrl_c(options.cancel.clone()));
+
+    let result = match command {
+        Commands::Explain(args) if !args.compare.is_empty() => compare::execute(*args, config, options).await,
+        Commands::Explain(args) => match explain_service(&args, config) {
+            Ok(service) => explain::execute(*args, Arc::new(service), config, options).await,
+            Err(e) => Err(e),
+        },
+        Commands::Tui { debug, prompt } => {
+            tui::handle_tui(debug, prompt, config, options).await.map(|_| CommandResult::success())
+        }
+        Commands::Repl => repl::execute(config, options).await,
+        Commands::Ping { wait } => ping::execute(wait, config, options).await,
+        Commands::Config { action } => config::execute(action, config, options),
+        Commands::Providers { action } => providers::execute(action, config, options).await,
+        Commands::Doctor => doctor::execute(config, options).await,
+        Commands::Cache { action } => cache::execute(action, config, options),
+    };
+
+    if let Some(handler) = signal_handler {
+        handler.abort();
+    }
+
+    if let Some(path) = config.performance.metrics.resolve_path() {
+        let report = match &result {
+            Ok(result) => result.clone(),
+            Err(e) => CommandResult::from_error(e),
+        };
+        let metric = metrics::CommandMetric::new(command_name, &report.with_duration(start.elapsed()));
+        // The timing log is best effort and never fails the command
+        if let Err(e) = metrics::append(&path, &metric) {
+            warn!(path = %path.display(), error = %e, "Failed to append command metrics");
+        }
     }
+
+    if options.output.is_json() {
+        let report = match &result {
+            Ok(result) => result.clone(),
+            Err(e) => CommandResult::from_error(e),
+        };
+        let report = report.with_duration(start.elapsed());
+        match options.output {
+            OutputFormat::Jsonl => options.writer.write_line(&output::done_line(&report).to_string())?,
+            _ => options.writer.write_line(&format_json_result(&report)?)?,
+        }
+    }
+
+    result.map(|_| ())
+}
+
+/// Cancel `cancel` on the first Ctrl-C and exit immediately on a second one
+fn cancel_on_ctrl_c(cancel: CancellationToken) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        info!("Interrupted, cancelling remaining work");
+        cancel.cancel();
+
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    })
+}
+
+/// Build the AI service for `explain`, with flags taking precedence over the config
+fn explain_service(args: &ExplainArgs, config: &Config) -> Result<AIService, GroundhogError> {
+    let single = args.count.is_none_or(|count| count.get() == 1);
+
+    Ok(AIService::from_config(config)
+        .with_depth(explain::resolve_depth(args, config))
+        .with_citations(args.cite)
+        .with_context_limit(config.context_limit())
+        .with_seed(args.seed.or_else(|| config.seed()))
+        .with_response_schema(args.json_schema.as_deref().map(schema::load).transpose()?)
+        .with_template(args.template.clone())
+        // Samples of the same topic are meant to differ
+        .with_dedupe(single)
+        .with_cache(config.performance.cache.resolve_dir().filter(|_| single).map(|dir| Arc::new(ResponseCache::new(dir)))))
+}
+
+/// Serialize a command result for `--output json`
+fn format_json_result(result: &CommandResult) -> Result<String, GroundhogError> {
+    serde_json::to_string_pretty(result).map_err(|e| {
+        InternalError::UnexpectedState {
+            message: format!("Failed to serialize command result: {}", e),
+        }
+        .into()
+    })
 }
 
 fn get_command_name(command: &Commands) -> &'static str {
     match command {
-        Commands::Explain { .. } => "explain",
+        Commands::Explain(_) => "explain",
         Commands::Tui { .. } => "tui",
+        Commands::Repl => "repl",
+        Commands::Ping { .. } => "ping",
+        Commands::Config { .. } => "config",
+        Commands::Providers { .. } => "providers",
+        Commands::Doctor => "doctor",
+        Commands::Cache { .. } => "cache",
     }
-} 
\ No newline at end of file
+}
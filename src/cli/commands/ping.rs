@@ -0,0 +1,67 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use tracing::{info, instrument};
+
+use crate::cli::commands::GlobalOptions;
+use crate::cli::progress::with_spinner;
+use crate::core::models::CommandResult;
+use crate::core::providers::HttpProvider;
+use crate::core::retry::RetryPolicy;
+use crate::core::services::AIService;
+use crate::infrastructure::Config;
+use crate::infrastructure::error::{ConfigError, GroundhogError};
+
+/// Check connectivity and authentication with the configured AI provider
+///
+/// With `wait`, transient failures are retried with backoff until the
+/// provider answers or the wait is over, printing a dot per retry.
+#[instrument(name = "command.ping", fields(command = "ping"), skip(config, options))]
+pub async fn execute(wait: Option<Duration>, config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    let ai = config.ai.as_ref().ok_or_else(|| ConfigError::MissingKey {
+        key: "ai".to_string(),
+    })?;
+
+    let provider = HttpProvider::from_config(ai, Duration::from_secs(config.performance.timeout))?;
+    let model = provider.model().to_string();
+    let service = AIService::new()
+        .with_provider(Arc::new(provider))
+        .with_retry_policy(RetryPolicy::from_config(&config.performance.retry));
+
+    info!(provider = ?ai.provider, model = %model, wait = ?wait, "Pinging AI provider");
+    let latency = match wait {
+        Some(wait) => {
+            let mut retried = false;
+            let result = service
+                .ping_until_ready(wait, |_| {
+                    retried = true;
+                    if !options.silent {
+                        eprint!(".");
+                        let _ = std::io::stderr().flush();
+                    }
+                })
+                .await;
+            if retried && !options.silent {
+                eprintln!();
+            }
+            result?
+        }
+        // Retries print dots instead, which a spinner would draw over
+        None => with_spinner("Waiting for provider", !options.silent, service.ping()).await?,
+    };
+
+    if !options.output.is_json() {
+        let out = &options.writer;
+        out.write_line(&format!("Provider: {}", ai.provider))?;
+        out.write_line(&format!("Model: {}", model))?;
+        out.write_line(&format!("Latency: {} ms", latency.as_millis()))?;
+    }
+
+    Ok(CommandResult::success().with_data(json!({
+        "provider": ai.provider.to_string(),
+        "model": model,
+        "latency_ms": latency.as_millis() as u64,
+    })))
+}
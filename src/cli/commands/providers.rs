@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use serde_json::json;
+use tracing::{info, instrument};
+
+use crate::cli::ProvidersCommands;
+use crate::cli::commands::GlobalOptions;
+use crate::cli::output::{self, OutputFormat};
+use crate::cli::progress::with_spinner;
+use crate::cli::table::Table;
+use crate::core::models::CommandResult;
+use crate::core::providers::HttpProvider;
+use crate::infrastructure::Config;
+use crate::infrastructure::config::AiProvider;
+use crate::infrastructure::error::GroundhogError;
+
+/// Handle the providers subcommands
+#[instrument(name = "command.providers", fields(command = "providers", action = ?action), skip(config, options))]
+pub async fn execute(
+    action: ProvidersCommands,
+    config: &Config,
+    options: &GlobalOptions,
+) -> Result<CommandResult, GroundhogError> {
+    match action {
+        ProvidersCommands::List => list(config, options).await,
+    }
+}
+
+/// Print supported providers and, when the configured one is reachable, its models
+///
+/// Models are only queried for a configured provider with an API key, or
+/// for a local provider which usually needs none.
+async fn list(config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    let configured = config.ai.as_ref().map(|ai| &ai.provider);
+    let text = !options.output.is_json();
+    let table = options.output == OutputFormat::Table;
+    let color = output::color_enabled(&config.output, options.writer.is_tty());
+    let out = &options.writer;
+
+    if table {
+        let rows = AiProvider::ALL.iter().map(|provider| {
+            let marker = if Some(provider) == configured { "*" } else { "" };
+            [provider.to_string(), marker.to_string()]
+        });
+        let providers = rows.fold(Table::new(["Provider", "Configured"]), |table, row| table.with_row(row));
+        out.write_raw(&providers.with_color(color).render())?;
+    } else if text {
+        out.write_line("Supported providers:")?;
+        for provider in &AiProvider::ALL {
+            let marker = if Some(provider) == configured { "*" } else { " " };
+            out.write_line(&format!("{} {}", marker, provider))?;
+        }
+    }
+
+    let models = match &config.ai {
+        Some(ai) if ai.provider == AiProvider::Local || ai.resolve_api_key().is_some() => {
+            let provider = HttpProvider::from_config(ai, Duration::from_secs(config.performance.timeout))?;
+            info!(provider = ?ai.provider, "Listing provider models");
+            let models = with_spinner("Fetching models", !options.silent, provider.list_models()).await?;
+            if table {
+                let list = models.iter().fold(Table::new(["Model"]), |table, model| table.with_row([model.as_str()]));
+                out.write_line("")?;
+                out.write_raw(&list.with_color(color).render())?;
+            } else if text {
+                out.write_line("")?;
+                out.write_line(&format!("Models ({}):", ai.provider))?;
+                for model in &models {
+                    out.write_line(&format!("  {}", model))?;
+                }
+            }
+            Some(models)
+        }
+        _ => None,
+    };
+
+    Ok(CommandResult::success().with_data(json!({
+        "providers": AiProvider::ALL.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        "configured": configured.map(ToString::to_string),
+        "models": models,
+    })))
+}
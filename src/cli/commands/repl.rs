@@ -0,0 +1,111 @@
+use std::io::{IsTerminal, Write};
+
+use serde_json::json;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tracing::{info, instrument, warn};
+
+use crate::cli::commands::GlobalOptions;
+use crate::cli::progress::with_spinner;
+use crate::core::models::{CommandResult, Conversation, Role};
+use crate::core::services::AIService;
+use crate::infrastructure::Config;
+use crate::infrastructure::error::{FileSystemError, GroundhogError};
+
+/// Line that ends the session before the end of input
+pub const QUIT_COMMAND: &str = ":quit";
+
+/// Answer prompts read from stdin, one per line, in a single conversation
+///
+/// A `> ` prompt is shown on stderr when stdin is a terminal.
+#[instrument(name = "command.repl", fields(command = "repl"), skip(config, options))]
+pub async fn execute(config: &Config, options: &GlobalOptions) -> Result<CommandResult, GroundhogError> {
+    info!("Starting REPL");
+
+    let service = AIService::for_chat(config);
+    let interactive = std::io::stdin().is_terminal();
+    let conversation = run(&service, BufReader::new(tokio::io::stdin()), interactive, options).await?;
+
+    let turns = conversation.messages.iter().filter(|m| m.role == Role::Assistant).count();
+    info!(turns, "REPL ended");
+    Ok(CommandResult::success().with_data(json!({
+        "conversation": conversation.id,
+        "turns": turns,
+    })))
+}
+
+/// Answer each line of `input` until it ends or a line is [`QUIT_COMMAND`]
+///
+/// Blank lines are skipped. A failed turn is reported on stderr and kept in
+/// the conversation as a note, and the session goes on.
+pub async fn run(
+    service: &AIService,
+    input: impl AsyncBufRead + Unpin,
+    interactive: bool,
+    options: &GlobalOptions,
+) -> Result<Conversation, GroundhogError> {
+    let mut conversation = Conversation::new();
+    let mut lines = input.lines();
+
+    loop {
+        if interactive {
+            eprint!("> ");
+            let _ = std::io::stderr().flush();
+        }
+        let Some(line) = lines.next_line().await.map_err(FileSystemError::Io)? else {
+            break;
+        };
+        let prompt = line.trim();
+        if prompt == QUIT_COMMAND {
+            break;
+        }
+        if prompt.is_empty() {
+            continue;
+        }
+
+        conversation.push_user(prompt);
+        let reply = with_spinner("Waiting for AI response", !options.silent, service.generate_reply(&conversation)).await;
+        match reply {
+            Ok(text) => {
+                if !options.output.is_json() {
+                    options.writer.write_line(&text)?;
+                }
+                conversation.push_assistant(text);
+            }
+            Err(e) => {
+                warn!(error = %e, "REPL turn failed");
+                eprintln!("error: {}", e.user_message());
+                conversation.push_note(e.user_message());
+            }
+        }
+    }
+
+    Ok(conversation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::cli::writer::MemoryOutput;
+    use crate::core::services::MockProvider;
+
+    #[tokio::test]
+    async fn test_answers_each_line_until_quit() {
+        let provider = Arc::new(MockProvider::new("second answer").with_reply("first answer"));
+        let service = AIService::new().with_provider(provider.clone());
+        let output = MemoryOutput::new();
+        let options = GlobalOptions {
+            writer: Arc::new(output.clone()),
+            ..GlobalOptions::default()
+        };
+
+        let input = "What is a closure?\n\n  Show an example  \n:quit\nignored\n".as_bytes();
+        let conversation = run(&service, input, false, &options).await.unwrap();
+
+        assert_eq!(output.contents(), "first answer\nsecond answer\n");
+        assert_eq!(conversation.len(), 4);
+        assert_eq!(provider.requests().len(), 2);
+        assert!(provider.requests()[1].prompt.ends_with("Assistant: first answer\n\nShow an example"));
+    }
+}
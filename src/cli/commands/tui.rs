@@ -1,20 +1,74 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
 use tracing::{info, instrument};
 
+use crate::cli::commands::GlobalOptions;
+use crate::cli::highlight::Highlighter;
+use crate::core::services::AIService;
+use crate::infrastructure::Config;
+use crate::infrastructure::config::STDIN_PATH;
 use crate::infrastructure::error::GroundhogError;
-use crate::tui;
+use crate::infrastructure::log_buffer::LogBuffer;
+use crate::tui::{App, ConfigLoader, KeyBindings};
 
 /// Handle the TUI command
-#[instrument]
-pub async fn handle_tui(debug_mode: bool) -> Result<(), GroundhogError> {
+///
+/// In debug mode the overlay shows lines from the captured log buffer, or an
+/// empty log when none was captured. A `prompt` is submitted as soon as the
+/// TUI starts. The reload action searches for configuration files the same
+/// way as startup, unless they were skipped or read from stdin. The log
+/// level can be changed while the TUI runs unless the embedding program set
+/// up logging itself.
+#[instrument(skip(config, options))]
+pub async fn handle_tui(
+    debug_mode: bool,
+    prompt: Option<String>,
+    config: &Config,
+    options: &GlobalOptions,
+) -> Result<(), GroundhogError> {
     info!("Starting TUI mode (debug: {})", debug_mode);
 
-    if debug_mode {
-        info!("TUI debug mode enabled");
+    let debug_logs = debug_mode.then(|| options.log_buffer.clone().unwrap_or_else(|| LogBuffer::new(0)));
+
+    let key_bindings = KeyBindings::from_config(&config.tui.keys)?;
+    let highlighter = if config.output.color {
+        Highlighter::from_config(&config.output)
+    } else {
+        Highlighter::plain()
+    };
+    let service = AIService::for_chat(config);
+
+    let reloadable = !options.no_config && options.config_path.as_deref() != Some(Path::new(STDIN_PATH));
+    let config_loader = reloadable.then(|| {
+        let (path, profile) = (options.config_path.clone(), options.profile.clone());
+        Box::new(move || Config::load_hierarchical(path.clone(), profile.clone())) as ConfigLoader
+    });
+
+    let app = match prompt {
+        Some(prompt) => App::new_with_prompt(prompt),
+        None => App::new(),
+    };
+    let mut app = app
+        .with_key_bindings(key_bindings)
+        .with_highlighter(highlighter)
+        .with_service(Arc::new(service))
+        .with_config(config.clone())
+        .with_request_timeout(Duration::from_secs(config.performance.timeout));
+    if let Some(logs) = debug_logs {
+        app = app.with_debug(logs);
+    }
+    if let Some(loader) = config_loader {
+        app = app.with_config_loader(loader);
+    }
+    if let Some(log_filter) = options.log_filter.clone() {
+        app = app.with_log_filter(log_filter);
     }
 
     // Launch the TUI application
-    tui::run().await?;
+    app.run().await?;
 
     info!("TUI mode ended");
     Ok(())
-} 
\ No newline at end of file
+}
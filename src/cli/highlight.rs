@@ -0,0 +1,229 @@
+use std::sync::OnceLock;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style as SyntectStyle, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::as_24_bit_terminal_escaped;
+use tracing::warn;
+
+use crate::cli::output::color_enabled;
+use crate::core::markdown::code_blocks;
+use crate::infrastructure::config::OutputConfig;
+
+/// Theme used when `output.theme` is not set
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Resets terminal colors at the end of a highlighted line
+const RESET: &str = "\x1b[0m";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_nonewlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Names of the built-in highlight themes
+pub fn theme_names() -> impl Iterator<Item = &'static str> {
+    theme_set().themes.keys().map(String::as_str)
+}
+
+/// Syntax highlighting for fenced code blocks in markdown text
+///
+/// A plain highlighter leaves code untouched, so callers can render the same
+/// way whether or not highlighting is enabled.
+#[derive(Clone, Copy, Default)]
+pub struct Highlighter {
+    theme: Option<(&'static str, &'static Theme)>,
+}
+
+impl std::fmt::Debug for Highlighter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Highlighter")
+            .field("theme", &self.theme_name())
+            .finish()
+    }
+}
+
+impl Highlighter {
+    /// Highlighter that leaves code as plain text
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    /// Highlight with the named built-in theme
+    ///
+    /// Unknown names log a warning and fall back to plain text.
+    pub fn new(theme: &str) -> Self {
+        match theme_set().themes.get_key_value(theme) {
+            Some((name, theme)) => Self {
+                theme: Some((name.as_str(), theme)),
+            },
+            None => {
+                warn!(
+                    theme,
+                    available = %theme_names().collect::<Vec<_>>().join(", "),
+                    "Unknown highlight theme, code will not be highlighted"
+                );
+                Self::plain()
+            }
+        }
+    }
+
+    /// Highlight with `output.theme`, or [`DEFAULT_THEME`] when it is not set
+    pub fn from_config(output: &OutputConfig) -> Self {
+        Self::new(output.theme.as_deref().unwrap_or(DEFAULT_THEME))
+    }
+
+    /// Like [`Highlighter::from_config`], but plain unless writing to a color terminal
+    ///
+    /// Colors are off when `is_tty` is false, with `output.color = false` or
+    /// with a non-empty `NO_COLOR`.
+    pub fn for_terminal(output: &OutputConfig, is_tty: bool) -> Self {
+        if color_enabled(output, is_tty) {
+            Self::from_config(output)
+        } else {
+            Self::plain()
+        }
+    }
+
+    /// Name of the theme in use, or `None` for plain text
+    pub fn theme_name(&self) -> Option<&'static str> {
+        self.theme.map(|(name, _)| name)
+    }
+
+    /// Whether code is highlighted at all
+    pub fn is_enabled(&self) -> bool {
+        self.theme.is_some()
+    }
+
+    /// Styled ranges for each line of `text`
+    ///
+    /// Lines inside fenced code blocks get `Some` ranges, highlighted with
+    /// the syntax named by the fence; fences, prose and every line of a plain
+    /// highlighter get `None`.
+    pub fn highlight_lines<'a>(&self, text: &'a str) -> Vec<Option<Vec<(SyntectStyle, &'a str)>>> {
+        let Some((_, theme)) = self.theme else {
+            return text.lines().map(|_| None).collect();
+        };
+
+        let syntaxes = syntax_set();
+        let blocks = code_blocks(text);
+        let mut current: Option<(usize, HighlightLines)> = None;
+
+        text.lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let block = blocks.iter().position(|block| block.contains_code_line(index))?;
+                if current.as_ref().is_none_or(|(open, _)| *open != block) {
+                    let syntax = blocks[block]
+                        .language
+                        .as_deref()
+                        .and_then(|language| syntaxes.find_syntax_by_token(language))
+                        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+                    current = Some((block, HighlightLines::new(syntax, theme)));
+                }
+
+                let (_, highlighter) = current.as_mut()?;
+                highlighter.highlight_line(line, syntaxes).ok()
+            })
+            .collect()
+    }
+
+    /// Render `text` with code blocks colored by ANSI escape codes
+    pub fn to_ansi(&self, text: &str) -> String {
+        if !self.is_enabled() {
+            return text.to_string();
+        }
+
+        let mut output = String::with_capacity(text.len());
+        for (line, ranges) in text.lines().zip(self.highlight_lines(text)) {
+            match ranges {
+                Some(ranges) => {
+                    output.push_str(&as_24_bit_terminal_escaped(&ranges, false));
+                    output.push_str(RESET);
+                }
+                None => output.push_str(line),
+            }
+            output.push('\n');
+        }
+        if !text.ends_with('\n') {
+            output.pop();
+        }
+        output
+    }
+}
+
+/// Convert a highlight style to a TUI style, keeping the foreground color and emphasis
+pub fn ratatui_style(style: SyntectStyle) -> ratatui::style::Style {
+    use ratatui::style::{Color, Modifier, Style};
+    use syntect::highlighting::FontStyle;
+
+    let mut converted = Style::default().fg(Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        converted = converted.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        converted = converted.add_modifier(Modifier::UNDERLINED);
+    }
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::infrastructure::log_buffer::LogBuffer;
+
+    const ANSWER: &str = "Use a loop:\n```rust\nfor i in 0..3 {}\n```\nDone.";
+
+    #[test]
+    fn test_unknown_theme_falls_back_to_plain_text_with_warning() {
+        let buffer = LogBuffer::new(10);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+
+        let highlighter = tracing::subscriber::with_default(subscriber, || Highlighter::new("no-such-theme"));
+
+        assert!(!highlighter.is_enabled());
+        assert_eq!(highlighter.to_ansi(ANSWER), ANSWER);
+        let logs = buffer.recent(10);
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains("WARN"));
+        assert!(logs[0].contains("Unknown highlight theme"));
+        assert!(logs[0].contains("no-such-theme"));
+    }
+
+    #[test]
+    fn test_known_theme_colors_only_code_lines() {
+        let highlighter = Highlighter::new(DEFAULT_THEME);
+        assert_eq!(highlighter.theme_name(), Some(DEFAULT_THEME));
+
+        let rendered = highlighter.to_ansi(ANSWER);
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "Use a loop:");
+        assert_eq!(lines[1], "```rust");
+        assert!(lines[2].contains("\x1b[38;2;"));
+        assert!(lines[2].ends_with(RESET));
+        assert_eq!(lines[3], "```");
+        assert_eq!(lines[4], "Done.");
+        assert!(!rendered.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_highlight_lines_marks_code_inside_unterminated_block() {
+        let ranges = Highlighter::new(DEFAULT_THEME).highlight_lines("```\nstill code");
+
+        assert!(ranges[0].is_none());
+        let code = ranges[1].as_ref().unwrap();
+        assert_eq!(code.iter().map(|(_, text)| *text).collect::<String>(), "still code");
+    }
+}
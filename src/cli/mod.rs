@@ -1,5 +1,11 @@
 pub mod args;
+pub mod clipboard;
 pub mod commands;
+pub mod highlight;
+pub mod output;
+pub mod progress;
+pub mod table;
+pub mod writer;
 
-pub use args::{Cli, Commands};
-pub use commands::execute_command; 
\ No newline at end of file
+pub use args::{CacheCommands, Cli, Commands, ConfigCommands, ExplainArgs, ProvidersCommands};
+pub use commands::{GlobalOptions, execute_command}; 
\ No newline at end of file
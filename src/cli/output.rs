@@ -0,0 +1,482 @@
+use std::io::Write;
+use std::path::Path;
+
+use clap::ValueEnum;
+use tracing::instrument;
+
+use crate::cli::table::Table;
+use crate::cli::writer;
+use crate::core::markdown::code_blocks;
+use crate::core::models::{CommandResult, Conversation, Role};
+use crate::infrastructure::config::OutputConfig;
+use crate::infrastructure::error::{ConfigError, FileSystemError, GroundhogError, ParseError};
+
+/// Format used to render command output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Plain text
+    #[default]
+    Text,
+    /// Markdown document with headings and metadata
+    Markdown,
+    /// Machine-readable JSON, including the command result
+    Json,
+    /// One JSON object per line, streaming `{"delta": ...}` chunks before a final `{"done": true}`
+    Jsonl,
+    /// Aligned columns, one row per result
+    Table,
+}
+
+impl OutputFormat {
+    /// Parse the `output.format` configuration value
+    pub fn from_config(value: &str) -> Result<Self, GroundhogError> {
+        <Self as ValueEnum>::from_str(value, true).map_err(|_| {
+            ConfigError::InvalidValue {
+                key: "output.format".to_string(),
+                value: value.to_string(),
+                expected: "one of: text, markdown, json, jsonl, table".to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// Whether output is JSON or JSON lines, so human-readable text must not be printed
+    pub fn is_json(self) -> bool {
+        matches!(self, Self::Json | Self::Jsonl)
+    }
+}
+
+/// Whether output to a terminal may be styled
+///
+/// Styling is off when `is_tty` is false, with `output.color = false` (set by
+/// `--no-color`) or with a non-empty `NO_COLOR`.
+pub fn color_enabled(output: &OutputConfig, is_tty: bool) -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    output.color && !no_color && is_tty
+}
+
+/// Writes `--output jsonl` delta lines, flushing each so consumers see chunks immediately
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    error: Option<std::io::Error>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, error: None }
+    }
+
+    /// Write a `{"delta": ...}` line, keeping the first write error for [`Self::finish`]
+    pub fn delta(&mut self, text: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        let line = serde_json::json!({ "delta": text });
+        if let Err(e) = writeln!(self.writer, "{}", line).and_then(|_| self.writer.flush()) {
+            self.error = Some(e);
+        }
+    }
+
+    /// Report any error from writing the delta lines
+    pub fn finish(self) -> Result<(), GroundhogError> {
+        match self.error {
+            Some(e) => Err(writer::output_error(e).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Final `--output jsonl` line: `{"done": true, "success": ...}` plus the fields of the result data
+pub fn done_line(result: &CommandResult) -> serde_json::Value {
+    let mut line = serde_json::Map::new();
+    line.insert("done".to_string(), true.into());
+    line.insert("success".to_string(), result.success.into());
+    match &result.data {
+        Some(serde_json::Value::Object(data)) => line.extend(data.clone()),
+        Some(data) => {
+            line.insert("data".to_string(), data.clone());
+        }
+        None => {}
+    }
+    if let Some(error) = &result.error {
+        line.insert("error".to_string(), serde_json::json!(error));
+    }
+    serde_json::Value::Object(line)
+}
+
+/// A single explanation to be rendered
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplanationOutput {
+    /// Topic or file the explanation is about
+    pub title: String,
+    pub body: String,
+    /// Extra key/value details such as source or language
+    pub metadata: Vec<(String, String)>,
+}
+
+impl ExplanationOutput {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            metadata: Vec::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Convert a conversation into one output section per message, titled by role
+pub fn conversation_outputs(conversation: &Conversation) -> Vec<ExplanationOutput> {
+    conversation
+        .messages
+        .iter()
+        .map(|message| {
+            let title = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::System => "System",
+                Role::Note => "Note",
+            };
+            ExplanationOutput::new(title, message.content.clone())
+        })
+        .collect()
+}
+
+/// Render explanations in the requested format
+#[instrument(name = "output.render", fields(format = ?format, count = explanations.len()), skip(explanations))]
+pub fn render(format: OutputFormat, explanations: &[ExplanationOutput]) -> Result<String, GroundhogError> {
+    match format {
+        OutputFormat::Text => Ok(render_text(explanations)),
+        OutputFormat::Markdown => {
+            let sections = explanations
+                .iter()
+                .map(render_markdown)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(sections.join("\n"))
+        }
+        OutputFormat::Json => Ok(format!("{:#}\n", to_json(explanations))),
+        OutputFormat::Jsonl => Ok(to_json(explanations)
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|explanation| format!("{}\n", explanation))
+            .collect()),
+        OutputFormat::Table => Ok(explanation_table(explanations).render()),
+    }
+}
+
+/// Longest summary shown in an explanation table before it is cut off
+const TABLE_SUMMARY_CHARS: usize = 72;
+
+/// Table of explanations with their title and the first line of their body
+pub fn explanation_table(explanations: &[ExplanationOutput]) -> Table {
+    explanations.iter().fold(Table::new(["Title", "Summary"]), |table, explanation| {
+        let first_line = explanation.body.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+        let summary = match first_line.char_indices().nth(TABLE_SUMMARY_CHARS) {
+            Some((end, _)) => format!("{}...", &first_line[..end]),
+            None => first_line.to_string(),
+        };
+        table.with_row([explanation.title.clone(), summary])
+    })
+}
+
+/// Keep the first `max_lines` lines of printed output, replacing the rest with a footer
+pub fn truncate_lines(text: String, max_lines: Option<usize>) -> String {
+    let Some(max_lines) = max_lines else { return text };
+    let total = text.lines().count();
+    if total <= max_lines {
+        return text;
+    }
+
+    let mut truncated: String = text.lines().take(max_lines).flat_map(|line| [line, "\n"]).collect();
+    truncated.push_str(&format!("... (truncated, {} more lines)\n", total - max_lines));
+    truncated
+}
+
+/// Convert explanations into a JSON array of `{title, body, metadata}` objects
+pub fn to_json(explanations: &[ExplanationOutput]) -> serde_json::Value {
+    explanations
+        .iter()
+        .map(|explanation| {
+            let metadata: serde_json::Map<String, serde_json::Value> = explanation
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+                .collect();
+            serde_json::json!({
+                "title": explanation.title,
+                "body": explanation.body,
+                "metadata": metadata,
+            })
+        })
+        .collect()
+}
+
+/// Render plain text, adding headers when there is more than one explanation
+fn render_text(explanations: &[ExplanationOutput]) -> String {
+    let show_headers = explanations.len() > 1;
+    let mut output = String::new();
+
+    for explanation in explanations {
+        if show_headers {
+            output.push_str(&format!("==> {} <==\n", explanation.title));
+        }
+        output.push_str(&explanation.body);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Render one explanation as a markdown section
+fn render_markdown(explanation: &ExplanationOutput) -> Result<String, GroundhogError> {
+    if let Some(block) = code_blocks(&explanation.body).iter().find(|b| !b.closed) {
+        return Err(ParseError::Markdown {
+            line: block.start_line + 1,
+            message: "unterminated code block in explanation".to_string(),
+        }
+        .into());
+    }
+
+    let mut output = format!("# {}\n\n", explanation.title.trim());
+    if !explanation.metadata.is_empty() {
+        for (key, value) in &explanation.metadata {
+            output.push_str(&format!("- **{}:** {}\n", key, value));
+        }
+        output.push('\n');
+    }
+    output.push_str(explanation.body.trim_end());
+    output.push('\n');
+
+    Ok(output)
+}
+
+/// Write rendered output to a file, creating parent directories
+///
+/// Existing files are only replaced when `force` is set.
+pub fn write_output_file(path: &Path, content: &str, force: bool) -> Result<(), GroundhogError> {
+    create_parent_dirs(path)?;
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .create_new(!force)
+        .open(path)
+        .map_err(|e| write_error(path, e))?;
+    file.write_all(content.as_bytes()).map_err(|e| write_error(path, e))
+}
+
+/// Append rendered output to a file as a new entry, creating the file if needed
+///
+/// Each entry starts with a separator line carrying the UTC time it was
+/// written, and entries after the first are set off by a blank line.
+pub fn append_output_file(path: &Path, content: &str) -> Result<(), GroundhogError> {
+    let timestamp = time::OffsetDateTime::now_utc()
+        .replace_nanosecond(0)
+        .ok()
+        .and_then(|now| now.format(&time::format_description::well_known::Rfc3339).ok())
+        .unwrap_or_default();
+
+    create_parent_dirs(path)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| write_error(path, e))?;
+    let is_empty = file.metadata().map(|metadata| metadata.len() == 0).unwrap_or(true);
+    file.write_all(append_entry(content, &timestamp, !is_empty).as_bytes())
+        .map_err(|e| write_error(path, e))
+}
+
+/// An appended entry: a separator with `timestamp`, then `content` ending in a newline
+fn append_entry(content: &str, timestamp: &str, after_existing: bool) -> String {
+    let mut entry = String::new();
+    if after_existing {
+        entry.push('\n');
+    }
+    entry.push_str(&format!("--- {} ---\n", timestamp));
+    entry.push_str(content);
+    if !content.ends_with('\n') {
+        entry.push('\n');
+    }
+    entry
+}
+
+fn create_parent_dirs(path: &Path) -> Result<(), GroundhogError> {
+    match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(parent) => std::fs::create_dir_all(parent).map_err(|e| write_error(path, e)),
+        None => Ok(()),
+    }
+}
+
+fn write_error(path: &Path, error: std::io::Error) -> GroundhogError {
+    match error.kind() {
+        std::io::ErrorKind::PermissionDenied => FileSystemError::NotWritable { path: path.to_path_buf() },
+        std::io::ErrorKind::AlreadyExists => FileSystemError::AlreadyExists { path: path.to_path_buf() },
+        _ => FileSystemError::Io(error),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_json_lines_writer_emits_one_delta_per_line() {
+        let mut buffer = Vec::new();
+        let mut writer = JsonLinesWriter::new(&mut buffer);
+        writer.delta("Owner");
+        writer.delta("ship \"moves\"");
+        writer.finish().unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, vec![serde_json::json!({ "delta": "Owner" }), serde_json::json!({ "delta": "ship \"moves\"" })]);
+    }
+
+    #[test]
+    fn test_done_line_merges_result_data() {
+        let result = CommandResult::success().with_data(serde_json::json!({ "usage": { "prompt_tokens": 2 } }));
+        assert_eq!(
+            done_line(&result),
+            serde_json::json!({ "done": true, "success": true, "usage": { "prompt_tokens": 2 } })
+        );
+    }
+
+    #[test]
+    fn test_truncate_lines_at_boundary() {
+        let text = "one\ntwo\nthree\n".to_string();
+        assert_eq!(truncate_lines(text.clone(), Some(3)), text);
+        assert_eq!(truncate_lines(text.clone(), Some(4)), text);
+        assert_eq!(truncate_lines(text.clone(), None), text);
+        assert_eq!(truncate_lines(text, Some(2)), "one\ntwo\n... (truncated, 1 more lines)\n");
+    }
+
+    #[test]
+    fn test_truncate_lines_footer_counts_remaining_lines() {
+        let text: String = (1..=10).map(|n| format!("line {}\n", n)).collect();
+        let truncated = truncate_lines(text, Some(1));
+
+        assert_eq!(truncated.lines().count(), 2);
+        assert!(truncated.starts_with("line 1\n"));
+        assert!(truncated.ends_with("... (truncated, 9 more lines)\n"));
+    }
+
+    #[test]
+    fn test_output_format_from_config() {
+        assert_eq!(OutputFormat::from_config("text").unwrap(), OutputFormat::Text);
+        assert_eq!(OutputFormat::from_config("Markdown").unwrap(), OutputFormat::Markdown);
+        assert_eq!(OutputFormat::from_config("json").unwrap(), OutputFormat::Json);
+        assert!(matches!(
+            OutputFormat::from_config("yaml"),
+            Err(GroundhogError::Config(ConfigError::InvalidValue { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_render_text_single() {
+        let output = render(OutputFormat::Text, &[ExplanationOutput::new("rust", "hello world")]).unwrap();
+        assert_eq!(output, "hello world\n");
+    }
+
+    #[test]
+    fn test_render_text_multiple_has_headers() {
+        let explanations = [ExplanationOutput::new("a.rs", "first"), ExplanationOutput::new("b.rs", "second")];
+        let output = render(OutputFormat::Text, &explanations).unwrap();
+        assert_eq!(output, "==> a.rs <==\nfirst\n==> b.rs <==\nsecond\n");
+    }
+
+    #[test]
+    fn test_render_markdown_has_heading_and_body() {
+        let explanation = ExplanationOutput::new("ownership", "Ownership moves values.\n\n```rust\nlet a = b;\n```")
+            .with_metadata("Language", "en");
+
+        let output = render(OutputFormat::Markdown, &[explanation]).unwrap();
+
+        assert!(output.starts_with("# ownership\n"));
+        assert!(output.contains("- **Language:** en"));
+        assert!(output.contains("Ownership moves values."));
+        assert!(output.contains("```rust\nlet a = b;\n```"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let explanation = ExplanationOutput::new("rust", "hello").with_metadata("Language", "en");
+
+        let output = render(OutputFormat::Json, &[explanation]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value[0]["title"], "rust");
+        assert_eq!(value[0]["body"], "hello");
+        assert_eq!(value[0]["metadata"]["Language"], "en");
+    }
+
+    #[test]
+    fn test_render_markdown_unterminated_code_block() {
+        let explanation = ExplanationOutput::new("broken", "text\n```rust\nfn main() {}");
+
+        match render(OutputFormat::Markdown, &[explanation]) {
+            Err(GroundhogError::Parse(ParseError::Markdown { line, .. })) => assert_eq!(line, 2),
+            other => panic!("Expected ParseError::Markdown, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_render_conversation_as_markdown() {
+        let mut conversation = Conversation::new();
+        conversation.push_user("What is ownership?");
+        conversation.push_assistant("Every value has one owner.");
+
+        let output = render(OutputFormat::Markdown, &conversation_outputs(&conversation)).unwrap();
+
+        assert_eq!(output, "# User\n\nWhat is ownership?\n\n# Assistant\n\nEvery value has one owner.\n");
+    }
+
+    #[test]
+    fn test_write_output_file_refuses_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("out.md");
+
+        write_output_file(&path, "first", false).unwrap();
+        assert!(matches!(
+            write_output_file(&path, "second", false),
+            Err(GroundhogError::FileSystem(FileSystemError::AlreadyExists { .. }))
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        write_output_file(&path, "second", true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+    }
+
+    #[test]
+    fn test_append_output_file_adds_separated_entries() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("session.md");
+
+        append_output_file(&path, "first answer\n").unwrap();
+        append_output_file(&path, "second answer").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let separators: Vec<&str> = content.lines().filter(|line| line.starts_with("--- ")).collect();
+        assert_eq!(separators.len(), 2);
+        assert!(separators.iter().all(|line| line.ends_with("Z ---")));
+        assert!(content.starts_with("--- "));
+        assert!(content.contains("first answer\n\n--- "));
+        assert!(content.ends_with("second answer\n"));
+    }
+
+    #[test]
+    fn test_append_entry_layout() {
+        assert_eq!(append_entry("text", "2026-01-02T03:04:05Z", false), "--- 2026-01-02T03:04:05Z ---\ntext\n");
+        assert_eq!(append_entry("text\n", "T", true), "\n--- T ---\ntext\n");
+    }
+}
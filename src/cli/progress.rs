@@ -0,0 +1,275 @@
+use std::borrow::Cow;
+use std::future::Future;
+use std::io::{self, IsTerminal, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Progress bar currently drawn on stderr, if any
+static ACTIVE_BAR: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Reports progress of a multi-step operation on stderr
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    bar: ProgressBar,
+}
+
+impl ProgressReporter {
+    /// Create a reporter, drawing on stderr only when `enabled` and stderr is a terminal
+    pub fn new(total: u64, enabled: bool) -> Self {
+        if !enabled || !io::stderr().is_terminal() {
+            return Self::hidden(total);
+        }
+
+        let bar = ProgressBar::with_draw_target(Some(total), ProgressDrawTarget::stderr());
+        bar.set_style(
+            ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} ({percent}%) {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+        );
+        set_active_bar(&bar);
+        Self { bar }
+    }
+
+    /// Create a reporter that tracks progress without drawing anything
+    pub fn hidden(total: u64) -> Self {
+        let bar = ProgressBar::hidden();
+        bar.set_length(total);
+        Self { bar }
+    }
+
+    /// Mark one more step as done
+    pub fn advance(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Number of completed steps
+    pub fn done(&self) -> u64 {
+        self.bar.position()
+    }
+
+    /// Total number of steps
+    pub fn total(&self) -> u64 {
+        self.bar.length().unwrap_or(0)
+    }
+
+    /// Completion percentage
+    pub fn percentage(&self) -> u8 {
+        progress_percentage(self.done(), self.total())
+    }
+
+    /// Remove the progress bar from the terminal
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+        clear_finished_bar();
+    }
+}
+
+/// Lifecycle of a [`Spinner`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpinnerState {
+    Idle,
+    Running,
+    Stopped,
+}
+
+/// Spinner shown on stderr while waiting for a single long operation
+///
+/// The state machine is tracked even when nothing is drawn, so callers
+/// behave the same with `--silent` or without a terminal.
+#[derive(Debug)]
+pub struct Spinner {
+    enabled: bool,
+    state: SpinnerState,
+    bar: Option<ProgressBar>,
+}
+
+impl Spinner {
+    /// Create a spinner, drawing on stderr only when `enabled` and stderr is a terminal
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: enabled && io::stderr().is_terminal(),
+            state: SpinnerState::Idle,
+            bar: None,
+        }
+    }
+
+    /// Create a spinner that never draws anything
+    pub fn hidden() -> Self {
+        Self::new(false)
+    }
+
+    /// Start spinning with a message; does nothing if already running
+    pub fn start(&mut self, message: impl Into<Cow<'static, str>>) {
+        if self.state == SpinnerState::Running {
+            return;
+        }
+        self.state = SpinnerState::Running;
+
+        if self.enabled {
+            let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr());
+            bar.set_style(
+                ProgressStyle::with_template("{spinner:.cyan} {msg} ({elapsed})")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            );
+            bar.set_message(message);
+            bar.enable_steady_tick(Duration::from_millis(100));
+            set_active_bar(&bar);
+            self.bar = Some(bar);
+        }
+    }
+
+    /// Stop spinning and clear the line; does nothing unless running
+    pub fn stop(&mut self) {
+        if self.state != SpinnerState::Running {
+            return;
+        }
+        self.state = SpinnerState::Stopped;
+
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+            clear_finished_bar();
+        }
+    }
+
+    /// Current state of the spinner
+    pub fn state(&self) -> SpinnerState {
+        self.state
+    }
+
+    /// Spin with `message` while awaiting `future`, stopping once it completes
+    pub async fn run<F: Future>(&mut self, message: impl Into<Cow<'static, str>>, future: F) -> F::Output {
+        self.start(message);
+        let output = future.await;
+        self.stop();
+        output
+    }
+}
+
+/// Await `future` behind a spinner, drawn only when `enabled` and stderr is a terminal
+///
+/// Commands pass `!options.silent` as `enabled`, so `--quiet` keeps the
+/// spinner while `--silent` hides it.
+pub async fn with_spinner<F: Future>(message: impl Into<Cow<'static, str>>, enabled: bool, future: F) -> F::Output {
+    Spinner::new(enabled).run(message, future).await
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Route log output around `bar` while it is drawn
+fn set_active_bar(bar: &ProgressBar) {
+    *ACTIVE_BAR.lock().unwrap_or_else(|e| e.into_inner()) = Some(bar.clone());
+}
+
+/// Forget the active bar once it has finished drawing
+fn clear_finished_bar() {
+    let mut active = ACTIVE_BAR.lock().unwrap_or_else(|e| e.into_inner());
+    if active.as_ref().is_some_and(|bar| bar.is_finished()) {
+        *active = None;
+    }
+}
+
+/// Percentage of `done` out of `total`, clamped to 100
+pub fn progress_percentage(done: u64, total: u64) -> u8 {
+    if total == 0 {
+        return 100;
+    }
+    ((done.min(total) * 100) / total) as u8
+}
+
+/// Stderr writer for log output that hides the active progress bar while writing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProgressAwareStderr;
+
+impl Write for ProgressAwareStderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let active = ACTIVE_BAR.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        match active {
+            Some(bar) => bar.suspend(|| io::stderr().write(buf)),
+            None => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for ProgressAwareStderr {
+    type Writer = ProgressAwareStderr;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_percentage() {
+        assert_eq!(progress_percentage(0, 4), 0);
+        assert_eq!(progress_percentage(1, 4), 25);
+        assert_eq!(progress_percentage(2, 3), 66);
+        assert_eq!(progress_percentage(4, 4), 100);
+        assert_eq!(progress_percentage(5, 4), 100);
+        assert_eq!(progress_percentage(0, 0), 100);
+    }
+
+    #[test]
+    fn test_spinner_state_machine() {
+        let mut spinner = Spinner::hidden();
+        assert_eq!(spinner.state(), SpinnerState::Idle);
+
+        // Stopping before starting is a no-op
+        spinner.stop();
+        assert_eq!(spinner.state(), SpinnerState::Idle);
+
+        spinner.start("Waiting");
+        assert_eq!(spinner.state(), SpinnerState::Running);
+        spinner.start("Still waiting");
+        assert_eq!(spinner.state(), SpinnerState::Running);
+
+        spinner.stop();
+        assert_eq!(spinner.state(), SpinnerState::Stopped);
+        spinner.stop();
+        assert_eq!(spinner.state(), SpinnerState::Stopped);
+
+        spinner.start("Again");
+        assert_eq!(spinner.state(), SpinnerState::Running);
+    }
+
+    #[tokio::test]
+    async fn test_run_returns_output_and_stops_spinner() {
+        let mut spinner = Spinner::hidden();
+
+        let output = spinner.run("Waiting", async { 42 }).await;
+        assert_eq!(output, 42);
+        assert_eq!(spinner.state(), SpinnerState::Stopped);
+
+        let output = with_spinner("Waiting", false, async { Err::<u8, _>("failed") }).await;
+        assert_eq!(output, Err("failed"));
+    }
+
+    #[test]
+    fn test_reporter_counts_steps() {
+        let reporter = ProgressReporter::hidden(3);
+        assert_eq!(reporter.total(), 3);
+        assert_eq!(reporter.done(), 0);
+
+        reporter.advance();
+        reporter.advance();
+        assert_eq!(reporter.done(), 2);
+        assert_eq!(reporter.percentage(), 66);
+
+        reporter.advance();
+        reporter.finish();
+        assert_eq!(reporter.percentage(), 100);
+    }
+}
@@ -0,0 +1,136 @@
+/// Starts bold text in a terminal
+const BOLD: &str = "\x1b[1m";
+/// Resets terminal styling
+const RESET: &str = "\x1b[0m";
+
+/// Spaces between columns
+const GAP: &str = "  ";
+
+/// Rows rendered as left-aligned columns under a header, for `--output table`
+///
+/// Widths count characters, so cells are expected to be single-line text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    color: bool,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Print the header in bold; without color the table is plain text
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Append a row; missing cells are left empty and extra ones dropped
+    pub fn add_row<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) {
+        let mut row: Vec<String> = cells.into_iter().map(Into::into).take(self.headers.len()).collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Builder form of [`Table::add_row`]
+    pub fn with_row<S: Into<String>>(mut self, cells: impl IntoIterator<Item = S>) -> Self {
+        self.add_row(cells);
+        self
+    }
+
+    /// Render the header, a dashed rule and every row, each line ending in a newline
+    ///
+    /// The last column is not padded, so lines carry no trailing spaces.
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|column| {
+                std::iter::once(&self.headers)
+                    .chain(&self.rows)
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut output = String::new();
+        let header = self.render_line(&self.headers, &widths);
+        if self.color {
+            output.push_str(&format!("{}{}{}\n", BOLD, header, RESET));
+        } else {
+            output.push_str(&header);
+            output.push('\n');
+        }
+
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        output.push_str(&rule.join(GAP));
+        output.push('\n');
+
+        for row in &self.rows {
+            output.push_str(&self.render_line(row, &widths));
+            output.push('\n');
+        }
+        output
+    }
+
+    fn render_line(&self, cells: &[String], widths: &[usize]) -> String {
+        let last = cells.len().saturating_sub(1);
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column == last {
+                    cell.clone()
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect();
+        padded.join(GAP).trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_align_to_widest_cell() {
+        let table = Table::new(["Name", "Size", "Note"])
+            .with_row(["a", "1234567", "first"])
+            .with_row(["longer name", "1", ""])
+            .with_row(["ünïcode", "22", "last"]);
+
+        assert_eq!(
+            table.render(),
+            "Name         Size     Note\n\
+             -----------  -------  -----\n\
+             a            1234567  first\n\
+             longer name  1\n\
+             ünïcode      22       last\n"
+        );
+    }
+
+    #[test]
+    fn test_rows_are_fitted_to_the_header() {
+        let mut table = Table::new(["Key", "Value"]);
+        table.add_row(["only key"]);
+        table.add_row(["k", "v", "dropped"]);
+
+        assert_eq!(table.render(), "Key       Value\n--------  -----\nonly key\nk         v\n");
+    }
+
+    #[test]
+    fn test_color_only_styles_the_header() {
+        let table = Table::new(["A", "B"]).with_row(["1", "2"]);
+
+        let colored = table.clone().with_color(true).render();
+        assert_eq!(colored, format!("{}A  B{}\n-  -\n1  2\n", BOLD, RESET));
+        assert!(!table.render().contains('\x1b'));
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::{ErrorKind, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::infrastructure::error::{FileSystemError, GroundhogError};
+
+/// Destination for command output
+///
+/// Commands write through this instead of printing directly, so their output
+/// can be captured by tests and embedding programs.
+pub trait Output: std::fmt::Debug + Send + Sync {
+    /// Write `text` exactly as given
+    fn write_raw(&self, text: &str) -> Result<(), GroundhogError>;
+
+    /// Whether the output is an interactive terminal
+    fn is_tty(&self) -> bool;
+
+    /// Write `text` followed by a newline
+    fn write_line(&self, text: &str) -> Result<(), GroundhogError> {
+        self.write_raw(&format!("{}\n", text))
+    }
+}
+
+/// Writes to the process's stdout
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutOutput;
+
+impl Output for StdoutOutput {
+    fn write_raw(&self, text: &str) -> Result<(), GroundhogError> {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(text.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| output_error(e).into())
+    }
+
+    fn is_tty(&self) -> bool {
+        std::io::stdout().is_terminal()
+    }
+}
+
+/// Error for a failed write to the output, telling a closed pipe apart from other failures
+pub fn output_error(error: std::io::Error) -> FileSystemError {
+    match error.kind() {
+        ErrorKind::BrokenPipe => FileSystemError::OutputClosed,
+        _ => FileSystemError::Io(error),
+    }
+}
+
+/// Collects output in memory, reported as not a terminal unless built with [`MemoryOutput::with_tty`]
+///
+/// Clones share the same buffer, so a test can keep one and hand another to
+/// the command.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryOutput {
+    buffer: Arc<Mutex<String>>,
+    tty: bool,
+}
+
+impl MemoryOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the output as an interactive terminal or not, to exercise terminal-only behavior
+    pub fn with_tty(mut self, tty: bool) -> Self {
+        self.tty = tty;
+        self
+    }
+
+    /// Everything written so far
+    pub fn contents(&self) -> String {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl Output for MemoryOutput {
+    fn write_raw(&self, text: &str) -> Result<(), GroundhogError> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner()).push_str(text);
+        Ok(())
+    }
+
+    fn is_tty(&self) -> bool {
+        self.tty
+    }
+}
+
+/// Adapts an [`Output`] to [`std::io::Write`], for writers such as
+/// [`JsonLinesWriter`](crate::cli::output::JsonLinesWriter)
+pub struct OutputWrite<'a>(pub &'a dyn Output);
+
+impl Write for OutputWrite<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = std::str::from_utf8(buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.0.write_raw(text).map_err(|e| match e.is_output_closed() {
+            true => std::io::Error::from(ErrorKind::BrokenPipe),
+            false => std::io::Error::other(e.to_string()),
+        })?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::JsonLinesWriter;
+
+    #[test]
+    fn test_memory_output_shares_buffer_between_clones() {
+        let output = MemoryOutput::new();
+        let shared: Arc<dyn Output> = Arc::new(output.clone());
+
+        shared.write_line("first").unwrap();
+        shared.write_raw("second").unwrap();
+
+        assert_eq!(output.contents(), "first\nsecond");
+        assert!(!shared.is_tty());
+    }
+
+    #[test]
+    fn test_json_lines_writer_through_output() {
+        let output = MemoryOutput::new();
+        let mut writer = JsonLinesWriter::new(OutputWrite(&output));
+        writer.delta("hi");
+        writer.finish().unwrap();
+
+        assert_eq!(output.contents(), "{\"delta\":\"hi\"}\n");
+    }
+}
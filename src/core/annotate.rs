@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+/// Notes keyed by the 1-based line number they annotate
+pub type LineNotes = BTreeMap<usize, String>;
+
+/// Prefix each line of `text` with its line number, counting from `first_line`
+///
+/// This is the form sent to the model so its notes can refer to lines.
+pub fn number_lines(text: &str, first_line: usize) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| format!("{}: {}\n", first_line + index, line))
+        .collect()
+}
+
+/// Parse `<line>: <note>` replies into notes
+///
+/// Accepts an optional list bullet and `L`/`line` prefix, and `:` or `|` as
+/// the separator. Other lines are ignored and repeated notes for the same
+/// line are joined.
+pub fn parse_notes(reply: &str) -> LineNotes {
+    let mut notes = LineNotes::new();
+
+    for line in reply.lines() {
+        let line = line.trim().trim_start_matches(['-', '*']).trim_start();
+        let lower = line.to_ascii_lowercase();
+        let rest = if lower.starts_with("line") {
+            &line[4..]
+        } else if lower.starts_with('l') {
+            &line[1..]
+        } else {
+            line
+        }
+        .trim_start();
+
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let Ok(number) = rest[..digits].parse::<usize>() else { continue };
+        let Some(note) = rest[digits..].trim_start().strip_prefix([':', '|']) else { continue };
+        let note = note.trim();
+        if number == 0 || note.is_empty() {
+            continue;
+        }
+
+        notes
+            .entry(number)
+            .and_modify(|existing: &mut String| {
+                existing.push(' ');
+                existing.push_str(note);
+            })
+            .or_insert_with(|| note.to_string());
+    }
+
+    notes
+}
+
+/// Render `content` with line numbers and each note on a `+` line after the line it annotates
+///
+/// Notes for lines past the end of the file are placed after the last line.
+pub fn interleave(content: &str, notes: &LineNotes) -> String {
+    let line_count = content.lines().count();
+    let width = line_count.max(1).to_string().len();
+    let mut output = String::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        output.push_str(&format!("{:>width$} | {}\n", number, line));
+
+        let end = if number == line_count { usize::MAX } else { number };
+        for note in notes.range(number..=end).map(|(_, note)| note) {
+            output.push_str(&format!("{:>width$} + {}\n", "", note));
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main() {\n    println!(\"hi\");\n}\n";
+
+    #[test]
+    fn test_number_lines_from_offset() {
+        assert_eq!(number_lines("a\nb\n", 10), "10: a\n11: b\n");
+    }
+
+    #[test]
+    fn test_parse_notes_accepts_common_forms() {
+        let reply = "Here are the notes:\n1: Entry point\n- L2: Prints a greeting\nline 2 | to stdout\n* 9: past the end\n0: ignored\n";
+        let notes = parse_notes(reply);
+
+        assert_eq!(notes.len(), 3);
+        assert_eq!(notes[&1], "Entry point");
+        assert_eq!(notes[&2], "Prints a greeting to stdout");
+        assert_eq!(notes[&9], "past the end");
+    }
+
+    #[test]
+    fn test_interleave_places_notes_after_their_lines() {
+        let notes = LineNotes::from([(1, "Entry point".to_string()), (2, "Prints a greeting".to_string())]);
+
+        assert_eq!(
+            interleave(SOURCE, &notes),
+            "1 | fn main() {\n  + Entry point\n2 |     println!(\"hi\");\n  + Prints a greeting\n3 | }\n"
+        );
+    }
+
+    #[test]
+    fn test_interleave_appends_out_of_range_notes_and_pads_numbers() {
+        let content: String = (1..=10).map(|n| format!("line {}\n", n)).collect();
+        let notes = LineNotes::from([(10, "last".to_string()), (42, "beyond".to_string())]);
+        let rendered = interleave(&content, &notes);
+
+        assert!(rendered.starts_with(" 1 | line 1\n"));
+        assert!(rendered.ends_with("10 | line 10\n   + last\n   + beyond\n"));
+    }
+
+    #[test]
+    fn test_interleave_without_notes_only_numbers_lines() {
+        assert_eq!(interleave("a\nb", &LineNotes::new()), "1 | a\n2 | b\n");
+    }
+}
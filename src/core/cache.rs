@@ -0,0 +1,202 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::core::services::CompletionRequest;
+use crate::infrastructure::error::{FileSystemError, GroundhogError};
+
+/// Version of the cache key and entry format
+///
+/// Bump it whenever prompts or the way responses are post-processed change,
+/// so entries written by older builds are no longer served.
+pub const CACHE_VERSION: u32 = 1;
+
+/// Extension of cache entry files
+const ENTRY_EXTENSION: &str = "json";
+
+/// Everything a cached response depends on
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheKey<'a> {
+    pub version: u32,
+    pub provider: &'a str,
+    pub model: &'a str,
+    /// Prompt template the prompt was built from, if any
+    pub template: Option<&'a str>,
+    /// Prompt, seed, sampling parameters and response schema
+    pub request: &'a CompletionRequest,
+}
+
+impl CacheKey<'_> {
+    /// Stable hex digest naming the entry file
+    ///
+    /// FNV-1a over the key's JSON form, which unlike the std hasher does
+    /// not change between builds.
+    pub fn digest(&self) -> String {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        let hash = json.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("{:016x}", hash)
+    }
+}
+
+/// Stored response
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    version: u32,
+    response: String,
+}
+
+/// Number and total size of cached responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// On-disk cache of AI responses, one JSON file per [`CacheKey`]
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory holding the entries
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cached response for `key`, if any
+    ///
+    /// Unreadable entries and entries from another [`CACHE_VERSION`] are misses.
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        let path = self.entry_path(key);
+        let entry: Entry = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+        if entry.version != key.version {
+            debug!(path = %path.display(), version = entry.version, "Ignoring cache entry from another version");
+            return None;
+        }
+        Some(entry.response)
+    }
+
+    /// Store `response` for `key`, creating the cache directory if needed
+    pub fn put(&self, key: &CacheKey, response: &str) -> Result<(), GroundhogError> {
+        let entry = Entry {
+            version: key.version,
+            response: response.to_string(),
+        };
+        fs::create_dir_all(&self.dir).map_err(|e| self.error(&self.dir, e))?;
+        let path = self.entry_path(key);
+        let json = serde_json::to_string(&entry).unwrap_or_default();
+        fs::write(&path, json).map_err(|e| self.error(&path, e))?;
+        Ok(())
+    }
+
+    /// Count the entries and their size; a missing directory is an empty cache
+    pub fn stats(&self) -> Result<CacheStats, GroundhogError> {
+        let mut stats = CacheStats::default();
+        for path in self.entries()? {
+            stats.entries += 1;
+            stats.bytes += fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        }
+        Ok(stats)
+    }
+
+    /// Remove every entry, returning how many were removed
+    pub fn clear(&self) -> Result<usize, GroundhogError> {
+        let entries = self.entries()?;
+        for path in &entries {
+            fs::remove_file(path).map_err(|e| self.error(path, e))?;
+        }
+        Ok(entries.len())
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.digest()).with_extension(ENTRY_EXTENSION)
+    }
+
+    /// Entry files in the cache directory
+    fn entries(&self) -> Result<Vec<PathBuf>, GroundhogError> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(self.error(&self.dir, e)),
+        };
+        Ok(dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION))
+            .collect())
+    }
+
+    fn error(&self, path: &Path, error: std::io::Error) -> GroundhogError {
+        match error.kind() {
+            ErrorKind::PermissionDenied => FileSystemError::PermissionDenied { path: path.to_path_buf() }.into(),
+            _ => FileSystemError::Io(error).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn key<'a>(model: &'a str, template: Option<&'a str>, request: &'a CompletionRequest) -> CacheKey<'a> {
+        CacheKey {
+            version: CACHE_VERSION,
+            provider: "Local",
+            model,
+            template,
+            request,
+        }
+    }
+
+    #[test]
+    fn test_changing_model_or_template_misses() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path());
+        let request = CompletionRequest {
+            prompt: "Explain closures".to_string(),
+            ..CompletionRequest::default()
+        };
+
+        cache.put(&key("llama3", None, &request), "cached").unwrap();
+
+        assert_eq!(cache.get(&key("llama3", None, &request)).as_deref(), Some("cached"));
+        assert_eq!(cache.get(&key("mistral", None, &request)), None);
+        assert_eq!(cache.get(&key("llama3", Some("Explain {input} briefly"), &request)), None);
+        let older = CacheKey {
+            version: CACHE_VERSION + 1,
+            ..key("llama3", None, &request)
+        };
+        assert_eq!(cache.get(&older), None);
+    }
+
+    #[test]
+    fn test_stats_and_clear_count_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path().join("cache"));
+        assert_eq!(cache.stats().unwrap(), CacheStats::default());
+
+        for prompt in ["one", "two"] {
+            let request = CompletionRequest {
+                prompt: prompt.to_string(),
+                ..CompletionRequest::default()
+            };
+            cache.put(&key("llama3", None, &request), "reply").unwrap();
+        }
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.entries, 2);
+        assert!(stats.bytes > 0);
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
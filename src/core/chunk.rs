@@ -0,0 +1,144 @@
+use std::ops::Range;
+
+use crate::core::tokens::{estimate_tokens, token_spans};
+
+/// Split `text` into byte ranges of at most `max_tokens` estimated tokens each
+///
+/// Consecutive chunks share `overlap` tokens so context carries across
+/// boundaries. The first chunk starts at 0 and the last ends at `text.len()`,
+/// so together the ranges cover the whole text. Text without any tokens
+/// produces no chunks.
+pub fn split(text: &str, max_tokens: usize, overlap: usize) -> Vec<Range<usize>> {
+    let spans: Vec<Range<usize>> = token_spans(text).collect();
+    if spans.is_empty() {
+        return Vec::new();
+    }
+
+    let max_tokens = max_tokens.max(1);
+    let overlap = overlap.min(max_tokens - 1);
+
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    loop {
+        let last = (first + max_tokens).min(spans.len());
+        let start = if first == 0 { 0 } else { spans[first].start };
+        let end = if last == spans.len() { text.len() } else { spans[last].start };
+        chunks.push(start..end);
+
+        if last == spans.len() {
+            break;
+        }
+        first = last - overlap;
+    }
+
+    chunks
+}
+
+/// Split `text` into byte ranges of whole lines of at most `max_tokens` estimated tokens each
+///
+/// Unlike [`split`] no line is ever shared by two chunks; a line over the
+/// limit gets a chunk of its own. The ranges cover the whole text, and text
+/// without any tokens produces no chunks.
+pub fn split_lines(text: &str, max_tokens: usize) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut tokens = 0;
+    for line in text.split_inclusive('\n') {
+        let line_tokens = estimate_tokens(line);
+        if tokens > 0 && tokens + line_tokens > max_tokens {
+            chunks.push(start..end);
+            start = end;
+            tokens = 0;
+        }
+        tokens += line_tokens;
+        end += line.len();
+    }
+    if tokens > 0 {
+        chunks.push(start..text.len());
+    }
+    chunks
+}
+
+/// 1-based line numbers covered by a byte range of `text`
+pub fn line_span(text: &str, range: &Range<usize>) -> (usize, usize) {
+    let first = text[..range.start].matches('\n').count() + 1;
+    let body = text[range.clone()].trim_end_matches('\n');
+    (first, first + body.matches('\n').count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text of `n` single-token words
+    fn words(n: usize) -> String {
+        (0..n).map(|i| format!("w{}", i % 10)).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_empty_text_has_no_chunks() {
+        assert!(split("", 10, 2).is_empty());
+        assert!(split("  \n ", 10, 2).is_empty());
+    }
+
+    #[test]
+    fn test_text_smaller_than_one_chunk() {
+        let text = words(5);
+        assert_eq!(split(&text, 10, 2), vec![0..text.len()]);
+    }
+
+    #[test]
+    fn test_exact_multiple_without_overlap() {
+        let text = words(20);
+        let chunks = split(&text, 10, 0);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].start, 0);
+        assert_eq!(chunks[0].end, chunks[1].start);
+        assert_eq!(chunks[1].end, text.len());
+        for chunk in &chunks {
+            assert_eq!(estimate_tokens(&text[chunk.clone()]), 10);
+        }
+    }
+
+    #[test]
+    fn test_exact_chunk_size_is_single_chunk() {
+        let text = words(10);
+        assert_eq!(split(&text, 10, 3), vec![0..text.len()]);
+    }
+
+    #[test]
+    fn test_overlap_repeats_trailing_tokens() {
+        let text = "a b c d e f g h i j";
+        let chunks: Vec<&str> = split(text, 4, 1).into_iter().map(|r| &text[r]).collect();
+
+        assert_eq!(chunks, vec!["a b c d ", "d e f g ", "g h i j"]);
+    }
+
+    #[test]
+    fn test_overlap_is_clamped_below_chunk_size() {
+        let text = words(6);
+        let chunks = split(&text, 2, 5);
+
+        // With overlap clamped to 1 each chunk advances by one token
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks.last().unwrap().end, text.len());
+    }
+
+    #[test]
+    fn test_split_lines_keeps_lines_whole() {
+        let text = "a b c\nd e\nf g h i j\nk\n";
+        let chunks: Vec<&str> = split_lines(text, 5).into_iter().map(|r| &text[r]).collect();
+
+        assert_eq!(chunks, vec!["a b c\nd e\n", "f g h i j\n", "k\n"]);
+        assert!(split_lines(" \n\n", 5).is_empty());
+    }
+
+    #[test]
+    fn test_line_span() {
+        let text = "one\ntwo\nthree\nfour\n";
+        assert_eq!(line_span(text, &(0..8)), (1, 2));
+        assert_eq!(line_span(text, &(8..text.len())), (3, 4));
+    }
+}
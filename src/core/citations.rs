@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use crate::core::markdown::code_blocks;
+
+/// Instruction sent to the model ahead of the content when citations are requested
+pub const INSTRUCTION: &str = "Cite your sources. Mark each statement that relies on a source with a numbered marker such as [1], and end with the sources, one per line, as the marker followed by the title and link.";
+
+/// Line introducing the collected sources
+pub const FOOTNOTES_HEADING: &str = "Footnotes:";
+
+/// Shown for a marker whose source the model did not list
+const MISSING_SOURCE: &str = "(source not given)";
+
+/// Headings models put above their sources, compared ignoring case, markup and a trailing colon
+const SOURCE_HEADINGS: [&str; 5] = ["references", "sources", "citations", "footnotes", "bibliography"];
+
+/// A numbered reference and the source it stands for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+    pub number: usize,
+    /// Title or link listed for the number, or `None` when the model cited it without listing it
+    pub source: Option<String>,
+}
+
+/// Numbers of the `[n]` markers in `text`, each once, in order of first appearance
+///
+/// Brackets inside code blocks, link text such as `[1](url)` and indexing
+/// such as `items[1]` are not markers.
+pub fn markers(text: &str) -> Vec<usize> {
+    let blocks = code_blocks(text);
+    let mut numbers = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if blocks.iter().any(|block| block.contains_line(index)) {
+            continue;
+        }
+        for number in line_markers(line) {
+            if !numbers.contains(&number) {
+                numbers.push(number);
+            }
+        }
+    }
+    numbers
+}
+
+/// Markers on a single line, in order
+fn line_markers(line: &str) -> Vec<usize> {
+    let mut numbers = Vec::new();
+    // End of the last index expression, so `grid[0][1]` is indexing throughout while `[1][2]` are two markers
+    let mut indexing_end = None;
+    for (start, _) in line.match_indices('[') {
+        let Some((digits, rest)) = line[start + 1..].split_once(']') else { break };
+        let indexing = indexing_end == Some(start)
+            || line[..start].chars().next_back().is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if indexing {
+            indexing_end = Some(start + digits.len() + 2);
+            continue;
+        }
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) || rest.starts_with('(') {
+            continue;
+        }
+        if let Ok(number) = digits.parse() {
+            numbers.push(number);
+        }
+    }
+    numbers
+}
+
+/// Split the sources the model listed at the end of `text` from the rest
+///
+/// Sources are lines such as `[1] The Rust Book, https://doc.rust-lang.org/book/`,
+/// optionally as list items or with a colon after the marker, after a
+/// heading such as `## References`. Only the trailing run of such lines
+/// counts, so a paragraph that happens to start with a marker stays in the
+/// text. The citations returned cover every marker in the remaining text and
+/// every listed source, ordered by number.
+pub fn collect(text: &str) -> (String, Vec<Citation>) {
+    let lines: Vec<&str> = text.lines().collect();
+    let blocks = code_blocks(text);
+    let mut sources = BTreeMap::new();
+    let mut body_end = lines.len();
+
+    for (index, line) in lines.iter().enumerate().rev() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if blocks.iter().any(|block| block.contains_line(index)) {
+            break;
+        }
+        if let Some((number, source)) = source_line(line) {
+            // Walking backwards, so the first listing of a number wins
+            sources.insert(number, source);
+            body_end = index;
+        } else {
+            if !sources.is_empty() && is_source_heading(line) {
+                body_end = index;
+            }
+            break;
+        }
+    }
+
+    let body = lines[..body_end].join("\n").trim_end().to_string();
+    let mut cited: BTreeMap<usize, Option<String>> = markers(&body).into_iter().map(|number| (number, None)).collect();
+    for (number, source) in sources {
+        cited.insert(number, Some(source));
+    }
+    let citations = cited.into_iter().map(|(number, source)| Citation { number, source }).collect();
+    (body, citations)
+}
+
+/// Gather the citations of `text` into a footnotes section at its end
+///
+/// Text without markers or listed sources is returned unchanged.
+pub fn with_footnotes(text: &str) -> String {
+    let (body, citations) = collect(text);
+    if citations.is_empty() {
+        return text.to_string();
+    }
+
+    let mut output = format!("{}\n\n{}\n", body, FOOTNOTES_HEADING);
+    for citation in citations {
+        let source = citation.source.as_deref().unwrap_or(MISSING_SOURCE);
+        output.push_str(&format!("[{}] {}\n", citation.number, source));
+    }
+    output
+}
+
+/// Parse a source line such as `[1] Title, https://...`, `- [2]: https://...` or `[^3] Title`
+fn source_line(line: &str) -> Option<(usize, String)> {
+    let line = line.trim();
+    let line = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).unwrap_or(line);
+    let (digits, source) = line.strip_prefix('[')?.split_once(']')?;
+    let digits = digits.strip_prefix('^').unwrap_or(digits);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let source = source.strip_prefix(':').unwrap_or(source).trim();
+    if source.is_empty() {
+        return None;
+    }
+    Some((digits.parse().ok()?, source.to_string()))
+}
+
+/// Whether `line` is a heading such as `## References`, `**Sources:**` or `Citations:`
+fn is_source_heading(line: &str) -> bool {
+    let heading = line.trim().trim_start_matches('#').trim_matches(|c: char| c == '*' || c == '_' || c.is_whitespace());
+    let heading = heading.strip_suffix(':').unwrap_or(heading).trim_end_matches(['*', '_']);
+    SOURCE_HEADINGS.iter().any(|name| heading.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLY: &str = "Rust frees memory when its owner goes out of scope [1]. Borrowing lets code use a value without taking ownership [2], and the borrow checker enforces this at compile time [1][3].\n\n```rust\nlet v = vec![1, 2, 3];\nprintln!(\"{}\", v[1]);\n```\n\nSee [the book](https://doc.rust-lang.org/book/) and `items[0]`.\n\n## References\n\n[1] The Rust Programming Language, ch. 4, https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html\n- [2]: Rust by Example, Borrowing, https://doc.rust-lang.org/rust-by-example/scope/borrow.html\n";
+
+    #[test]
+    fn test_markers_skip_code_links_and_indexing() {
+        assert_eq!(markers(REPLY), vec![1, 2, 3]);
+        assert_eq!(markers("a[1] grid[0][1] [2](https://example.com) [x] [] [4"), Vec::<usize>::new());
+        assert_eq!(markers("Claim [12], again [12], then [3]."), vec![12, 3]);
+    }
+
+    #[test]
+    fn test_collect_takes_trailing_sources_and_heading() {
+        let (body, citations) = collect(REPLY);
+
+        assert!(body.ends_with("See [the book](https://doc.rust-lang.org/book/) and `items[0]`."), "{}", body);
+        assert!(!body.contains("References"));
+        assert_eq!(citations, vec![
+            Citation {
+                number: 1,
+                source: Some("The Rust Programming Language, ch. 4, https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html".to_string()),
+            },
+            Citation {
+                number: 2,
+                source: Some("Rust by Example, Borrowing, https://doc.rust-lang.org/rust-by-example/scope/borrow.html".to_string()),
+            },
+            Citation { number: 3, source: None },
+        ]);
+    }
+
+    #[test]
+    fn test_leading_marker_in_body_is_not_a_source() {
+        let text = "[1] Ownership is checked at compile time.\n\nMore detail follows.";
+        let (body, citations) = collect(text);
+
+        assert_eq!(body, text);
+        assert_eq!(citations, vec![Citation { number: 1, source: None }]);
+    }
+
+    #[test]
+    fn test_with_footnotes_appends_section() {
+        let footnoted = with_footnotes("Closures capture their environment [1].\n\nSources:\n[1] https://doc.rust-lang.org/book/ch13-01-closures.html");
+
+        assert_eq!(
+            footnoted,
+            "Closures capture their environment [1].\n\nFootnotes:\n[1] https://doc.rust-lang.org/book/ch13-01-closures.html\n"
+        );
+        assert_eq!(with_footnotes("No sources here."), "No sources here.");
+    }
+}
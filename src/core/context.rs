@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use tracing::{debug, warn};
+
+use crate::core::tokens::{estimate_tokens, token_spans};
+use crate::infrastructure::error::{CommandError, FileSystemError, GroundhogError};
+
+/// Line ending a context file cut short by the token budget
+pub const TRUNCATED_MARKER: &str = "... (truncated)";
+
+/// Files matching any of `patterns`, sorted and without duplicates
+///
+/// Directories and entries that cannot be read while globbing are skipped.
+pub fn expand(patterns: &[String]) -> Result<Vec<PathBuf>, GroundhogError> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let entries = glob::glob(pattern).map_err(|e| CommandError::InvalidArguments {
+            command: "explain".to_string(),
+            message: format!("invalid --context pattern '{}': {}", pattern, e),
+        })?;
+        paths.extend(entries.filter_map(Result::ok).filter(|path| path.is_file()));
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Concatenate `paths` under `--- <path> ---` headers within `budget` estimated tokens
+///
+/// Files larger than `max_bytes` or not valid UTF-8 are skipped with a
+/// warning. The file that crosses the budget is cut at a token boundary and
+/// marked truncated, and the files after it are left out.
+pub fn build(paths: &[PathBuf], max_bytes: u64, budget: usize) -> Result<String, GroundhogError> {
+    let mut context = String::new();
+    let mut used = 0;
+
+    for (index, path) in paths.iter().enumerate() {
+        let size = std::fs::metadata(path).map_err(FileSystemError::Io)?.len();
+        if size > max_bytes {
+            warn!(path = %path.display(), size, limit = max_bytes, "Skipping context file larger than max_file_size");
+            continue;
+        }
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                warn!(path = %path.display(), "Skipping context file that is not UTF-8 text");
+                continue;
+            }
+            Err(e) => return Err(FileSystemError::Io(e).into()),
+        };
+
+        let header = format!("--- {} ---\n", path.display());
+        let cost = estimate_tokens(&header) + estimate_tokens(&content);
+        if used + cost <= budget {
+            context.push_str(&header);
+            context.push_str(&content);
+            if !content.ends_with('\n') {
+                context.push('\n');
+            }
+            used += cost;
+            continue;
+        }
+
+        let remaining = budget.saturating_sub(used + estimate_tokens(&header));
+        if remaining > 0 {
+            let end = token_spans(&content).nth(remaining - 1).map_or(0, |span| span.end);
+            context.push_str(&header);
+            context.push_str(&content[..end]);
+            context.push('\n');
+            context.push_str(TRUNCATED_MARKER);
+            context.push('\n');
+        }
+        warn!(budget, omitted = paths.len() - index - 1, "Context files exceed the token budget, truncating");
+        break;
+    }
+
+    debug!(files = paths.len(), estimated_tokens = estimate_tokens(&context), "Built context");
+    Ok(context)
+}
+
+/// Section put ahead of a prompt to give the model related files
+pub fn prompt_section(context: &str) -> String {
+    format!("Related files for context:\n\n{}\n", context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_expand_matches_files_recursively() {
+        let dir = TempDir::new().unwrap();
+        let lib = write(&dir, "src/lib.rs", "pub mod a;");
+        let nested = write(&dir, "src/a/mod.rs", "pub fn a() {}");
+        write(&dir, "README.md", "# readme");
+
+        let root = dir.path().display();
+        let patterns = vec![format!("{}/src/**/*.rs", root), format!("{}/src/lib.rs", root)];
+        assert_eq!(expand(&patterns).unwrap(), vec![nested, lib]);
+    }
+
+    #[test]
+    fn test_expand_rejects_invalid_pattern() {
+        assert!(matches!(
+            expand(&["src/[".to_string()]),
+            Err(GroundhogError::Command(CommandError::InvalidArguments { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_build_adds_headers_and_skips_large_files() {
+        let dir = TempDir::new().unwrap();
+        let small = write(&dir, "a.rs", "fn a() {}");
+        let large = write(&dir, "b.rs", &"x".repeat(64));
+
+        let context = build(&[small.clone(), large], 32, 1000).unwrap();
+        assert_eq!(context, format!("--- {} ---\nfn a() {{}}\n", small.display()));
+    }
+
+    #[test]
+    fn test_build_truncates_at_budget() {
+        let dir = TempDir::new().unwrap();
+        let first = write(&dir, "a.txt", "one two three four five six");
+        let second = write(&dir, "b.txt", "never included");
+
+        let header_tokens = estimate_tokens(&format!("--- {} ---\n", first.display()));
+        let context = build(&[first, second.clone()], u64::MAX, header_tokens + 2).unwrap();
+
+        assert!(context.ends_with("\none two\n... (truncated)\n"), "{}", context);
+        assert!(!context.contains(&second.display().to_string()));
+    }
+}
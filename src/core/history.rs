@@ -0,0 +1,125 @@
+use crate::core::models::{ChatMessage, Conversation, Role};
+use crate::core::tokens::estimate_tokens;
+
+/// Tokens counted for each message on top of its content, for the role label and separators
+const MESSAGE_OVERHEAD: usize = 4;
+
+/// Estimated tokens `message` takes up in a prompt
+pub fn message_tokens(message: &ChatMessage) -> usize {
+    estimate_tokens(&message.content) + MESSAGE_OVERHEAD
+}
+
+/// Messages of `conversation` to send when at most `budget` tokens fit
+///
+/// System prompts are always kept and count against the budget first.
+/// Notes from the application are never sent, so they are left out and not
+/// counted. Whole turns, a user message and the replies up to the next one,
+/// are dropped oldest first, so no reply is sent without its question. The
+/// latest turn is kept even when it alone is over budget. The conversation
+/// itself is left untouched, so the full history can still be shown and
+/// exported.
+pub fn window(conversation: &Conversation, budget: usize) -> Vec<&ChatMessage> {
+    let messages: Vec<&ChatMessage> = conversation.messages.iter().filter(|m| m.role != Role::Note).collect();
+    let mut total: usize = messages.iter().map(|message| message_tokens(message)).sum();
+
+    let turns: Vec<&ChatMessage> = messages.iter().copied().filter(|m| m.role != Role::System).collect();
+    let turn_starts: Vec<usize> = turns
+        .iter()
+        .enumerate()
+        .filter(|(index, message)| *index == 0 || message.role == Role::User)
+        .map(|(index, _)| index)
+        .collect();
+    let mut first = 0;
+    for turn in turn_starts.windows(2) {
+        if total <= budget {
+            break;
+        }
+        total -= turns[turn[0]..turn[1]].iter().map(|message| message_tokens(message)).sum::<usize>();
+        first = turn[1];
+    }
+
+    let mut turn_index = 0;
+    messages
+        .into_iter()
+        .filter(|message| {
+            if message.role == Role::System {
+                return true;
+            }
+            turn_index += 1;
+            turn_index > first
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(turns: usize) -> Conversation {
+        let mut conversation = Conversation::new();
+        for turn in 1..=turns {
+            conversation.push_user(format!("question {}", turn));
+            conversation.push_assistant(format!("answer {}", turn));
+        }
+        conversation
+    }
+
+    fn contents(messages: &[&ChatMessage]) -> Vec<String> {
+        messages.iter().map(|message| message.content.clone()).collect()
+    }
+
+    #[test]
+    fn test_conversation_within_budget_is_kept_whole() {
+        let conversation = conversation(3);
+        let total = conversation.messages.iter().map(message_tokens).sum();
+
+        assert_eq!(window(&conversation, total).len(), conversation.len());
+    }
+
+    #[test]
+    fn test_oldest_turns_dropped_whole() {
+        let conversation = conversation(4);
+        let budget = 3 * message_tokens(&conversation.messages[0]);
+
+        assert_eq!(contents(&window(&conversation, budget)), vec!["question 4", "answer 4"]);
+    }
+
+    #[test]
+    fn test_system_prompt_kept_with_newest_turns_when_over_budget() {
+        let mut conversation = Conversation::new();
+        conversation.push_system("Answer like a patient teacher.");
+        for turn in 1..=4 {
+            conversation.push_user(format!("question {}", turn));
+            conversation.push_assistant(format!("answer {}", turn));
+        }
+        let budget = message_tokens(&conversation.messages[0]) + 4 * message_tokens(&conversation.messages[1]);
+
+        assert_eq!(
+            contents(&window(&conversation, budget)),
+            vec!["Answer like a patient teacher.", "question 3", "answer 3", "question 4", "answer 4"]
+        );
+    }
+
+    #[test]
+    fn test_notes_left_out_and_not_counted() {
+        let mut conversation = conversation(2);
+        conversation.push_note("Request failed: ".repeat(50));
+        conversation.push_user("question 3");
+        let budget = 6 * message_tokens(&conversation.messages[0]);
+
+        assert_eq!(
+            contents(&window(&conversation, budget)),
+            vec!["question 1", "answer 1", "question 2", "answer 2", "question 3"]
+        );
+    }
+
+    #[test]
+    fn test_latest_message_kept_when_over_budget() {
+        let mut conversation = conversation(1);
+        conversation.push_user("a very long question ".repeat(50));
+
+        let kept = window(&conversation, 1);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].role, Role::User);
+    }
+}
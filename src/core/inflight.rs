@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+use tracing::debug;
+
+use crate::infrastructure::error::GroundhogError;
+
+/// Completions being requested right now, keyed by a digest of the whole request
+///
+/// The first caller for a key sends the request; callers arriving while it is
+/// in flight wait for its reply instead of sending their own. Failures are not
+/// shared: waiting callers then send the request themselves, so each gets its
+/// own error.
+#[derive(Debug, Default)]
+pub struct InFlight {
+    calls: Mutex<HashMap<String, watch::Receiver<Option<String>>>>,
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `request` for `key`, or share the reply of an identical request already in flight
+    pub async fn run<F, Fut>(&self, key: &str, request: F) -> Result<String, GroundhogError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, GroundhogError>>,
+    {
+        let sender = {
+            let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+            match calls.get(key) {
+                Some(receiver) => Err(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    calls.insert(key.to_string(), receiver);
+                    Ok(sender)
+                }
+            }
+        };
+
+        let sender = match sender {
+            Ok(sender) => sender,
+            Err(mut receiver) => {
+                let shared = receiver.wait_for(Option::is_some).await.ok().and_then(|reply| reply.clone());
+                if let Some(reply) = shared {
+                    debug!(key, "Shared reply of an identical request");
+                    return Ok(reply);
+                }
+                debug!(key, "Identical request failed, sending own request");
+                return request().await;
+            }
+        };
+
+        // Removes the entry even if this caller is dropped mid-request
+        let _entry = Entry { calls: &self.calls, key };
+        let result = request().await;
+        if let Ok(reply) = &result {
+            let _ = sender.send(Some(reply.clone()));
+        }
+        result
+    }
+}
+
+/// Removes a key from the in-flight map when its request ends
+struct Entry<'a> {
+    calls: &'a Mutex<HashMap<String, watch::Receiver<Option<String>>>>,
+    key: &'a str,
+}
+
+impl Drop for Entry<'_> {
+    fn drop(&mut self) {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).remove(self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::infrastructure::error::NetworkError;
+
+    #[tokio::test]
+    async fn test_failures_are_not_shared() {
+        let in_flight = InFlight::new();
+        let calls = AtomicUsize::new(0);
+        let request = || async {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            match call {
+                0 => Err(NetworkError::Timeout { timeout_ms: 1000 }.into()),
+                _ => Ok("second try".to_string()),
+            }
+        };
+        let (first, second) = tokio::join!(in_flight.run("prompt", request), in_flight.run("prompt", request));
+
+        assert!(first.is_err());
+        assert_eq!(second.unwrap(), "second try");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(in_flight.calls.lock().unwrap().is_empty());
+    }
+}
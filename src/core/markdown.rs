@@ -0,0 +1,113 @@
+/// A fenced code block found in markdown text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// Language from the opening fence info string, if any
+    pub language: Option<String>,
+    /// Zero-based line index of the opening fence
+    pub start_line: usize,
+    /// Zero-based line index of the closing fence, or the last line when unterminated
+    pub end_line: usize,
+    /// Whether the block has a closing fence
+    pub closed: bool,
+}
+
+impl CodeBlock {
+    /// Check whether a zero-based line index falls inside this block, fences included
+    pub fn contains_line(&self, line: usize) -> bool {
+        (self.start_line..=self.end_line).contains(&line)
+    }
+
+    /// Check whether a zero-based line index holds code, i.e. is inside the fences
+    pub fn contains_code_line(&self, line: usize) -> bool {
+        let end = if self.closed { self.end_line } else { self.end_line + 1 };
+        line > self.start_line && line < end
+    }
+}
+
+/// Return the info string if the line is a code fence
+fn fence_info(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    trimmed
+        .strip_prefix("```")
+        .or_else(|| trimmed.strip_prefix("~~~"))
+        .map(str::trim)
+}
+
+/// Find all fenced code blocks in markdown text
+///
+/// An unterminated block extends to the end of the text and is marked as not closed,
+/// which lets callers render partial (e.g. streaming) output while still detecting it.
+pub fn code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(usize, Option<String>)> = None;
+    let mut last_line = 0;
+
+    for (index, line) in text.lines().enumerate() {
+        last_line = index;
+        let Some(info) = fence_info(line) else {
+            continue;
+        };
+
+        match open.take() {
+            Some((start_line, language)) => blocks.push(CodeBlock {
+                language,
+                start_line,
+                end_line: index,
+                closed: true,
+            }),
+            None => {
+                let language = info.split_whitespace().next().map(str::to_string);
+                open = Some((index, language));
+            }
+        }
+    }
+
+    if let Some((start_line, language)) = open {
+        blocks.push(CodeBlock {
+            language,
+            start_line,
+            end_line: last_line,
+            closed: false,
+        });
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_blocks_detects_language_and_bounds() {
+        let text = "intro\n```rust\nfn main() {}\n```\noutro";
+        let blocks = code_blocks(text);
+
+        assert_eq!(
+            blocks,
+            vec![CodeBlock {
+                language: Some("rust".to_string()),
+                start_line: 1,
+                end_line: 3,
+                closed: true,
+            }]
+        );
+        assert!(blocks[0].contains_line(2));
+        assert!(!blocks[0].contains_line(4));
+    }
+
+    #[test]
+    fn test_code_blocks_unterminated() {
+        let blocks = code_blocks("text\n```\nlet x = 1;\nlet y = 2;");
+
+        assert_eq!(blocks.len(), 1);
+        assert!(!blocks[0].closed);
+        assert_eq!(blocks[0].language, None);
+        assert_eq!(blocks[0].end_line, 3);
+    }
+
+    #[test]
+    fn test_code_blocks_none() {
+        assert!(code_blocks("just prose\nwith lines").is_empty());
+    }
+}
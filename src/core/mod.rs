@@ -1,5 +1,20 @@
+pub mod annotate;
+pub mod cache;
+pub mod chunk;
+pub mod citations;
+pub mod context;
+pub mod history;
+pub mod inflight;
+pub mod markdown;
 pub mod models;
+pub mod patch;
+pub mod providers;
+pub mod rate_limit;
+pub mod request;
+pub mod retry;
+pub mod schema;
 pub mod services;
+pub mod tokens;
 
 // Re-export commonly used types
 pub use models::*; 
\ No newline at end of file
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::infrastructure::error::GroundhogError;
+
 /// Represents a command execution context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandContext {
@@ -34,6 +36,35 @@ pub struct CommandResult {
     pub success: bool,
     pub message: Option<String>,
     pub duration_ms: u64,
+    /// Command-specific output for machine-readable formats
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    /// Error details when the command failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorDetails>,
+}
+
+/// Machine-readable description of a command failure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetails {
+    /// Error category, e.g. "config" or "filesystem"
+    pub kind: String,
+    /// Specific error within the category, e.g. "not_found"; empty in results from older versions
+    #[serde(default)]
+    pub code: String,
+    pub message: String,
+    pub exit_code: i32,
+}
+
+impl From<&GroundhogError> for ErrorDetails {
+    fn from(error: &GroundhogError) -> Self {
+        Self {
+            kind: error.kind().to_string(),
+            code: error.code().to_string(),
+            message: error.to_string(),
+            exit_code: error.exit_code(),
+        }
+    }
 }
 
 impl CommandResult {
@@ -42,6 +73,8 @@ impl CommandResult {
             success: true,
             message: None,
             duration_ms: 0,
+            data: None,
+            error: None,
         }
     }
     
@@ -50,6 +83,8 @@ impl CommandResult {
             success: true,
             message: Some(message.into()),
             duration_ms: 0,
+            data: None,
+            error: None,
         }
     }
     
@@ -58,6 +93,19 @@ impl CommandResult {
             success: false,
             message: Some(message.into()),
             duration_ms: 0,
+            data: None,
+            error: None,
+        }
+    }
+    
+    /// Failed result for `error`, with its user message and error details
+    ///
+    /// Data attached with [`GroundhogError::WithData`] is kept. Chain
+    /// [`CommandResult::with_duration`] to record how long the command ran.
+    pub fn from_error(error: &GroundhogError) -> Self {
+        match error {
+            GroundhogError::WithData { data, source } => Self::from_error(source).with_data(data.clone()),
+            _ => Self::failure(error.user_message()).with_error(ErrorDetails::from(error)),
         }
     }
     
@@ -66,6 +114,21 @@ impl CommandResult {
         self
     }
     
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+    
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+    
+    pub fn with_error(mut self, error: ErrorDetails) -> Self {
+        self.error = Some(error);
+        self
+    }
+    
     /// Check if the command was successful
     pub fn is_success(&self) -> bool {
         self.success
@@ -77,11 +140,119 @@ impl CommandResult {
     }
 }
 
+/// Author of a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+    /// Instructions for the model, kept however long the conversation grows
+    System,
+    /// A note from the application, such as an error, shown but never sent to the model
+    Note,
+}
+
+impl Role {
+    /// Lowercase name, as used in serialized messages and provider requests
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::System => "system",
+            Role::Note => "note",
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single message in a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+    pub timestamp: std::time::SystemTime,
+}
+
+impl ChatMessage {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            timestamp: std::time::SystemTime::now(),
+        }
+    }
+}
+
+/// A chat between the user and the assistant, shared by the TUI and CLI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: String,
+    pub messages: Vec<ChatMessage>,
+    pub created_at: std::time::SystemTime,
+}
+
+impl Conversation {
+    /// Start an empty conversation with a random id
+    pub fn new() -> Self {
+        Self {
+            id: format!("{:016x}", rand::random::<u64>()),
+            messages: Vec::new(),
+            created_at: std::time::SystemTime::now(),
+        }
+    }
+    
+    /// Append a message from the user
+    pub fn push_user(&mut self, content: impl Into<String>) {
+        self.messages.push(ChatMessage::new(Role::User, content));
+    }
+    
+    /// Append a message from the assistant
+    pub fn push_assistant(&mut self, content: impl Into<String>) {
+        self.messages.push(ChatMessage::new(Role::Assistant, content));
+    }
+    
+    /// Append a system prompt with instructions for the model
+    pub fn push_system(&mut self, content: impl Into<String>) {
+        self.messages.push(ChatMessage::new(Role::System, content));
+    }
+    
+    /// Append a note from the application, such as an error
+    pub fn push_note(&mut self, content: impl Into<String>) {
+        self.messages.push(ChatMessage::new(Role::Note, content));
+    }
+    
+    /// The last `n` messages, or all of them if there are fewer
+    pub fn last_n(&self, n: usize) -> &[ChatMessage] {
+        &self.messages[self.messages.len().saturating_sub(n)..]
+    }
+    
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::time::Duration;
 
+    use crate::infrastructure::error::FileSystemError;
+
     #[test]
     fn test_command_context_new() {
         let context = CommandContext::new("explain");
@@ -131,6 +302,40 @@ mod tests {
         assert_eq!(result.message, Some("Something went wrong".to_string()));
     }
 
+    #[test]
+    fn test_command_result_from_error() {
+        let error = GroundhogError::from(FileSystemError::NotFound { path: "missing.rs".into() });
+        let result = CommandResult::from_error(&error).with_duration(Duration::from_millis(7));
+
+        assert!(result.is_failure());
+        assert_eq!(result.message, Some(error.user_message()));
+        assert_eq!(result.duration_ms, 7);
+        assert_eq!(
+            result.error,
+            Some(ErrorDetails {
+                kind: "filesystem".to_string(),
+                code: "not_found".to_string(),
+                message: "File system operation failed".to_string(),
+                exit_code: 66,
+            })
+        );
+    }
+
+    #[test]
+    fn test_command_result_from_error_keeps_data() {
+        let source = GroundhogError::from(FileSystemError::NotFound { path: "missing.rs".into() });
+        let plain = CommandResult::from_error(&source);
+        let error = GroundhogError::WithData {
+            data: serde_json::json!({ "checks": [] }),
+            source: Box::new(source),
+        };
+        let result = CommandResult::from_error(&error);
+
+        assert_eq!(error.exit_code(), 66);
+        assert_eq!(result.data, Some(serde_json::json!({ "checks": [] })));
+        assert_eq!((result.message, result.error), (plain.message, plain.error));
+    }
+
     #[test]
     fn test_command_result_with_duration() {
         let duration = Duration::from_millis(150);
@@ -162,4 +367,78 @@ mod tests {
         assert_eq!(result.message, deserialized.message);
         assert_eq!(result.duration_ms, deserialized.duration_ms);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_command_result_optional_fields_serialization() {
+        let plain = serde_json::to_value(CommandResult::success()).unwrap();
+        assert!(plain.get("data").is_none());
+        assert!(plain.get("error").is_none());
+
+        let failed = CommandResult::failure("boom").with_error(ErrorDetails {
+            kind: "config".to_string(),
+            code: "invalid_format".to_string(),
+            message: "boom".to_string(),
+            exit_code: 65,
+        });
+        let value = serde_json::to_value(&failed).unwrap();
+        assert_eq!(value["error"]["kind"], "config");
+        assert_eq!(value["error"]["code"], "invalid_format");
+        assert_eq!(value["error"]["exit_code"], 65);
+
+        // Results written before error codes existed still parse
+        let older: CommandResult = serde_json::from_str(
+            r#"{"success":false,"message":"boom","duration_ms":0,"error":{"kind":"config","message":"boom","exit_code":65}}"#,
+        )
+        .unwrap();
+        assert_eq!(older.error.unwrap().code, "");
+    }
+
+    #[test]
+    fn test_conversation_serialization_round_trip() {
+        let mut conversation = Conversation::new();
+        conversation.push_user("What is ownership?");
+        conversation.push_assistant("Every value has a single owner.");
+        
+        let serialized = serde_json::to_string(&conversation).unwrap();
+        let deserialized: Conversation = serde_json::from_str(&serialized).unwrap();
+        
+        assert_eq!(conversation, deserialized);
+        let value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(value["messages"][0]["role"], "user");
+        assert_eq!(value["messages"][1]["role"], "assistant");
+    }
+
+    #[test]
+    fn test_role_display() {
+        assert_eq!(Role::User.to_string(), "user");
+        assert_eq!(Role::Assistant.to_string(), "assistant");
+        assert_eq!(Role::System.to_string(), "system");
+        assert_eq!(Role::Note.to_string(), "note");
+    }
+
+    #[test]
+    fn test_role_serde_round_trip() {
+        for role in [Role::User, Role::Assistant, Role::System, Role::Note] {
+            let serialized = serde_json::to_string(&role).unwrap();
+            assert_eq!(serialized, format!("\"{}\"", role));
+            assert_eq!(serde_json::from_str::<Role>(&serialized).unwrap(), role);
+        }
+        assert!(serde_json::from_str::<Role>("\"User\"").is_err());
+    }
+
+    #[test]
+    fn test_conversation_last_n() {
+        let mut conversation = Conversation::new();
+        assert!(conversation.last_n(3).is_empty());
+        
+        conversation.push_user("one");
+        conversation.push_assistant("two");
+        conversation.push_user("three");
+        
+        let contents = |messages: &[ChatMessage]| messages.iter().map(|m| m.content.clone()).collect::<Vec<_>>();
+        assert!(conversation.last_n(0).is_empty());
+        assert_eq!(contents(conversation.last_n(2)), vec!["two", "three"]);
+        assert_eq!(contents(conversation.last_n(3)), vec!["one", "two", "three"]);
+        assert_eq!(contents(conversation.last_n(10)), vec!["one", "two", "three"]);
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::Path;
+
+use crate::infrastructure::error::ParseError;
+
+/// A line of a hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkLine {
+    /// Unchanged line, present before and after
+    Context(String),
+    /// Line only in the original file
+    Remove(String),
+    /// Line only in the changed file
+    Add(String),
+}
+
+/// A contiguous change to a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// 1-based line of the original file the hunk starts at, as given in its header
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    /// Lines the hunk expects in the original file
+    pub fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    /// Lines the hunk leaves in the changed file
+    pub fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Add(text) => Some(text.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Changes to a single file, in file order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch {
+    pub hunks: Vec<Hunk>,
+}
+
+impl Patch {
+    /// Render as a unified diff of `path`, with hunk headers recounted from the hunk lines
+    pub fn to_unified(&self, path: &str) -> String {
+        let mut output = format!("--- a/{}\n+++ b/{}\n", path, path);
+        let mut offset: isize = 0;
+        for hunk in &self.hunks {
+            let (old_len, new_len) = (hunk.old_lines().len(), hunk.new_lines().len());
+            let new_start = (hunk.old_start as isize + offset).max(0);
+            output.push_str(&format!("@@ -{},{} +{},{} @@\n", hunk.old_start, old_len, new_start, new_len));
+            for line in &hunk.lines {
+                let (marker, text) = match line {
+                    HunkLine::Context(text) => (' ', text),
+                    HunkLine::Remove(text) => ('-', text),
+                    HunkLine::Add(text) => ('+', text),
+                };
+                output.push(marker);
+                output.push_str(text);
+                output.push('\n');
+            }
+            offset += new_len as isize - old_len as isize;
+        }
+        output
+    }
+}
+
+/// Parse the hunks of a unified diff of one file
+///
+/// Text around the diff, such as a markdown fence or a sentence of prose,
+/// is ignored, as are the file headers. Hunks end after the number of lines
+/// their header gives; a hunk cut short is an error. Blank lines inside a
+/// hunk count as empty context lines, since trailing spaces are often lost.
+pub fn parse(diff: &str) -> Result<Patch, ParseError> {
+    let mut patch = Patch::default();
+    // Lines still expected in the last hunk, from the original and changed file
+    let (mut old, mut new) = (0, 0);
+
+    for (index, line) in diff.lines().enumerate() {
+        let number = index + 1;
+        let Some(hunk) = patch.hunks.last_mut().filter(|_| old + new > 0) else {
+            if let Some(header) = line.strip_prefix("@@ ") {
+                let (old_start, old_len, new_len) = parse_hunk_header(header).ok_or_else(|| ParseError::Diff {
+                    line: number,
+                    message: format!("malformed hunk header '{}'", line),
+                })?;
+                patch.hunks.push(Hunk { old_start, lines: Vec::new() });
+                (old, new) = (old_len, new_len);
+            }
+            continue;
+        };
+
+        let parsed = match line.chars().next() {
+            None => HunkLine::Context(String::new()),
+            Some(' ') => HunkLine::Context(line[1..].to_string()),
+            Some('-') => HunkLine::Remove(line[1..].to_string()),
+            Some('+') => HunkLine::Add(line[1..].to_string()),
+            Some('\\') => continue,
+            _ => {
+                return Err(ParseError::Diff {
+                    line: number,
+                    message: format!("hunk ends early, {} original and {} changed lines short", old, new),
+                });
+            }
+        };
+        let fits = match parsed {
+            HunkLine::Context(_) => old > 0 && new > 0,
+            HunkLine::Remove(_) => old > 0,
+            HunkLine::Add(_) => new > 0,
+        };
+        if !fits {
+            return Err(ParseError::Diff {
+                line: number,
+                message: "hunk has more lines than its header gives".to_string(),
+            });
+        }
+        match parsed {
+            HunkLine::Context(_) => (old, new) = (old - 1, new - 1),
+            HunkLine::Remove(_) => old -= 1,
+            HunkLine::Add(_) => new -= 1,
+        }
+        hunk.lines.push(parsed);
+    }
+
+    if old + new > 0 {
+        return Err(ParseError::Diff {
+            line: diff.lines().count(),
+            message: format!("diff ends inside a hunk, {} original and {} changed lines short", old, new),
+        });
+    }
+    if patch.hunks.is_empty() {
+        return Err(ParseError::Diff {
+            line: 1,
+            message: "no hunks found".to_string(),
+        });
+    }
+    Ok(patch)
+}
+
+/// Parse `-a,b +c,d @@`, returning the original start and both lengths
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut ranges = header.split_whitespace();
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = range(old)?;
+    let (_, new_len) = range(new)?;
+    Some((old_start, old_len, new_len))
+}
+
+/// Apply `patch` to `content`, the contents of `path`
+///
+/// Each hunk must find its original lines, compared without trailing
+/// whitespace, after the previous hunk. The match closest to the line its
+/// header gives is used, so hunks whose line numbers are slightly off still
+/// apply. Nothing is changed unless every hunk applies. The patched text
+/// keeps the file's line endings, `\r\n` or `\n`.
+pub fn apply(path: &Path, content: &str, patch: &Patch) -> Result<String, ParseError> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut output: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut cursor = 0;
+
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let old = hunk.old_lines();
+        // An insertion's header gives the line it follows rather than the first line it replaces
+        let expected = if old.is_empty() { hunk.old_start } else { hunk.old_start.saturating_sub(1) };
+        let matches_at = |start: usize| {
+            start + old.len() <= lines.len()
+                && lines[start..start + old.len()].iter().zip(&old).all(|(line, old)| line.trim_end() == old.trim_end())
+        };
+        let start = (cursor..=lines.len())
+            .filter(|&start| matches_at(start))
+            .min_by_key(|&start| start.abs_diff(expected))
+            .ok_or_else(|| ParseError::PatchConflict {
+                path: path.to_path_buf(),
+                hunk: index + 1,
+                message: match old.first() {
+                    Some(first) => format!("expected '{}' near line {}", first.trim(), hunk.old_start),
+                    None => format!("line {} is past the end of the file", hunk.old_start),
+                },
+            })?;
+
+        output.extend(&lines[cursor..start]);
+        output.extend(hunk.new_lines().into_iter().map(|line| line.trim_end_matches('\r')));
+        cursor = start + old.len();
+    }
+    output.extend(&lines[cursor..]);
+
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut patched = output.join(newline);
+    if !output.is_empty() && (content.ends_with('\n') || content.is_empty()) {
+        patched.push_str(newline);
+    }
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "use std::fs;\n\nfn read(path: &str) -> String {\n    fs::read_to_string(path).unwrap()\n}\n\nfn main() {\n    println!(\"{}\", read(\"a.txt\"));\n}\n";
+
+    const REPLY: &str = "Here is the change:\n\n```diff\n--- a/main.rs\n+++ b/main.rs\n@@ -3,3 +3,3 @@\n-fn read(path: &str) -> String {\n-    fs::read_to_string(path).unwrap()\n+fn read(path: &str) -> std::io::Result<String> {\n+    fs::read_to_string(path)\n }\n@@ -7,3 +7,4 @@\n fn main() {\n-    println!(\"{}\", read(\"a.txt\"));\n+    let text = read(\"a.txt\").expect(\"a.txt is readable\");\n+    println!(\"{}\", text);\n }\n```\n";
+
+    #[test]
+    fn test_parse_ignores_prose_fences_and_headers() {
+        let patch = parse(REPLY).unwrap();
+
+        assert_eq!(patch.hunks.len(), 2);
+        assert_eq!(patch.hunks[0].old_start, 3);
+        assert_eq!(patch.hunks[0].old_lines().len(), 3);
+        assert_eq!(patch.hunks[1].new_lines(), vec![
+            "fn main() {",
+            "    let text = read(\"a.txt\").expect(\"a.txt is readable\");",
+            "    println!(\"{}\", text);",
+            "}",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_diffs() {
+        match parse("no diff here") {
+            Err(ParseError::Diff { message, .. }) => assert_eq!(message, "no hunks found"),
+            other => panic!("Expected ParseError::Diff, got {:?}", other),
+        }
+        match parse("@@ -1,3 +1,3 @@\n a\n-b\n+c\nnot a hunk line\n") {
+            Err(ParseError::Diff { line, .. }) => assert_eq!(line, 5),
+            other => panic!("Expected ParseError::Diff, got {:?}", other),
+        }
+        match parse("@@ -1 +1 @@\n-a\n") {
+            Err(ParseError::Diff { message, .. }) => assert!(message.contains("ends inside a hunk"), "{}", message),
+            other => panic!("Expected ParseError::Diff, got {:?}", other),
+        }
+        assert!(matches!(parse("@@ -x,1 +1 @@\n"), Err(ParseError::Diff { line: 1, .. })));
+    }
+
+    #[test]
+    fn test_apply_sample_file() {
+        let patched = apply(Path::new("main.rs"), SOURCE, &parse(REPLY).unwrap()).unwrap();
+
+        assert_eq!(
+            patched,
+            "use std::fs;\n\nfn read(path: &str) -> std::io::Result<String> {\n    fs::read_to_string(path)\n}\n\nfn main() {\n    let text = read(\"a.txt\").expect(\"a.txt is readable\");\n    println!(\"{}\", text);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_keeps_crlf_line_endings() {
+        let patched = apply(Path::new("main.rs"), &SOURCE.replace('\n', "\r\n"), &parse(REPLY).unwrap()).unwrap();
+
+        assert_eq!(patched, apply(Path::new("main.rs"), SOURCE, &parse(REPLY).unwrap()).unwrap().replace('\n', "\r\n"));
+    }
+
+    #[test]
+    fn test_apply_tolerates_shifted_line_numbers() {
+        let shifted = REPLY.replace("@@ -3,3 +3,3 @@", "@@ -5,3 +5,3 @@");
+        let expected = apply(Path::new("main.rs"), SOURCE, &parse(REPLY).unwrap()).unwrap();
+
+        assert_eq!(apply(Path::new("main.rs"), SOURCE, &parse(&shifted).unwrap()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_apply_rejects_mismatched_context() {
+        let changed = SOURCE.replace("unwrap()", "expect(\"readable\")");
+
+        match apply(Path::new("main.rs"), &changed, &parse(REPLY).unwrap()) {
+            Err(ParseError::PatchConflict { path, hunk, message }) => {
+                assert_eq!(path, Path::new("main.rs"));
+                assert_eq!(hunk, 1);
+                assert!(message.contains("near line 3"), "{}", message);
+            }
+            other => panic!("Expected ParseError::PatchConflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_to_unified_recounts_headers() {
+        let unified = parse(REPLY).unwrap().to_unified("main.rs");
+
+        assert!(unified.starts_with("--- a/main.rs\n+++ b/main.rs\n@@ -3,3 +3,3 @@\n"));
+        assert!(unified.contains("\n@@ -7,3 +7,4 @@\n fn main() {\n"));
+        assert_eq!(parse(&unified).unwrap(), parse(REPLY).unwrap());
+    }
+}
@@ -0,0 +1,518 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::{debug, instrument};
+
+use crate::core::models::Role;
+use crate::core::services::{Completion, CompletionRequest, Provider};
+use crate::infrastructure::config::{AiConfig, AiProvider};
+use crate::infrastructure::error::{GroundhogError, NetworkError, ParseError};
+use crate::infrastructure::http::HttpClient;
+use crate::infrastructure::logging::Redacted;
+
+/// Body of a provider's `models` endpoint
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: Option<String>,
+}
+
+/// Version header required by the Anthropic API
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Response size cap for Anthropic, which requires `max_tokens`
+const ANTHROPIC_MAX_TOKENS: u32 = 1024;
+
+/// Provider talking to an OpenAI, Anthropic or OpenAI-compatible local HTTP API
+#[derive(Clone)]
+pub struct HttpProvider {
+    kind: AiProvider,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    client: HttpClient,
+}
+
+impl std::fmt::Debug for HttpProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpProvider")
+            .field("kind", &self.kind)
+            .field("endpoint", &self.endpoint)
+            .field("model", &self.model)
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .field("timeout", &self.client.timeout())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpProvider {
+    /// Build a provider from the `[ai]` configuration section, on the shared HTTP client
+    pub fn from_config(ai: &AiConfig, timeout: Duration) -> Result<Self, GroundhogError> {
+        Ok(Self::with_client(ai, HttpClient::shared(timeout, ai.proxy.as_ref())?))
+    }
+
+    /// Build a provider from the `[ai]` configuration section that sends through `client`
+    pub fn with_client(ai: &AiConfig, client: HttpClient) -> Self {
+        let endpoint = ai
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| default_endpoint(&ai.provider).to_string());
+
+        Self {
+            kind: ai.provider.clone(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model: ai.model.clone(),
+            api_key: ai.resolve_api_key(),
+            client,
+        }
+    }
+
+    /// Model requests are sent to
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Ids of the models available from the provider's `models` endpoint
+    #[instrument(name = "provider.list_models", skip(self), fields(provider = ?self.kind))]
+    pub async fn list_models(&self) -> Result<Vec<String>, GroundhogError> {
+        let url = self.url("models");
+        let response = self.send(&url, self.client.get(&url)).await?;
+        let payload = response.text().await.map_err(|e| self.request_error(e))?;
+        let list: ModelList = serde_json::from_str(&payload).map_err(|e| ParseError::Response {
+            url: url.clone(),
+            line: e.line(),
+            column: e.column(),
+            source: e,
+        })?;
+        Ok(list.data.into_iter().filter_map(|model| model.id).collect())
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.endpoint, path)
+    }
+
+    /// Build the provider's `messages` array from role-tagged contents
+    ///
+    /// Anthropic takes system instructions as a separate top-level `system`
+    /// field, so for it they are returned separately and joined.
+    fn request_messages(&self, messages: &[(Role, &str)]) -> (Value, Option<String>) {
+        let (system, chat): (Vec<_>, Vec<_>) = messages
+            .iter()
+            .partition(|(role, _)| *role == Role::System && self.kind == AiProvider::Anthropic);
+
+        let messages = chat
+            .iter()
+            .map(|(role, content)| json!({ "role": role.as_str(), "content": content }))
+            .collect();
+        let system = (!system.is_empty()).then(|| {
+            system.iter().map(|(_, content)| *content).collect::<Vec<_>>().join("\n\n")
+        });
+        (messages, system)
+    }
+
+    /// Attach provider-specific authentication headers
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match (&self.kind, &self.api_key) {
+            (AiProvider::Anthropic, Some(key)) => request
+                .header("x-api-key", key)
+                .header("anthropic-version", ANTHROPIC_VERSION),
+            (AiProvider::Anthropic, None) => request.header("anthropic-version", ANTHROPIC_VERSION),
+            (_, Some(key)) => request.bearer_auth(key),
+            (_, None) => request,
+        }
+    }
+
+    /// Send a request and turn non-success statuses into network errors
+    async fn send(&self, url: &str, request: RequestBuilder) -> Result<Response, GroundhogError> {
+        let request = self.authorize(request).build().map_err(|e| self.request_error(e))?;
+        debug!(
+            url,
+            method = %request.method(),
+            headers = ?Redacted(request.headers()),
+            "Sending provider request"
+        );
+        let response = self.client.execute(request).await.map_err(|e| self.request_error(e))?;
+
+        let status = response.status();
+        debug!(
+            url,
+            status = status.as_u16(),
+            headers = ?Redacted(response.headers()),
+            "Provider responded"
+        );
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let message = response.text().await.unwrap_or_default();
+        Err(status_error(status, message).into())
+    }
+
+    /// Map a failed request to a network error, reporting the configured timeout
+    fn request_error(&self, error: reqwest::Error) -> GroundhogError {
+        self.client.error(error).into()
+    }
+}
+
+#[async_trait]
+impl Provider for HttpProvider {
+    #[instrument(name = "provider.complete", skip(self, request), fields(provider = ?self.kind, model = %self.model))]
+    async fn complete(&self, request: CompletionRequest) -> Result<Completion, GroundhogError> {
+        let (messages, system) = self.request_messages(&[(Role::User, &request.prompt)]);
+        let params = &request.params;
+        let (url, mut body) = match self.kind {
+            AiProvider::Anthropic => {
+                let mut body = json!({
+                    "model": self.model,
+                    "max_tokens": params.max_tokens.unwrap_or(ANTHROPIC_MAX_TOKENS),
+                    "messages": messages,
+                });
+                // Anthropic has no response format parameter, so the schema is stated as an instruction
+                let schema = request
+                    .response_schema
+                    .as_ref()
+                    .map(|schema| format!("Reply only with JSON matching this JSON schema:\n{}", schema));
+                let system = match (system, schema) {
+                    (Some(system), Some(schema)) => Some(format!("{}\n\n{}", system, schema)),
+                    (system, schema) => system.or(schema),
+                };
+                insert_some(&mut body, "system", system);
+                (self.url("messages"), body)
+            }
+            AiProvider::OpenAI | AiProvider::Local => {
+                let mut body = json!({ "model": self.model, "messages": messages });
+                // Anthropic has no seed parameter, so it is only sent here
+                insert_some(&mut body, "seed", request.seed);
+                insert_some(&mut body, "max_tokens", params.max_tokens);
+                insert_some(
+                    &mut body,
+                    "response_format",
+                    request.response_schema.as_ref().map(|schema| {
+                        json!({ "type": "json_schema", "json_schema": { "name": "response", "schema": schema } })
+                    }),
+                );
+                (self.url("chat/completions"), body)
+            }
+        };
+        insert_some(&mut body, "temperature", params.temperature);
+        insert_some(&mut body, "top_p", params.top_p);
+
+        let response = self.send(&url, self.client.post(&url).json(&body)).await?;
+        let payload = response.text().await.map_err(|e| self.request_error(e))?;
+        let value: Value = serde_json::from_str(&payload).map_err(|e| ParseError::Response {
+            url: url.clone(),
+            line: e.line(),
+            column: e.column(),
+            source: e,
+        })?;
+
+        let text = match self.kind {
+            AiProvider::Anthropic => value["content"][0]["text"].as_str(),
+            AiProvider::OpenAI | AiProvider::Local => value["choices"][0]["message"]["content"].as_str(),
+        };
+        let text = text.ok_or_else(|| NetworkError::Http {
+            status: 200,
+            message: "response did not contain completion text".to_string(),
+            source: None,
+        })?;
+
+        Ok(Completion { text: text.to_string() })
+    }
+
+    /// List models, which checks connectivity and authentication without generating text
+    #[instrument(name = "provider.ping", skip(self), fields(provider = ?self.kind))]
+    async fn ping(&self) -> Result<(), GroundhogError> {
+        let url = self.url("models");
+        self.send(&url, self.client.get(&url)).await?;
+        Ok(())
+    }
+}
+
+/// Base URL used when `ai.endpoint` is not set
+pub fn default_endpoint(provider: &AiProvider) -> &'static str {
+    match provider {
+        AiProvider::OpenAI => "https://api.openai.com/v1",
+        AiProvider::Anthropic => "https://api.anthropic.com/v1",
+        AiProvider::Local => "http://localhost:11434/v1",
+    }
+}
+
+/// Add `key` to a JSON request body when the optional parameter is set
+fn insert_some(body: &mut Value, key: &str, value: Option<impl Into<Value>>) {
+    if let (Some(value), Some(body)) = (value, body.as_object_mut()) {
+        body.insert(key.to_string(), value.into());
+    }
+}
+
+/// Map an HTTP status to the matching network error
+fn status_error(status: StatusCode, message: String) -> NetworkError {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => NetworkError::AuthenticationFailed,
+        _ => NetworkError::Http {
+            status: status.as_u16(),
+            message,
+            source: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::core::services::AIService;
+    use crate::infrastructure::config::AiParams;
+
+    fn config(provider: AiProvider, server: &MockServer) -> AiConfig {
+        AiConfig {
+            api_key: Some("secret".to_string()),
+            endpoint: Some(format!("{}/v1", server.uri())),
+            ..AiConfig::new(provider, "test-model")
+        }
+    }
+
+    fn service(ai: &AiConfig) -> AIService {
+        let provider = HttpProvider::from_config(ai, Duration::from_secs(5)).unwrap();
+        AIService::new().with_provider(Arc::new(provider))
+    }
+
+    #[tokio::test]
+    async fn test_ping_success_measures_latency() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })).set_delay(Duration::from_millis(20)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let latency = service(&config(AiProvider::Local, &server)).ping().await.unwrap();
+        assert!(latency >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_ping_unauthorized() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let result = service(&config(AiProvider::OpenAI, &server)).ping().await;
+        assert!(matches!(
+            result,
+            Err(GroundhogError::Network(NetworkError::AuthenticationFailed))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_returns_ids() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{ "id": "gpt-4o" }, { "id": "gpt-4o-mini" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::from_config(&config(AiProvider::OpenAI, &server), Duration::from_secs(5)).unwrap();
+        assert_eq!(provider.list_models().await.unwrap(), vec!["gpt-4o", "gpt-4o-mini"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_without_model_list_is_a_parse_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "object": "list" })))
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::from_config(&config(AiProvider::OpenAI, &server), Duration::from_secs(5)).unwrap();
+        assert!(matches!(
+            provider.list_models().await,
+            Err(GroundhogError::Parse(ParseError::Response { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_debug_masks_api_key() {
+        let ai = AiConfig {
+            api_key: Some("sk-secret".to_string()),
+            ..AiConfig::new(AiProvider::OpenAI, "gpt-4o")
+        };
+        let provider = HttpProvider::from_config(&ai, Duration::from_secs(5)).unwrap();
+
+        let formatted = format!("{provider:?}");
+        assert!(!formatted.contains("sk-secret"));
+        assert!(formatted.contains(r#"api_key: Some("***")"#));
+    }
+
+    #[test]
+    fn test_request_messages_map_roles() {
+        let messages = [(Role::System, "Be brief"), (Role::User, "ownership"), (Role::Assistant, "moves")];
+        let provider = |kind| {
+            let ai = AiConfig::new(kind, "m");
+            HttpProvider::from_config(&ai, Duration::from_secs(5)).unwrap()
+        };
+
+        let (openai, system) = provider(AiProvider::OpenAI).request_messages(&messages);
+        assert_eq!(openai[0], json!({ "role": "system", "content": "Be brief" }));
+        assert_eq!(openai[2]["role"], "assistant");
+        assert_eq!(system, None);
+
+        let (anthropic, system) = provider(AiProvider::Anthropic).request_messages(&messages);
+        assert_eq!(anthropic, json!([
+            { "role": "user", "content": "ownership" },
+            { "role": "assistant", "content": "moves" },
+        ]));
+        assert_eq!(system.as_deref(), Some("Be brief"));
+    }
+
+    #[tokio::test]
+    async fn test_ping_without_provider_fails() {
+        assert!(AIService::new().ping().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_openai_completion() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({ "model": "test-model" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "role": "assistant", "content": "Ownership explained" } }]
+            })))
+            .mount(&server)
+            .await;
+
+        let text = service(&config(AiProvider::OpenAI, &server))
+            .generate_explanation("ownership")
+            .await
+            .unwrap();
+        assert_eq!(text, "Ownership explained");
+    }
+
+    #[tokio::test]
+    async fn test_openai_completion_sends_seed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({ "seed": 42 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "role": "assistant", "content": "Seeded" } }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let text = service(&config(AiProvider::OpenAI, &server))
+            .with_seed(Some(42))
+            .generate_explanation("ownership")
+            .await
+            .unwrap();
+        assert_eq!(text, "Seeded");
+    }
+
+    #[tokio::test]
+    async fn test_json_schema_constrains_and_checks_replies() {
+        let schema = json!({
+            "type": "object",
+            "required": ["summary"],
+            "properties": { "summary": { "type": "string" } }
+        });
+        let reply = |content: &str| {
+            ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "role": "assistant", "content": content } }]
+            }))
+        };
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "messages": [{ "content": "conforming" }],
+                "response_format": { "type": "json_schema", "json_schema": { "schema": schema } }
+            })))
+            .respond_with(reply(r#"{"summary": "Values have one owner"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "messages": [{ "content": "mismatched" }] })))
+            .respond_with(reply(r#"{"summary": 42}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let service = service(&config(AiProvider::OpenAI, &server)).with_response_schema(Some(schema.clone()));
+
+        let text = service.generate_explanation("conforming").await.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), json!({ "summary": "Values have one owner" }));
+
+        match service.generate_explanation("mismatched").await {
+            Err(GroundhogError::Parse(ParseError::SchemaMismatch { pointer, message })) => {
+                assert_eq!(pointer, "/summary");
+                assert_eq!(message, "expected string, found number");
+            }
+            other => panic!("Expected ParseError::SchemaMismatch, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_completion_sends_params() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(json!({ "max_tokens": 64, "temperature": 0.5 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "content": [{ "type": "text", "text": "Tuned" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let params = AiParams {
+            temperature: Some(0.5),
+            max_tokens: Some(64),
+            top_p: None,
+        };
+        let text = service(&config(AiProvider::Anthropic, &server))
+            .with_params(params)
+            .generate_explanation("ownership")
+            .await
+            .unwrap();
+        assert_eq!(text, "Tuned");
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_completion_uses_api_key_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "secret"))
+            .and(header("anthropic-version", ANTHROPIC_VERSION))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "content": [{ "type": "text", "text": "Borrowing explained" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let text = service(&config(AiProvider::Anthropic, &server))
+            .generate_explanation("borrowing")
+            .await
+            .unwrap();
+        assert_eq!(text, "Borrowing explained");
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::infrastructure::config::RateLimitConfig;
+
+/// Client-side token bucket keeping requests under a provider's rate limit
+///
+/// The bucket holds up to `burst` tokens and refills continuously at the
+/// configured rate. Each request takes one token, waiting for it when the
+/// bucket is empty; waiters are served in the order they arrived.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    /// Tokens added per second
+    refill_rate: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_minute` on average, with up to `burst` sent back to back
+    ///
+    /// Both values are raised to at least one.
+    pub fn new(requests_per_minute: u32, burst: u32) -> Self {
+        let capacity = f64::from(burst.max(1));
+        Self {
+            capacity,
+            refill_rate: f64::from(requests_per_minute.max(1)) / 60.0,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Build a limiter from the `[ai.rate_limit]` configuration section
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        Self::new(config.requests_per_minute, config.burst)
+    }
+
+    /// Wait until a request may be sent, taking one token
+    pub async fn acquire(&self) {
+        let mut bucket = self.bucket.lock().await;
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_rate).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens < 1.0 {
+            let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_rate);
+            debug!(wait_ms = wait.as_millis() as u64, "Rate limit reached, waiting");
+            // Holding the lock while sleeping keeps later callers queued behind this one
+            tokio::time::sleep(wait).await;
+            bucket.tokens = 1.0;
+            bucket.updated = Instant::now();
+        }
+        bucket.tokens -= 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_beyond_burst_wait_for_refill() {
+        let limiter = RateLimiter::new(60, 2);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+
+        // Two requests go out at once, the other three one second apart
+        assert_eq!(start.elapsed(), Duration::from_secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_time_refills_up_to_burst() {
+        let limiter = RateLimiter::new(600, 3);
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+    }
+}
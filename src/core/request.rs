@@ -0,0 +1,361 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::context;
+use crate::core::services::{Completion, Usage};
+use crate::infrastructure::config::{AiParams, STDIN_PATH};
+
+/// Placeholder in a prompt template replaced by the input text
+pub const TEMPLATE_PLACEHOLDER: &str = "{input}";
+
+/// How detailed an explanation should be
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplainDepth {
+    /// One sentence
+    Brief,
+    /// A paragraph
+    Normal,
+    /// A thorough walkthrough
+    Deep,
+}
+
+impl ExplainDepth {
+    /// Instruction sent to the model ahead of the content
+    pub fn instruction(self) -> &'static str {
+        match self {
+            ExplainDepth::Brief => "Answer in one sentence.",
+            ExplainDepth::Normal => "Answer in a paragraph.",
+            ExplainDepth::Deep => "Answer with a thorough walkthrough.",
+        }
+    }
+}
+
+impl fmt::Display for ExplainDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExplainDepth::Brief => "brief",
+            ExplainDepth::Normal => "normal",
+            ExplainDepth::Deep => "deep",
+        })
+    }
+}
+
+/// Layout of file explanations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplainFormat {
+    /// A prose explanation of the whole file
+    #[default]
+    Prose,
+    /// `<line>: <note>` annotations of the file's lines
+    Annotated,
+    /// A unified diff that makes the change described by the focus
+    Patch,
+}
+
+impl ExplainFormat {
+    fn is_prose(&self) -> bool {
+        *self == ExplainFormat::Prose
+    }
+}
+
+/// What the user asked to have explained
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplainInput {
+    /// A topic such as "ownership"
+    Topic(String),
+    /// A file on disk
+    File(PathBuf),
+    /// Text piped to standard input
+    Stdin,
+}
+
+impl ExplainInput {
+    /// Input for a path argument, where `-` means standard input
+    pub fn from_path(path: PathBuf) -> Self {
+        if path.as_os_str() == STDIN_PATH {
+            ExplainInput::Stdin
+        } else {
+            ExplainInput::File(path)
+        }
+    }
+
+    /// File the input is read from, `-` for standard input, or `None` for a topic
+    pub fn path(&self) -> Option<PathBuf> {
+        match self {
+            ExplainInput::Topic(_) => None,
+            ExplainInput::File(path) => Some(path.clone()),
+            ExplainInput::Stdin => Some(PathBuf::from(STDIN_PATH)),
+        }
+    }
+}
+
+impl fmt::Display for ExplainInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExplainInput::Topic(topic) => f.write_str(topic),
+            ExplainInput::File(path) => write!(f, "{}", path.display()),
+            ExplainInput::Stdin => f.write_str(STDIN_PATH),
+        }
+    }
+}
+
+/// Text of a file or stdin input, or of a part of it, once the caller has read it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputText {
+    pub text: String,
+    /// Line number of the text's first line in the input, counting from 1
+    pub first_line: usize,
+}
+
+impl InputText {
+    /// The whole input
+    pub fn whole(text: String) -> Self {
+        Self { text, first_line: 1 }
+    }
+}
+
+/// An explanation request, as sent to an [`Explainer`](crate::core::services::Explainer)
+///
+/// Without a language or sampling parameters, those of the
+/// [`AIService`](crate::core::services::AIService) the request is sent to
+/// are used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainRequest {
+    pub input: ExplainInput,
+    /// Language code to answer in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Sampling parameters, with unset values left to the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<AiParams>,
+    /// Prompt template for topics, with `{input}` standing for the topic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Related files put ahead of the input, see [`context::build`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Send the topic exactly as given, ignoring the template and context
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw: bool,
+    /// Topic a file explanation focuses on, or the change a patch makes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<String>,
+    /// Layout of a file explanation
+    #[serde(default, skip_serializing_if = "ExplainFormat::is_prose")]
+    pub format: ExplainFormat,
+    /// Text of a file or stdin input, filled in once it has been read
+    #[serde(skip)]
+    pub text: Option<InputText>,
+}
+
+impl ExplainRequest {
+    pub fn new(input: ExplainInput) -> Self {
+        Self {
+            input,
+            lang: None,
+            params: None,
+            template: None,
+            context: None,
+            raw: false,
+            focus: None,
+            format: ExplainFormat::default(),
+            text: None,
+        }
+    }
+
+    /// Request answers in the given language
+    pub fn with_lang(mut self, lang: Option<String>) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    /// Use the given sampling parameters
+    pub fn with_params(mut self, params: AiParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Wrap topics in a prompt template
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Include related files ahead of the input
+    pub fn with_context(mut self, context: Option<String>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Send the topic without a template, context or language instruction
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// Focus a file explanation on a topic, or describe the change a patch makes
+    pub fn with_focus(mut self, focus: Option<String>) -> Self {
+        self.focus = focus;
+        self
+    }
+
+    /// Lay a file explanation out as prose, annotations or a patch
+    pub fn with_format(mut self, format: ExplainFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Explain `text`, read from the file or stdin input
+    pub fn with_text(mut self, text: InputText) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    /// Topic being explained, if the input is a topic
+    pub fn topic(&self) -> Option<&str> {
+        match &self.input {
+            ExplainInput::Topic(topic) => Some(topic),
+            ExplainInput::File(_) | ExplainInput::Stdin => None,
+        }
+    }
+
+    /// Text sent for a topic request, with the template applied and any context ahead of it
+    ///
+    /// Raw requests send the topic alone. File and stdin inputs are read and
+    /// chunked by the caller instead.
+    pub fn topic_text(&self) -> Option<String> {
+        if self.raw {
+            return self.topic().map(str::to_string);
+        }
+        let topic = self.render(self.topic()?);
+        Some(match &self.context {
+            Some(related) => format!("{}\n{}", context::prompt_section(related), topic),
+            None => topic,
+        })
+    }
+
+    /// Text being explained, used to estimate prompt usage
+    pub fn subject(&self) -> &str {
+        match (&self.text, self.topic()) {
+            (Some(text), _) => &text.text,
+            (None, Some(topic)) => topic,
+            (None, None) => "",
+        }
+    }
+
+    /// Apply the template to `text`
+    ///
+    /// A template without `{input}` has the text appended after a blank line.
+    pub fn render(&self, text: &str) -> String {
+        match &self.template {
+            Some(template) if template.contains(TEMPLATE_PLACEHOLDER) => template.replace(TEMPLATE_PLACEHOLDER, text),
+            Some(template) => format!("{}\n\n{}", template, text),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// The completion produced for an [`ExplainRequest`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainResponse {
+    pub completion: Completion,
+    /// Input the completion explains
+    pub source: ExplainInput,
+    /// Tokens used by the prompt sent and the completion
+    #[serde(default)]
+    pub usage: Usage,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_input_variants() {
+        assert_eq!(ExplainInput::Topic("ownership".to_string()).to_string(), "ownership");
+        assert_eq!(ExplainInput::from_path(PathBuf::from("src/main.rs")), ExplainInput::File(PathBuf::from("src/main.rs")));
+        assert_eq!(ExplainInput::from_path(PathBuf::from("-")), ExplainInput::Stdin);
+        assert_eq!(ExplainInput::Stdin.to_string(), "-");
+        assert_eq!(ExplainInput::Stdin.path(), Some(PathBuf::from("-")));
+        assert_eq!(ExplainInput::Topic("ownership".to_string()).path(), None);
+    }
+
+    #[test]
+    fn test_explain_request_serializes_each_input() {
+        let request = ExplainRequest::new(ExplainInput::Topic("ownership".to_string()))
+            .with_template(Some("Explain {input}".to_string()));
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["input"], serde_json::json!({ "topic": "ownership" }));
+        assert_eq!(json["template"], "Explain {input}");
+        assert!(json.get("context").is_none());
+
+        let localized = ExplainRequest::new(ExplainInput::Topic("ownership".to_string()))
+            .with_lang(Some("de".to_string()))
+            .with_params(AiParams {
+                temperature: Some(0.5),
+                ..AiParams::default()
+            });
+        let json_localized = serde_json::to_value(&localized).unwrap();
+        assert_eq!(json_localized["lang"], "de");
+        assert_eq!(json_localized["params"]["temperature"], 0.5);
+
+        let file = serde_json::to_value(ExplainRequest::new(ExplainInput::File(PathBuf::from("a.rs")))).unwrap();
+        assert_eq!(file["input"], serde_json::json!({ "file": "a.rs" }));
+        assert!(file.get("format").is_none());
+        let patch = ExplainRequest::new(ExplainInput::File(PathBuf::from("a.rs")))
+            .with_focus(Some("add logging".to_string()))
+            .with_format(ExplainFormat::Patch)
+            .with_text(InputText::whole("fn a() {}\n".to_string()));
+        let json_patch = serde_json::to_value(&patch).unwrap();
+        assert_eq!(json_patch["format"], "patch");
+        assert_eq!(json_patch["focus"], "add logging");
+        assert!(json_patch.get("text").is_none());
+        let stdin = serde_json::to_value(ExplainRequest::new(ExplainInput::Stdin)).unwrap();
+        assert_eq!(stdin["input"], "stdin");
+
+        let round_trip: ExplainRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip, request);
+    }
+
+    #[test]
+    fn test_render_applies_template() {
+        let plain = ExplainRequest::new(ExplainInput::Topic("traits".to_string()));
+        assert_eq!(plain.render("traits"), "traits");
+
+        let templated = plain.clone().with_template(Some("Explain {input} to a beginner".to_string()));
+        assert_eq!(templated.render("traits"), "Explain traits to a beginner");
+
+        let prefixed = plain.with_template(Some("Be brief.".to_string()));
+        assert_eq!(prefixed.render("traits"), "Be brief.\n\ntraits");
+        assert_eq!(prefixed.topic_text(), Some("Be brief.\n\ntraits".to_string()));
+        assert_eq!(ExplainRequest::new(ExplainInput::Stdin).topic_text(), None);
+    }
+
+    #[test]
+    fn test_raw_topic_text_skips_template_and_context() {
+        let request = ExplainRequest::new(ExplainInput::Topic("exactly this".to_string()))
+            .with_template(Some("Explain {input}".to_string()))
+            .with_context(Some("--- a.rs ---\n".to_string()))
+            .with_raw(true);
+
+        assert_eq!(request.topic_text(), Some("exactly this".to_string()));
+        assert!(serde_json::to_value(&request).unwrap()["raw"].as_bool().unwrap());
+    }
+
+    #[test]
+    fn test_topic_text_puts_context_first() {
+        let request = ExplainRequest::new(ExplainInput::Topic("traits".to_string()))
+            .with_context(Some("--- a.rs ---\ntrait A {}\n".to_string()));
+
+        let topic = request.topic_text().unwrap();
+        assert!(topic.starts_with("Related files for context:\n\n--- a.rs ---\ntrait A {}\n"));
+        assert!(topic.ends_with("\ntraits"));
+    }
+}
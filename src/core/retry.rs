@@ -0,0 +1,298 @@
+use std::future::Future;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::infrastructure::config::RetryConfig;
+use crate::infrastructure::error::{GroundhogError, NetworkError};
+
+/// Exponential backoff with jitter for retrying failed AI requests
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of each delay that is randomized
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Build a policy from the `[performance.retry]` configuration section
+    pub fn from_config(config: &RetryConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            base_delay: Duration::from_millis(config.base_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms),
+            jitter: config.jitter.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `retry` (starting at 1), without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Jittered delay before retry number `retry`
+    ///
+    /// The delay falls in `[backoff * (1 - jitter), backoff]`.
+    pub fn delay<R: Rng + ?Sized>(&self, retry: u32, rng: &mut R) -> Duration {
+        let backoff = self.backoff(retry);
+        if self.jitter <= 0.0 {
+            return backoff;
+        }
+        let scale = 1.0 - self.jitter * rng.r#gen::<f64>();
+        backoff.mul_f64(scale)
+    }
+
+    /// Run `operation`, retrying transient failures with jittered backoff
+    pub async fn run<T, F, Fut>(&self, operation: F) -> Result<T, GroundhogError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GroundhogError>>,
+    {
+        self.run_with_rng(operation, &mut StdRng::from_entropy()).await
+    }
+
+    /// Like [`RetryPolicy::run`], drawing jitter from the given random number generator
+    pub async fn run_with_rng<T, F, Fut, R>(&self, mut operation: F, rng: &mut R) -> Result<T, GroundhogError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GroundhogError>>,
+        R: Rng + ?Sized,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(e) if attempt < self.max_attempts && is_retryable(&e) => {
+                    let delay = self.delay(attempt, rng);
+                    warn!(attempt, max_attempts = self.max_attempts, delay_ms = delay.as_millis() as u64, error = %e, "Request failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `operation` until it succeeds or `deadline` passes, retrying transient failures with jittered backoff
+    ///
+    /// Unlike [`RetryPolicy::run`] the number of attempts is not limited.
+    /// `on_retry` is called with each failure before waiting to retry, and an
+    /// attempt still running at the deadline fails with a timeout.
+    pub async fn run_until<T, F, Fut>(
+        &self,
+        deadline: Instant,
+        mut on_retry: impl FnMut(&GroundhogError),
+        mut operation: F,
+    ) -> Result<T, GroundhogError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GroundhogError>>,
+    {
+        let start = Instant::now();
+        let mut rng = StdRng::from_entropy();
+        let mut retry = 1;
+        loop {
+            let result = match tokio::time::timeout_at(deadline, operation()).await {
+                Ok(result) => result,
+                Err(_) => Err(NetworkError::Timeout {
+                    timeout_ms: deadline.saturating_duration_since(start).as_millis() as u64,
+                }
+                .into()),
+            };
+
+            match result {
+                Err(e) if is_retryable(&e) && Instant::now() < deadline => {
+                    let delay = self.delay(retry, &mut rng).min(deadline.saturating_duration_since(Instant::now()));
+                    debug!(retry, delay_ms = delay.as_millis() as u64, error = %e, "Not ready, retrying before deadline");
+                    on_retry(&e);
+                    tokio::time::sleep(delay).await;
+                    retry = retry.saturating_add(1);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&RetryConfig::default())
+    }
+}
+
+/// Whether an error is transient and worth retrying
+pub fn is_retryable(error: &GroundhogError) -> bool {
+    match error {
+        GroundhogError::Network(NetworkError::Timeout { .. } | NetworkError::ConnectionFailed { .. }) => true,
+        GroundhogError::Network(NetworkError::Http { status, .. }) => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::from_config(&RetryConfig {
+            max_attempts: 5,
+            base_delay_ms: 100,
+            max_delay_ms: 1_000,
+            jitter: 0.5,
+        })
+    }
+
+    /// Policy without delays so retry loops finish immediately
+    fn instant_policy() -> RetryPolicy {
+        RetryPolicy {
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            ..policy()
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = policy();
+        let delays: Vec<u128> = (1..=6).map(|retry| policy.backoff(retry).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_000, 1_000]);
+    }
+
+    #[test]
+    fn test_jittered_delays_within_bounds_for_seed() {
+        let policy = policy();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for retry in 1..=6 {
+            let backoff = policy.backoff(retry);
+            let delay = policy.delay(retry, &mut rng);
+            assert!(delay <= backoff, "retry {}: {:?} > {:?}", retry, delay, backoff);
+            assert!(delay >= backoff.mul_f64(0.5), "retry {}: {:?} too short", retry, delay);
+        }
+
+        // The same seed produces the same delays
+        let first: Vec<_> = (1..=4).map(|r| policy.delay(r, &mut StdRng::seed_from_u64(7))).collect();
+        let second: Vec<_> = (1..=4).map(|r| policy.delay(r, &mut StdRng::seed_from_u64(7))).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_zero_jitter_uses_exact_backoff() {
+        let policy = RetryPolicy { jitter: 0.0, ..policy() };
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(policy.delay(3, &mut rng), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(is_retryable(&NetworkError::Timeout { timeout_ms: 10 }.into()));
+        assert!(is_retryable(&NetworkError::Http { status: 503, message: String::new(), source: None }.into()));
+        assert!(is_retryable(&NetworkError::Http { status: 429, message: String::new(), source: None }.into()));
+        assert!(!is_retryable(&NetworkError::Http { status: 400, message: String::new(), source: None }.into()));
+        assert!(!is_retryable(&NetworkError::AuthenticationFailed.into()));
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_transient_failures() {
+        let calls = AtomicU32::new(0);
+        let result = instant_policy()
+            .run_with_rng(
+                || async {
+                    match calls.fetch_add(1, Ordering::SeqCst) {
+                        0 | 1 => Err(NetworkError::Timeout { timeout_ms: 10 }.into()),
+                        _ => Ok("done"),
+                    }
+                },
+                &mut StdRng::seed_from_u64(3),
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_stops_at_deadline() {
+        let calls = AtomicU32::new(0);
+        let mut retries = 0;
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(20),
+            ..policy()
+        };
+
+        let result: Result<(), _> = policy
+            .run_until(
+                Instant::now() + Duration::from_millis(100),
+                |_| retries += 1,
+                || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Err(NetworkError::Http { status: 503, message: String::new(), source: None }.into())
+                },
+            )
+            .await;
+
+        assert!(result.is_err());
+        // More attempts than max_attempts fit before the deadline
+        assert!(calls.load(Ordering::SeqCst) > 5);
+        assert_eq!(retries + 1, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_run_until_fails_fast_on_permanent_errors() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = instant_policy()
+            .run_until(Instant::now() + Duration::from_secs(5), |_| {}, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(NetworkError::AuthenticationFailed.into())
+            })
+            .await;
+
+        assert!(matches!(result, Err(GroundhogError::Network(NetworkError::AuthenticationFailed))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = instant_policy()
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(NetworkError::Http { status: 500, message: String::new(), source: None }.into())
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_run_does_not_retry_permanent_failures() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = instant_policy()
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(NetworkError::AuthenticationFailed.into())
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
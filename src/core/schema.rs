@@ -0,0 +1,213 @@
+use std::fmt;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::infrastructure::error::{GroundhogError, ParseError};
+use crate::infrastructure::fs;
+
+/// A value failing to match a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer of the offending value, `/` for the whole response
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(pointer: &str, message: impl Into<String>) -> Self {
+        Self {
+            pointer: if pointer.is_empty() { "/".to_string() } else { pointer.to_string() },
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+impl From<Violation> for ParseError {
+    fn from(violation: Violation) -> Self {
+        ParseError::SchemaMismatch {
+            pointer: violation.pointer,
+            message: violation.message,
+        }
+    }
+}
+
+/// Load a JSON schema file
+pub fn load(path: &Path) -> Result<Value, GroundhogError> {
+    let text = fs::read_text(path, u64::MAX)?;
+    serde_json::from_str(&text).map_err(|e| {
+        ParseError::Json {
+            input: path.display().to_string(),
+            line: Some(e.line()),
+            column: Some(e.column()),
+            source: e,
+        }
+        .into()
+    })
+}
+
+/// Parse a response as JSON and check it against `schema`
+///
+/// A response wrapped in a fenced code block is unwrapped first, since
+/// models often format JSON that way. A response that is not JSON at all,
+/// or does not match, is reported as [`ParseError::SchemaMismatch`].
+pub fn check_response(schema: &Value, response: &str) -> Result<Value, GroundhogError> {
+    let value: Value = serde_json::from_str(unfence(response))
+        .map_err(|e| ParseError::from(Violation::new("", format!("not valid JSON: {}", e))))?;
+
+    match violations(schema, &value).into_iter().next() {
+        None => Ok(value),
+        Some(violation) => Err(ParseError::from(violation).into()),
+    }
+}
+
+/// Every way `value` fails to match `schema`
+///
+/// Supports the commonly used subset of JSON Schema: `type`, `enum`,
+/// `const`, `properties`, `required`, `additionalProperties: false` and
+/// `items`. Other keywords are ignored.
+pub fn violations(schema: &Value, value: &Value) -> Vec<Violation> {
+    let mut found = Vec::new();
+    check(schema, value, "", &mut found);
+    found
+}
+
+fn check(schema: &Value, value: &Value, pointer: &str, found: &mut Vec<Violation>) {
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|name| has_type(value, name)) {
+            found.push(Violation::new(pointer, format!("expected {}, found {}", names.join(" or "), type_name(value))));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        found.push(Violation::new(pointer, format!("{} is not one of the allowed values", value)));
+    }
+    if let Some(constant) = schema.get("const")
+        && constant != value
+    {
+        found.push(Violation::new(pointer, format!("expected {}", constant)));
+    }
+
+    if let Value::Object(object) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                found.push(Violation::new(pointer, format!("missing required property '{}'", key)));
+            }
+        }
+        for (key, item) in object {
+            let child = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property) => check(property, item, &child, found),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    found.push(Violation::new(&child, "unexpected property"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{}/{}", pointer, index), found);
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => value.as_i64().is_some() || value.as_u64().is_some(),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// The contents of a fenced code block spanning the whole text, or the trimmed text itself
+fn unfence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else { return trimmed };
+    let Some(body) = rest.strip_suffix("```") else { return trimmed };
+    // Skip the language tag on the opening fence line
+    body.split_once('\n').map_or(body, |(_, code)| code).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["name", "tags"],
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string" },
+                "level": { "enum": ["brief", "deep"] },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "score": { "type": ["integer", "null"] }
+            }
+        })
+    }
+
+    #[test]
+    fn test_conforming_value_has_no_violations() {
+        let value = json!({ "name": "rust", "level": "deep", "tags": ["lang"], "score": null });
+        assert!(violations(&schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn test_violations_name_the_offending_value() {
+        let value = json!({ "level": "medium", "tags": ["lang", 3], "score": 1.5, "extra": true });
+
+        assert_eq!(
+            violations(&schema(), &value).iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "/: missing required property 'name'",
+                "/extra: unexpected property",
+                "/level: \"medium\" is not one of the allowed values",
+                "/score: expected integer or null, found number",
+                "/tags/1: expected string, found number",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_response_unwraps_code_fences() {
+        let response = "```json\n{\"name\": \"rust\", \"tags\": []}\n```";
+        assert_eq!(check_response(&schema(), response).unwrap()["name"], "rust");
+
+        match check_response(&schema(), "not json") {
+            Err(GroundhogError::Parse(ParseError::SchemaMismatch { pointer, message })) => {
+                assert_eq!(pointer, "/");
+                assert!(message.starts_with("not valid JSON"), "{}", message);
+            }
+            other => panic!("Expected ParseError::SchemaMismatch, got {:?}", other),
+        }
+    }
+}
@@ -1,17 +1,332 @@
-// Future: AI service integration, file processing services, etc.
-// This module will contain the core business logic services
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use crate::infrastructure::error::GroundhogError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
 
-/// Placeholder for future AI service integration
+use crate::core::annotate;
+use crate::core::cache::{CACHE_VERSION, CacheKey, ResponseCache};
+use crate::core::citations;
+use crate::core::context;
+use crate::core::history;
+use crate::core::inflight::InFlight;
+use crate::core::models::{Conversation, Role};
+use crate::core::providers::HttpProvider;
+use crate::core::rate_limit::RateLimiter;
+use crate::core::request::{ExplainDepth, ExplainFormat, ExplainRequest};
+use crate::core::retry::RetryPolicy;
+use crate::core::schema;
+use crate::core::tokens::{DEFAULT_CONTEXT_LIMIT, estimate_tokens};
+use crate::infrastructure::config::{AiConfig, AiParams, Config};
+use crate::infrastructure::error::{ConfigError, GroundhogError, InternalError};
+
+/// A request sent to an AI provider
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionRequest {
+    pub prompt: String,
+    /// Sampling seed for reproducible output, ignored by providers without support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Sampling parameters, with unset values left to the provider
+    #[serde(default)]
+    pub params: AiParams,
+    /// JSON schema the reply must match, for providers with structured output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
+}
+
+/// A completion returned by an AI provider
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+    pub text: String,
+}
+
+/// Token counts for a completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+}
+
+impl Usage {
+    /// Estimate usage from the prompt and completion text
+    pub fn estimate(prompt: &str, completion: &str) -> Self {
+        Self {
+            prompt_tokens: estimate_tokens(prompt),
+            completion_tokens: estimate_tokens(completion),
+        }
+    }
+}
+
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+/// Price of a model in US dollars per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+/// Published prices of hosted models, keyed by model name prefix
+///
+/// Dated variants such as `gpt-4o-2024-08-06` use the price of their prefix.
+pub const MODEL_PRICES: &[(&str, ModelPrice)] = &[
+    ("gpt-4o-mini", ModelPrice { prompt: 0.15, completion: 0.60 }),
+    ("gpt-4o", ModelPrice { prompt: 2.50, completion: 10.00 }),
+    ("gpt-4-turbo", ModelPrice { prompt: 10.00, completion: 30.00 }),
+    ("gpt-3.5-turbo", ModelPrice { prompt: 0.50, completion: 1.50 }),
+    ("claude-3-5-sonnet", ModelPrice { prompt: 3.00, completion: 15.00 }),
+    ("claude-3-5-haiku", ModelPrice { prompt: 0.80, completion: 4.00 }),
+    ("claude-3-opus", ModelPrice { prompt: 15.00, completion: 75.00 }),
+    ("claude-3-haiku", ModelPrice { prompt: 0.25, completion: 1.25 }),
+];
+
+impl ModelPrice {
+    /// Price of `model`, matched by the longest known name prefix
+    pub fn for_model(model: &str) -> Option<Self> {
+        MODEL_PRICES
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price)
+    }
+
+    /// Cost of `usage` in US dollars
+    pub fn cost(&self, usage: Usage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt + usage.completion_tokens as f64 * self.completion) / 1_000_000.0
+    }
+}
+
+/// Token usage summed over a TUI session or a batch run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageTracker {
+    total: Usage,
+    requests: usize,
+    model: Option<String>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Price usage as `model`; without a model no cost is estimated
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Add the usage of one request
+    pub fn record(&mut self, usage: Usage) {
+        self.total += usage;
+        self.requests += 1;
+    }
+
+    /// Add every request recorded by `other`
+    pub fn merge(&mut self, other: &UsageTracker) {
+        self.total += other.total;
+        self.requests += other.requests;
+    }
+
+    /// Usage summed over every recorded request
+    pub fn total(&self) -> Usage {
+        self.total
+    }
+
+    /// Number of recorded requests
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+
+    /// Total number of prompt and completion tokens
+    pub fn total_tokens(&self) -> usize {
+        self.total.prompt_tokens + self.total.completion_tokens
+    }
+
+    /// Estimated cost in US dollars, if the model's price is known
+    pub fn estimated_cost(&self) -> Option<f64> {
+        let price = ModelPrice::for_model(self.model.as_deref()?)?;
+        Some(price.cost(self.total))
+    }
+}
+
+impl std::fmt::Display for UsageTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} prompt + {} completion tokens over {} {}",
+            self.total.prompt_tokens,
+            self.total.completion_tokens,
+            self.requests,
+            if self.requests == 1 { "request" } else { "requests" }
+        )?;
+        if let Some(cost) = self.estimated_cost() {
+            write!(f, " (~${:.4})", cost)?;
+        }
+        Ok(())
+    }
+}
+
+/// Backend capable of producing completions for a prompt
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// Produce a completion for the given request
+    async fn complete(&self, request: CompletionRequest) -> Result<Completion, GroundhogError>;
+    
+    /// Produce a completion, passing text to `on_delta` as it arrives
+    ///
+    /// Providers without streaming support emit the whole completion as one delta.
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+        on_delta: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<Completion, GroundhogError> {
+        let completion = self.complete(request).await?;
+        on_delta(&completion.text);
+        Ok(completion)
+    }
+    
+    /// Check connectivity and authentication with a minimal request
+    async fn ping(&self) -> Result<(), GroundhogError> {
+        self.complete(CompletionRequest {
+            prompt: "ping".to_string(),
+            ..CompletionRequest::default()
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Answer language and sampling parameters a prompt is sent with
+#[derive(Debug, Clone, Copy)]
+struct PromptSettings<'a> {
+    language: Option<&'a str>,
+    params: &'a AiParams,
+}
+
+/// AI service wrapping a provider backend
 pub struct AIService {
-    // Future: API client, model configuration, etc.
     pub enabled: bool,
+    /// Language the AI should answer in
+    pub language: Option<String>,
+    /// Maximum number of prompt tokens the model accepts
+    pub context_limit: usize,
+    /// Tokens of earlier turns sent with a reply, half the context limit when unset
+    pub history_limit: Option<usize>,
+    /// How failed provider requests are retried
+    pub retry: RetryPolicy,
+    /// Sampling seed forwarded to the provider
+    pub seed: Option<u64>,
+    /// Sampling parameters forwarded to the provider
+    pub params: AiParams,
+    /// Model requests are sent to, when built from configuration
+    pub model: Option<String>,
+    /// Provider requests are sent to, when built from configuration
+    pub provider_name: Option<String>,
+    /// Prompt template topics are wrapped in, part of the cache key
+    pub template: Option<String>,
+    /// Whether concurrent identical prompts share one provider call
+    pub dedupe: bool,
+    /// Level of detail requested for explanations
+    pub depth: Option<ExplainDepth>,
+    /// Whether explanations are asked to cite their sources with `[n]` markers
+    pub citations: bool,
+    /// Text put ahead of every prompt built by [`AIService::build_prompt`]
+    pub prompt_prefix: Option<String>,
+    /// Text put after every prompt built by [`AIService::build_prompt`]
+    pub prompt_suffix: Option<String>,
+    /// Limits how often requests are sent to the provider
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// JSON schema replies are constrained to and checked against
+    pub response_schema: Option<serde_json::Value>,
+    /// Replies already received for the same request, reused instead of sending it again
+    pub cache: Option<Arc<ResponseCache>>,
+    provider: Option<Arc<dyn Provider>>,
+    in_flight: InFlight,
 }
 
 impl AIService {
     pub fn new() -> Self {
-        Self { enabled: false }
+        Self {
+            enabled: false,
+            language: None,
+            context_limit: DEFAULT_CONTEXT_LIMIT,
+            history_limit: None,
+            retry: RetryPolicy::default(),
+            seed: None,
+            params: AiParams::default(),
+            model: None,
+            provider_name: None,
+            template: None,
+            dedupe: true,
+            depth: None,
+            citations: false,
+            prompt_prefix: None,
+            prompt_suffix: None,
+            rate_limiter: None,
+            response_schema: None,
+            cache: None,
+            provider: None,
+            in_flight: InFlight::new(),
+        }
+    }
+    
+    /// Build the service for the `[ai]` section of `config`, disabled when there is none
+    ///
+    /// Requests give up after `[performance] timeout` and are retried as
+    /// `[performance.retry]` says.
+    pub fn from_config(config: &Config) -> Self {
+        let timeout = Duration::from_secs(config.performance.timeout);
+        config
+            .ai
+            .as_ref()
+            .map_or_else(Self::new, |ai| Self::with_config(ai, timeout))
+            .with_retry_policy(RetryPolicy::from_config(&config.performance.retry))
+    }
+    
+    /// Build the service for a TUI or REPL session from the `[ai]` and `[output]` sections
+    pub fn for_chat(config: &Config) -> Self {
+        Self::from_config(config)
+            .with_language(config.output.resolve_language())
+            .with_seed(config.seed())
+            .with_params(config.ai_params())
+    }
+    
+    /// Build a service for the `[ai]` section, talking to its provider over HTTP
+    ///
+    /// The service is only enabled when the config is usable, i.e. names a
+    /// model and resolves any API key its provider needs. Requests give up
+    /// after `timeout`, normally `[performance] timeout`.
+    pub fn with_config(ai: &AiConfig, timeout: Duration) -> Self {
+        let mut service = Self::new()
+            .with_context_limit(ai.context_limit)
+            .with_history_limit(ai.history_limit)
+            .with_seed(ai.seed)
+            .with_params(ai.params.clone())
+            .with_prompt_framing(ai.prompt_prefix.clone(), ai.prompt_suffix.clone())
+            .with_rate_limiter(ai.rate_limit.as_ref().map(|limit| Arc::new(RateLimiter::from_config(limit))));
+        service.model = Some(ai.model.clone());
+        service.provider_name = Some(ai.provider.to_string());
+        if !ai.is_usable() {
+            return service;
+        }
+        
+        match HttpProvider::from_config(ai, timeout) {
+            Ok(provider) => service.with_provider(Arc::new(provider)),
+            Err(e) => {
+                warn!(error = %e, "Failed to create AI provider client");
+                service
+            }
+        }
     }
     
     pub fn with_enabled(mut self, enabled: bool) -> Self {
@@ -19,14 +334,545 @@ impl AIService {
         self
     }
     
-    /// Future: Generate explanations using AI
+    /// Use the given provider backend, enabling the service
+    pub fn with_provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = Some(provider);
+        self.enabled = true;
+        self
+    }
+    
+    /// Request responses in the given language
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+    
+    /// Set the model context limit used for prompt sizing
+    pub fn with_context_limit(mut self, context_limit: usize) -> Self {
+        self.context_limit = context_limit;
+        self
+    }
+    
+    /// Limit the chat history sent with [`AIService::generate_reply`] to this many tokens
+    pub fn with_history_limit(mut self, history_limit: Option<usize>) -> Self {
+        self.history_limit = history_limit;
+        self
+    }
+    
+    /// Set how failed provider requests are retried
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+    
+    /// Forward a sampling seed to the provider for reproducible output
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+    
+    /// Forward sampling parameters such as temperature to the provider
+    pub fn with_params(mut self, params: AiParams) -> Self {
+        self.params = params;
+        self
+    }
+    
+    /// Share one provider call between concurrent identical prompts, on by default
+    ///
+    /// Turn this off when identical prompts are meant to get different
+    /// replies, as when sampling several explanations.
+    pub fn with_dedupe(mut self, dedupe: bool) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+    
+    /// Ask for explanations with the given level of detail
+    pub fn with_depth(mut self, depth: Option<ExplainDepth>) -> Self {
+        self.depth = depth;
+        self
+    }
+    
+    /// Ask for explanations that cite their sources, see [`citations::INSTRUCTION`]
+    pub fn with_citations(mut self, citations: bool) -> Self {
+        self.citations = citations;
+        self
+    }
+    
+    /// Frame every prompt with a prefix and a suffix, such as a team's standing instructions
+    ///
+    /// Raw prompts are sent without them.
+    pub fn with_prompt_framing(mut self, prefix: Option<String>, suffix: Option<String>) -> Self {
+        self.prompt_prefix = prefix;
+        self.prompt_suffix = suffix;
+        self
+    }
+    
+    /// Wait for `limiter` before each provider request, including retries
+    ///
+    /// Services sharing a limiter share its budget.
+    pub fn with_rate_limiter(mut self, limiter: Option<Arc<RateLimiter>>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+    
+    /// Ask for replies matching a JSON schema
+    ///
+    /// The schema is passed to providers that support structured output, and
+    /// every reply is checked against it; replies are returned as formatted
+    /// JSON.
+    pub fn with_response_schema(mut self, schema: Option<serde_json::Value>) -> Self {
+        self.response_schema = schema;
+        self
+    }
+    
+    /// Reuse replies stored in `cache`, and store new ones there
+    ///
+    /// Entries are keyed by provider, model, template, prompt, seed, sampling
+    /// parameters and schema, so changing any of them is a miss. A cached
+    /// reply is streamed as a single delta.
+    pub fn with_cache(mut self, cache: Option<Arc<ResponseCache>>) -> Self {
+        self.cache = cache;
+        self
+    }
+    
+    /// Record the prompt template topics are wrapped in, so cached replies depend on it
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
+    }
+    
+    /// The service's own answer language and sampling parameters
+    fn settings(&self) -> PromptSettings<'_> {
+        PromptSettings {
+            language: self.language.as_deref(),
+            params: &self.params,
+        }
+    }
+    
+    /// Answer language and sampling parameters of `request`, falling back to the service's own
+    fn settings_for<'a>(&'a self, request: &'a ExplainRequest) -> PromptSettings<'a> {
+        PromptSettings {
+            language: request.lang.as_deref().or(self.language.as_deref()),
+            params: request.params.as_ref().unwrap_or(&self.params),
+        }
+    }
+    
+    /// Generate an explanation for a topic
     pub async fn generate_explanation(&self, topic: &str) -> Result<String, GroundhogError> {
+        self.topic_explanation(topic, self.settings()).await.map(|(text, _)| text)
+    }
+    
+    /// Explain a topic, with the tokens used by the prompt sent and the reply
+    async fn topic_explanation(&self, topic: &str, settings: PromptSettings<'_>) -> Result<(String, Usage), GroundhogError> {
         if !self.enabled {
-            return Ok(format!("AI service is disabled. Topic: {}", topic));
+            let language = settings.language.map(str::to_string);
+            let details: Vec<String> = [("language", language), ("depth", self.depth.map(|d| d.to_string()))]
+                .into_iter()
+                .filter_map(|(name, value)| value.map(|value| format!("{}: {}", name, value)))
+                .collect();
+            let text = if details.is_empty() {
+                format!("AI service is disabled. Topic: {}", topic)
+            } else {
+                format!("AI service is disabled. Topic: {} ({})", topic, details.join(", "))
+            };
+            return Ok(placeholder(topic, text));
+        }
+        
+        if self.provider.is_none() {
+            return Ok(placeholder(topic, format!("AI-generated explanation for '{}' (not implemented yet)", topic)));
+        }
+        
+        self.complete(&self.apply_instructions(topic), settings).await
+    }
+    
+    /// Reply to the last user message of `conversation`, sending the earlier turns as context
+    ///
+    /// System prompts are always sent, application notes are left out, and
+    /// the oldest turns are dropped once the history is over
+    /// [`AIService::history_limit`]. Without system prompts or earlier turns
+    /// this is the same as [`AIService::generate_explanation`].
+    pub async fn generate_reply(&self, conversation: &Conversation) -> Result<String, GroundhogError> {
+        self.generate_explanation(&self.reply_topic(conversation)?).await
+    }
+    
+    /// Stream a reply to the last user message of `conversation`, giving up as soon as `cancel` is triggered
+    ///
+    /// The earlier turns are sent as for [`AIService::generate_reply`].
+    pub async fn stream_reply_with_cancel(
+        &self,
+        conversation: &Conversation,
+        cancel: &CancellationToken,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        let topic = self.reply_topic(conversation)?;
+        self.stream_explanation_with_cancel(&topic, cancel, on_delta).await
+    }
+    
+    /// Text replied to for `conversation`: its last user message, after the system prompts and the earlier turns that fit
+    ///
+    /// The system prompts and earlier turns are only added when a provider
+    /// will receive them.
+    fn reply_topic(&self, conversation: &Conversation) -> Result<String, GroundhogError> {
+        let budget = self.history_limit.unwrap_or(self.context_limit / 2);
+        let messages = history::window(conversation, budget);
+        let dropped = conversation.messages.iter().filter(|m| m.role != Role::Note).count() - messages.len();
+        if dropped > 0 {
+            debug!(dropped, budget, "Dropped oldest turns from the chat history");
+        }
+        let (system, turns): (Vec<_>, Vec<_>) = messages.into_iter().partition(|m| m.role == Role::System);
+        let Some(index) = turns.iter().rposition(|m| m.role == Role::User) else {
+            return Err(InternalError::UnexpectedState {
+                message: "conversation has no user message to reply to".to_string(),
+            }
+            .into());
+        };
+        let prompt = &turns[index].content;
+        if !self.enabled || self.provider.is_none() {
+            return Ok(prompt.clone());
+        }
+        
+        let history: Vec<String> = turns[..index]
+            .iter()
+            .map(|message| format!("{}: {}", if message.role == Role::User { "User" } else { "Assistant" }, message.content))
+            .collect();
+        let mut sections: Vec<String> = system.iter().map(|message| message.content.clone()).collect();
+        if !history.is_empty() {
+            sections.push(format!("Conversation so far:\n{}", history.join("\n")));
+        }
+        sections.push(prompt.clone());
+        Ok(sections.join("\n\n"))
+    }
+    
+    /// Generate an explanation for a topic, giving up as soon as `cancel` is triggered
+    pub async fn generate_explanation_with_cancel(
+        &self,
+        topic: &str,
+        cancel: &CancellationToken,
+    ) -> Result<String, GroundhogError> {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(InternalError::Cancelled {
+                operation: "explanation".to_string(),
+            }
+            .into()),
+            result = self.generate_explanation(topic) => result,
+        }
+    }
+    
+    /// Stream an explanation for a topic, giving up as soon as `cancel` is triggered
+    pub async fn stream_explanation_with_cancel(
+        &self,
+        topic: &str,
+        cancel: &CancellationToken,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(InternalError::Cancelled {
+                operation: "explanation".to_string(),
+            }
+            .into()),
+            result = self.stream_explanation(topic, on_delta) => result,
+        }
+    }
+    
+    /// Stream an explanation for a topic, passing each chunk to `on_delta`
+    ///
+    /// Failures are retried only until the first chunk has been emitted.
+    pub async fn stream_explanation(
+        &self,
+        topic: &str,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        self.stream_topic(topic, self.settings(), on_delta).await
+    }
+    
+    /// Stream an explanation for a topic in the given language and with the given sampling parameters
+    async fn stream_topic(
+        &self,
+        topic: &str,
+        settings: PromptSettings<'_>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        if !self.enabled || self.provider.is_none() {
+            let (text, usage) = self.topic_explanation(topic, settings).await?;
+            on_delta(&text);
+            return Ok(usage);
+        }
+        
+        let prompt = self.frame_prompt(&self.apply_instructions(topic), settings.language);
+        self.stream_prompt(prompt, settings, on_delta).await
+    }
+    
+    /// Stream a completion of an already built prompt
+    async fn stream_prompt(
+        &self,
+        prompt: String,
+        settings: PromptSettings<'_>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        let provider = self.provider()?;
+        let request = self.request(prompt, settings);
+        let key = self.cache_key(&request);
+        if let Some(text) = self.cached(&key) {
+            let checked = self.check_response(&text)?;
+            on_delta(&checked);
+            return Ok(Usage::estimate(&request.prompt, &text));
+        }
+        
+        let emitted = AtomicBool::new(false);
+        let on_delta = Mutex::new(on_delta);
+        let attempt = || async {
+            self.throttle().await;
+            let mut forward = |delta: &str| {
+                emitted.store(true, Ordering::Relaxed);
+                (*on_delta.lock().unwrap_or_else(|e| e.into_inner()))(delta);
+            };
+            match provider.stream(request.clone(), &mut forward).await {
+                // Chunks already shown cannot be taken back, so the failure is final
+                Err(e) if emitted.load(Ordering::Relaxed) => Ok(Err(e)),
+                result => result.map(Ok),
+            }
+        };
+        let completion = self.retry.run(attempt).await??;
+        self.check_response(&completion.text)?;
+        self.store(&key, &completion.text);
+        Ok(Usage::estimate(&request.prompt, &completion.text))
+    }
+    
+    /// Send `text` to the provider as is, without the language instruction or prompt framing
+    async fn generate_raw(&self, text: &str, settings: PromptSettings<'_>) -> Result<(String, Usage), GroundhogError> {
+        if !self.enabled || self.provider.is_none() {
+            return Ok(placeholder(text, format!("AI service is disabled. Prompt: {}", text)));
+        }
+        
+        self.send(text.to_string(), settings).await
+    }
+    
+    /// Generate an explanation for the contents of a file, optionally focused on a topic
+    ///
+    /// `context` holds related files and is put ahead of the instruction.
+    async fn generate_file_explanation(
+        &self,
+        path: &Path,
+        content: &str,
+        focus: Option<&str>,
+        context: Option<&str>,
+        settings: PromptSettings<'_>,
+    ) -> Result<(String, Usage), GroundhogError> {
+        if !self.enabled || self.provider.is_none() {
+            return Ok(placeholder(content, format!("AI service is disabled. File: {}", path.display())));
+        }
+        
+        let instruction = match focus {
+            Some(focus) => format!("Explain the following file '{}', focusing on: {}", path.display(), focus),
+            None => format!("Explain the following file '{}'", path.display()),
+        };
+        let instruction = match context {
+            Some(related) => format!("{}\n{}", context::prompt_section(related), instruction),
+            None => instruction,
+        };
+        self.complete(&self.apply_instructions(&format!("{}\n\n```\n{}\n```", instruction, content)), settings).await
+    }
+    
+    /// Ask for `<line>: <note>` annotations of a file whose lines are already numbered
+    async fn generate_file_annotations(
+        &self,
+        path: &Path,
+        numbered_content: &str,
+        focus: Option<&str>,
+        settings: PromptSettings<'_>,
+    ) -> Result<(String, Usage), GroundhogError> {
+        if !self.enabled || self.provider.is_none() {
+            return Ok(placeholder(numbered_content, format!("AI service is disabled. File: {}", path.display())));
+        }
+        
+        let focus = focus.map(|focus| format!(", focusing on: {}", focus)).unwrap_or_default();
+        let instruction = format!(
+            "Annotate the following file '{}'{}. Its lines are numbered. Reply only with lines of the form \
+             `<line number>: <note>` for the lines worth explaining.",
+            path.display(),
+            focus
+        );
+        self.complete(&format!("{}\n\n```\n{}```", instruction, numbered_content), settings).await
+    }
+    
+    /// Ask for a unified diff of a file that makes the change described by `instruction`
+    async fn generate_file_patch(
+        &self,
+        path: &Path,
+        content: &str,
+        instruction: &str,
+        settings: PromptSettings<'_>,
+    ) -> Result<(String, Usage), GroundhogError> {
+        if !self.enabled || self.provider.is_none() {
+            return Ok(placeholder(content, format!("AI service is disabled. File: {}", path.display())));
+        }
+        
+        let instruction = format!(
+            "Change the following file '{}' to: {}. Reply only with a unified diff of the file, with `--- a/` and \
+             `+++ b/` headers and `@@` hunks whose line counts match their lines. Keep the rest of the file as it is.",
+            path.display(),
+            instruction
+        );
+        self.complete(&format!("{}\n\n```\n{}\n```", instruction, content), settings).await
+    }
+    
+    /// Send content to the provider and return the completion text with its usage
+    async fn complete(&self, content: &str, settings: PromptSettings<'_>) -> Result<(String, Usage), GroundhogError> {
+        self.send(self.frame_prompt(content, settings.language), settings).await
+    }
+    
+    /// Send an already built prompt to the provider, retrying failures
+    ///
+    /// With [`AIService::dedupe`] on, a request already in flight with the
+    /// same prompt, sampling settings and schema is not sent again; its reply
+    /// is shared instead. Cached replies are not sent at all.
+    /// Usage is estimated from the whole prompt, instructions included.
+    async fn send(&self, prompt: String, settings: PromptSettings<'_>) -> Result<(String, Usage), GroundhogError> {
+        let provider = self.provider()?;
+        let request = self.request(prompt, settings);
+        let key = self.cache_key(&request);
+        if let Some(text) = self.cached(&key) {
+            return Ok((self.check_response(&text)?, Usage::estimate(&request.prompt, &text)));
+        }
+        
+        let attempt = || async {
+            self.throttle().await;
+            provider.complete(request.clone()).await
+        };
+        let send = || async { Ok(self.retry.run(attempt).await?.text) };
+        
+        let text = if self.dedupe {
+            self.in_flight.run(&key.digest(), send).await?
+        } else {
+            send().await?
+        };
+        let checked = self.check_response(&text)?;
+        self.store(&key, &text);
+        Ok((checked, Usage::estimate(&request.prompt, &text)))
+    }
+    
+    /// Cache key of `request` for this service's provider, model and template
+    fn cache_key<'a>(&'a self, request: &'a CompletionRequest) -> CacheKey<'a> {
+        CacheKey {
+            version: CACHE_VERSION,
+            provider: self.provider_name.as_deref().unwrap_or_default(),
+            model: self.model.as_deref().unwrap_or_default(),
+            template: self.template.as_deref(),
+            request,
+        }
+    }
+    
+    /// The reply cached for `key`, if the cache is on and has one
+    fn cached(&self, key: &CacheKey<'_>) -> Option<String> {
+        let text = self.cache.as_ref()?.get(key)?;
+        debug!(digest = %key.digest(), "Using cached response");
+        Some(text)
+    }
+    
+    /// Cache the reply for `key`; the cache is best effort and never fails the request
+    fn store(&self, key: &CacheKey<'_>, text: &str) {
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.put(key, text)
+        {
+            warn!(dir = %cache.dir().display(), error = %e, "Failed to cache response");
+        }
+    }
+    
+    /// Completion request for `prompt` with the given sampling parameters and the service's seed and schema
+    fn request(&self, prompt: String, settings: PromptSettings<'_>) -> CompletionRequest {
+        CompletionRequest {
+            prompt,
+            seed: self.seed,
+            params: settings.params.clone(),
+            response_schema: self.response_schema.clone(),
+        }
+    }
+    
+    /// Check a reply against the response schema, if any, returning it as formatted JSON
+    fn check_response(&self, text: &str) -> Result<String, GroundhogError> {
+        match &self.response_schema {
+            Some(schema) => {
+                let value = schema::check_response(schema, text)?;
+                Ok(serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string()))
+            }
+            None => Ok(text.to_string()),
+        }
+    }
+    
+    /// Wait until the rate limit, if any, allows another request
+    async fn throttle(&self) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+    }
+    
+    fn provider(&self) -> Result<&Arc<dyn Provider>, GroundhogError> {
+        self.provider.as_ref().ok_or_else(|| {
+            InternalError::UnexpectedState {
+                message: "AI service has no provider configured".to_string(),
+            }
+            .into()
+        })
+    }
+    
+    /// Put the depth and citation instructions, if any, ahead of `content`
+    ///
+    /// Annotations, patches and raw prompts are sent without them.
+    fn apply_instructions(&self, content: &str) -> String {
+        let citations = self.citations.then_some(citations::INSTRUCTION);
+        [self.depth.map(ExplainDepth::instruction), citations, Some(content)]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+    
+    /// Build the prompt sent to the provider, including any language instruction
+    ///
+    /// The configured prefix and suffix surround `content`, each separated
+    /// by a blank line; blank ones are left out.
+    pub fn build_prompt(&self, content: &str) -> String {
+        self.frame_prompt(content, self.language.as_deref())
+    }
+    
+    /// Build the prompt sent to the provider, answering in `language` rather than the service's language
+    fn frame_prompt(&self, content: &str, language: Option<&str>) -> String {
+        let framed = [self.prompt_prefix.as_deref(), Some(content), self.prompt_suffix.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        match language {
+            Some(language) => format!("Respond in the language with code '{}'.\n\n{}", language, framed),
+            None => framed,
         }
+    }
+    
+    /// Measure the round trip of a minimal request to the provider
+    pub async fn ping(&self) -> Result<Duration, GroundhogError> {
+        let provider = self.provider.as_ref().ok_or_else(|| ConfigError::MissingKey {
+            key: "ai".to_string(),
+        })?;
         
-        // Placeholder implementation
-        Ok(format!("AI-generated explanation for '{}' (not implemented yet)", topic))
+        let start = Instant::now();
+        provider.ping().await?;
+        Ok(start.elapsed())
+    }
+    
+    /// Ping the provider until it answers or `wait` has passed, backing off between attempts
+    ///
+    /// Only transient failures such as refused connections are retried;
+    /// `on_retry` is called with each of them.
+    pub async fn ping_until_ready(
+        &self,
+        wait: Duration,
+        on_retry: impl FnMut(&GroundhogError),
+    ) -> Result<Duration, GroundhogError> {
+        let deadline = tokio::time::Instant::now() + wait;
+        self.retry.run_until(deadline, on_retry, || self.ping()).await
     }
     
     /// Check if the AI service is available
@@ -41,9 +887,304 @@ impl Default for AIService {
     }
 }
 
+/// A reply made up without a provider, with usage estimated from `subject`
+fn placeholder(subject: &str, text: String) -> (String, Usage) {
+    let usage = Usage::estimate(subject, &text);
+    (text, usage)
+}
+
+/// Backend producing explanations, so commands can run against something other than [`AIService`]
+#[async_trait]
+pub trait Explainer: Send + Sync {
+    /// Produce an explanation for the request
+    async fn explain(&self, request: ExplainRequest) -> Result<Completion, GroundhogError>;
+    
+    /// Produce an explanation and the tokens it used
+    ///
+    /// Explainers that do not know the prompt they send estimate the prompt
+    /// tokens from the request's subject.
+    async fn explain_with_usage(&self, request: ExplainRequest) -> Result<(Completion, Usage), GroundhogError> {
+        let completion = self.explain(request.clone()).await?;
+        let usage = Usage::estimate(request.subject(), &completion.text);
+        Ok((completion, usage))
+    }
+    
+    /// Produce an explanation, passing text to `on_delta` as it arrives
+    ///
+    /// Explainers without streaming support emit the whole explanation as one delta.
+    async fn explain_streaming(
+        &self,
+        request: ExplainRequest,
+        on_delta: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        let completion = self.explain(request.clone()).await?;
+        on_delta(&completion.text);
+        Ok(Usage::estimate(request.subject(), &completion.text))
+    }
+    
+    /// Whether explanations come from a real backend rather than a placeholder
+    fn is_available(&self) -> bool {
+        true
+    }
+    
+    /// Prompt token budget; larger files are explained in chunks
+    fn context_limit(&self) -> usize {
+        DEFAULT_CONTEXT_LIMIT
+    }
+    
+    /// Language explanations are written in, if one was requested
+    fn language(&self) -> Option<&str> {
+        None
+    }
+}
+
+#[async_trait]
+impl Explainer for AIService {
+    async fn explain(&self, request: ExplainRequest) -> Result<Completion, GroundhogError> {
+        self.explain_with_usage(request).await.map(|(completion, _)| completion)
+    }
+    
+    /// Explain a topic, or the text read from a file or stdin input in the request's format
+    async fn explain_with_usage(&self, request: ExplainRequest) -> Result<(Completion, Usage), GroundhogError> {
+        let settings = self.settings_for(&request);
+        let (text, usage) = match (request.topic_text(), &request.text) {
+            (Some(text), _) if request.raw => self.generate_raw(&text, settings).await?,
+            (Some(topic), _) => self.topic_explanation(&topic, settings).await?,
+            (None, Some(input)) => {
+                let path = request.input.path().unwrap_or_default();
+                let focus = request.focus.as_deref();
+                match request.format {
+                    ExplainFormat::Prose => {
+                        self.generate_file_explanation(&path, &input.text, focus, request.context.as_deref(), settings).await?
+                    }
+                    ExplainFormat::Annotated => {
+                        let numbered = annotate::number_lines(&input.text, input.first_line);
+                        self.generate_file_annotations(&path, &numbered, focus, settings).await?
+                    }
+                    ExplainFormat::Patch => {
+                        let instruction = focus.ok_or_else(|| InternalError::UnexpectedState {
+                            message: format!("patch request for '{}' does not describe the change", request.input),
+                        })?;
+                        self.generate_file_patch(&path, &input.text, instruction, settings).await?
+                    }
+                }
+            }
+            (None, None) => {
+                return Err(InternalError::UnexpectedState {
+                    message: format!("'{}' has not been read", request.input),
+                }
+                .into());
+            }
+        };
+        Ok((Completion { text }, usage))
+    }
+    
+    async fn explain_streaming(
+        &self,
+        request: ExplainRequest,
+        on_delta: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<Usage, GroundhogError> {
+        let settings = self.settings_for(&request);
+        match request.topic_text() {
+            Some(text) if request.raw && self.enabled && self.provider.is_some() => {
+                self.stream_prompt(text, settings, on_delta).await
+            }
+            Some(topic) if !request.raw => self.stream_topic(&topic, settings, on_delta).await,
+            _ => {
+                let (completion, usage) = self.explain_with_usage(request).await?;
+                on_delta(&completion.text);
+                Ok(usage)
+            }
+        }
+    }
+    
+    fn is_available(&self) -> bool {
+        AIService::is_available(self)
+    }
+    
+    fn context_limit(&self) -> usize {
+        self.context_limit
+    }
+    
+    fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+}
+
+/// Scripted step replayed by a [`MockProvider`]
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug)]
+enum MockStep {
+    Reply(String),
+    Fail(GroundhogError),
+    Hang,
+}
+
+/// Provider with scripted responses, latency and failure injection, for
+/// exercising retry, timeout and cancellation without a network
+///
+/// Scripted steps are consumed in order; once they run out every request
+/// gets the default reply.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug)]
+pub struct MockProvider {
+    steps: std::sync::Mutex<std::collections::VecDeque<MockStep>>,
+    default_reply: String,
+    latency: Duration,
+    requests: std::sync::Mutex<Vec<CompletionRequest>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MockProvider {
+    /// Provider answering every request with `default_reply`
+    pub fn new(default_reply: impl Into<String>) -> Self {
+        Self {
+            steps: Default::default(),
+            default_reply: default_reply.into(),
+            latency: Duration::ZERO,
+            requests: Default::default(),
+        }
+    }
+    
+    /// Queue a successful reply
+    pub fn with_reply(self, text: impl Into<String>) -> Self {
+        self.push(MockStep::Reply(text.into()))
+    }
+    
+    /// Queue a failed request
+    pub fn with_failure(self, error: impl Into<GroundhogError>) -> Self {
+        self.push(MockStep::Fail(error.into()))
+    }
+    
+    /// Queue `count` failures built by `error`
+    pub fn with_failures<E: Into<GroundhogError>>(self, count: usize, error: impl Fn() -> E) -> Self {
+        (0..count).fold(self, |provider, _| provider.with_failure(error()))
+    }
+    
+    /// Queue a request that never completes, for timeouts and cancellation
+    pub fn with_hang(self) -> Self {
+        self.push(MockStep::Hang)
+    }
+    
+    /// Delay every response by `latency`
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+    
+    /// Requests received so far, including failed ones
+    pub fn requests(&self) -> Vec<CompletionRequest> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    
+    fn push(self, step: MockStep) -> Self {
+        self.steps.lock().unwrap_or_else(|e| e.into_inner()).push_back(step);
+        self
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+#[async_trait]
+impl Provider for MockProvider {
+    async fn complete(&self, request: CompletionRequest) -> Result<Completion, GroundhogError> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).push(request);
+        let step = self.steps.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+        
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        
+        match step {
+            Some(MockStep::Reply(text)) => Ok(Completion { text }),
+            Some(MockStep::Fail(error)) => Err(error),
+            Some(MockStep::Hang) => std::future::pending().await,
+            None => Ok(Completion {
+                text: self.default_reply.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Provider that records requests and replies with a fixed text
+    #[derive(Default)]
+    pub struct RecordingProvider {
+        pub requests: Mutex<Vec<CompletionRequest>>,
+        pub response: String,
+    }
+
+    impl RecordingProvider {
+        pub fn new(response: impl Into<String>) -> Self {
+            Self {
+                requests: Mutex::new(Vec::new()),
+                response: response.into(),
+            }
+        }
+
+        pub fn requests(&self) -> Vec<CompletionRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    /// Provider that streams its response in fixed chunks
+    pub struct ChunkedProvider {
+        pub chunks: Vec<String>,
+    }
+
+    #[async_trait]
+    impl Provider for ChunkedProvider {
+        async fn complete(&self, _request: CompletionRequest) -> Result<Completion, GroundhogError> {
+            Ok(Completion {
+                text: self.chunks.concat(),
+            })
+        }
+
+        async fn stream(
+            &self,
+            _request: CompletionRequest,
+            on_delta: &mut (dyn for<'a> FnMut(&'a str) + Send),
+        ) -> Result<Completion, GroundhogError> {
+            for chunk in &self.chunks {
+                on_delta(chunk);
+            }
+            self.complete(CompletionRequest::default()).await
+        }
+    }
+
+    /// Provider whose requests never complete
+    pub struct HangingProvider;
+
+    #[async_trait]
+    impl Provider for HangingProvider {
+        async fn complete(&self, _request: CompletionRequest) -> Result<Completion, GroundhogError> {
+            std::future::pending().await
+        }
+    }
+
+    #[async_trait]
+    impl Provider for RecordingProvider {
+        async fn complete(&self, request: CompletionRequest) -> Result<Completion, GroundhogError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(Completion {
+                text: self.response.clone(),
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::testing::{ChunkedProvider, HangingProvider, RecordingProvider};
+    use std::path::PathBuf;
+    use crate::core::request::{ExplainInput, InputText};
+    use crate::infrastructure::config::{AiProvider, ConfigFormat};
+    use crate::infrastructure::error::NetworkError;
 
     #[test]
     fn test_ai_service_new() {
@@ -59,6 +1200,83 @@ mod tests {
         assert!(service.is_available());
     }
 
+    #[test]
+    fn test_service_is_disabled_without_ai_config() {
+        assert!(!AIService::from_config(&Config::default()).is_available());
+    }
+
+    #[test]
+    fn test_from_config_applies_retry_settings() {
+        let toml = "[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\n\n[performance.retry]\nmax_attempts = 5\n";
+        let config = Config::from_str(toml, ConfigFormat::Toml).unwrap();
+
+        let service = AIService::from_config(&config);
+        assert_eq!(service.retry, RetryPolicy::from_config(&config.performance.retry));
+        assert_eq!(service.retry.max_attempts, 5);
+    }
+
+    #[test]
+    fn test_ai_service_with_config() {
+        let ai = AiConfig {
+            context_limit: 4096,
+            seed: Some(7),
+            ..AiConfig::new(AiProvider::Local, "llama3")
+        };
+        let service = AIService::with_config(&ai, Duration::from_secs(30));
+        assert!(service.is_available());
+        assert!(service.provider.is_some());
+        assert_eq!(service.context_limit, 4096);
+        assert_eq!(service.seed, Some(7));
+
+        let unnamed = AiConfig { model: " ".to_string(), ..ai };
+        let service = AIService::with_config(&unnamed, Duration::from_secs(30));
+        assert!(!service.is_available());
+        assert!(service.provider.is_none());
+    }
+
+    #[test]
+    fn test_prompt_framing_reaches_service() {
+        let toml = "[ai]\nprovider = \"Local\"\nmodel = \"llama\"\nprompt_suffix = \"Answer with citations.\"\n";
+        let ai = Config::from_str(toml, ConfigFormat::Toml).unwrap().ai.unwrap();
+
+        let service = AIService::with_config(&ai, Duration::from_secs(30));
+        assert_eq!(service.prompt_prefix, None);
+        assert_eq!(service.build_prompt("topic"), "topic\n\nAnswer with citations.");
+    }
+
+    #[test]
+    fn test_usage_tracker_sums_requests() {
+        let mut tracker = UsageTracker::new();
+        tracker.record(Usage { prompt_tokens: 100, completion_tokens: 20 });
+        tracker.record(Usage { prompt_tokens: 50, completion_tokens: 30 });
+
+        assert_eq!(tracker.total(), Usage { prompt_tokens: 150, completion_tokens: 50 });
+        assert_eq!(tracker.requests(), 2);
+        assert_eq!(tracker.total_tokens(), 200);
+        assert_eq!(tracker.estimated_cost(), None);
+        assert_eq!(tracker.to_string(), "150 prompt + 50 completion tokens over 2 requests");
+
+        let mut batch = UsageTracker::new();
+        batch.record(Usage { prompt_tokens: 1, completion_tokens: 1 });
+        batch.merge(&tracker);
+        assert_eq!(batch.requests(), 3);
+        assert_eq!(batch.total(), Usage { prompt_tokens: 151, completion_tokens: 51 });
+    }
+
+    #[test]
+    fn test_usage_tracker_estimates_cost_from_model_price() {
+        let mut tracker = UsageTracker::new().with_model(Some("gpt-4o-mini-2024-07-18".to_string()));
+        tracker.record(Usage { prompt_tokens: 1_000_000, completion_tokens: 500_000 });
+
+        // The longer "gpt-4o-mini" prefix wins over "gpt-4o": 0.15 + 0.5 * 0.60
+        let cost = tracker.estimated_cost().unwrap();
+        assert!((cost - 0.45).abs() < 1e-9, "{}", cost);
+        assert!(tracker.to_string().ends_with("over 1 request (~$0.4500)"));
+
+        let local = UsageTracker::new().with_model(Some("llama3".to_string()));
+        assert_eq!(local.estimated_cost(), None);
+    }
+
     #[test]
     fn test_ai_service_default() {
         let service = AIService::default();
@@ -73,6 +1291,487 @@ mod tests {
         assert!(result.contains("rust"));
     }
 
+    #[tokio::test]
+    async fn test_generate_explanation_disabled_echoes_language() {
+        let service = AIService::new().with_language(Some("fr".to_string()));
+        let result = service.generate_explanation("rust").await.unwrap();
+        assert!(result.contains("AI service is disabled"));
+        assert!(result.contains("language: fr"));
+    }
+
+    #[tokio::test]
+    async fn test_language_instruction_sent_to_provider() {
+        let provider = Arc::new(RecordingProvider::new("Rust est un langage"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_language(Some("fr".to_string()));
+
+        let result = service.generate_explanation("rust").await.unwrap();
+        assert_eq!(result, "Rust est un langage");
+
+        let requests = provider.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].prompt.starts_with("Respond in the language with code 'fr'."));
+        assert!(requests[0].prompt.ends_with("rust"));
+    }
+
+    #[tokio::test]
+    async fn test_no_language_instruction_by_default() {
+        let provider = Arc::new(RecordingProvider::new("ok"));
+        let service = AIService::new().with_provider(provider.clone());
+
+        service.generate_explanation("rust").await.unwrap();
+        assert_eq!(provider.requests()[0].prompt, "rust");
+    }
+
+    #[tokio::test]
+    async fn test_stream_explanation_emits_chunks_and_usage() {
+        let chunks = vec!["Ownership ".to_string(), "moves values".to_string()];
+        let service = AIService::new().with_provider(Arc::new(ChunkedProvider { chunks: chunks.clone() }));
+
+        let mut deltas = Vec::new();
+        let usage = service
+            .stream_explanation("ownership", &mut |delta| deltas.push(delta.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(deltas, chunks);
+        assert_eq!(usage, Usage::estimate("ownership", "Ownership moves values"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_explanation_falls_back_to_single_delta() {
+        let service = AIService::new().with_provider(Arc::new(RecordingProvider::new("whole answer")));
+
+        let mut deltas = Vec::new();
+        service
+            .stream_explanation("rust", &mut |delta| deltas.push(delta.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(deltas, vec!["whole answer"]);
+    }
+
+    #[tokio::test]
+    async fn test_usage_counts_the_whole_prompt_sent() {
+        let provider = Arc::new(RecordingProvider::new("Prints a greeting"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_language(Some("fr".to_string()));
+        let request = ExplainRequest::new(ExplainInput::File(PathBuf::from("main.rs")))
+            .with_text(InputText::whole("fn main() {}".to_string()));
+
+        let (completion, usage) = service.explain_with_usage(request).await.unwrap();
+
+        assert_eq!(usage, Usage::estimate(&provider.requests()[0].prompt, &completion.text));
+        assert!(usage.prompt_tokens > estimate_tokens("fn main() {}"));
+    }
+
+    #[tokio::test]
+    async fn test_request_language_and_params_override_the_service() {
+        let provider = Arc::new(RecordingProvider::new("ok"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_language(Some("fr".to_string()));
+        let params = AiParams {
+            temperature: Some(0.2),
+            ..AiParams::default()
+        };
+        let request = ExplainRequest::new(ExplainInput::Topic("traits".to_string()))
+            .with_lang(Some("de".to_string()))
+            .with_params(params.clone());
+
+        service.explain(request).await.unwrap();
+        let mut deltas = Vec::new();
+        let request = ExplainRequest::new(ExplainInput::Topic("traits".to_string()));
+        service.explain_streaming(request, &mut |delta| deltas.push(delta.to_string())).await.unwrap();
+
+        let requests = provider.requests();
+        assert!(requests[0].prompt.starts_with("Respond in the language with code 'de'."));
+        assert_eq!(requests[0].params, params);
+        // Without its own, a request gets the service's language and parameters
+        assert!(requests[1].prompt.starts_with("Respond in the language with code 'fr'."));
+        assert_eq!(requests[1].params, AiParams::default());
+    }
+
+    #[tokio::test]
+    async fn test_annotated_request_numbers_lines_from_its_first_line() {
+        let provider = Arc::new(RecordingProvider::new("12: Starts the app"));
+        let service = AIService::new().with_provider(provider.clone());
+        let text = InputText {
+            text: "fn main() {\n    run();\n".to_string(),
+            first_line: 11,
+        };
+        let request = ExplainRequest::new(ExplainInput::File(PathBuf::from("main.rs")))
+            .with_format(ExplainFormat::Annotated)
+            .with_text(text);
+
+        service.explain(request).await.unwrap();
+
+        assert!(provider.requests()[0].prompt.contains("11: fn main() {\n12:     run();\n"));
+    }
+
+    #[tokio::test]
+    async fn test_unread_file_request_is_rejected() {
+        let service = AIService::new().with_provider(Arc::new(RecordingProvider::new("unused")));
+        let request = ExplainRequest::new(ExplainInput::File(PathBuf::from("main.rs")));
+
+        assert!(matches!(service.explain(request).await, Err(GroundhogError::Internal(_))));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_reply_is_cached_and_replayed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let provider = Arc::new(RecordingProvider::new("whole answer"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_cache(Some(Arc::new(ResponseCache::new(dir.path().to_path_buf()))));
+
+        for _ in 0..2 {
+            let mut deltas = Vec::new();
+            service
+                .stream_explanation("rust", &mut |delta| deltas.push(delta.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(deltas, vec!["whole answer"]);
+        }
+        assert_eq!(provider.requests().len(), 1);
+    }
+
+    fn instant_retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            jitter: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_succeeds_after_scripted_failures() {
+        let provider = Arc::new(
+            MockProvider::new("recovered").with_failures(2, || NetworkError::Http {
+                status: 503,
+                message: "unavailable".to_string(),
+                source: None,
+            }),
+        );
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_retry_policy(instant_retry(3));
+
+        assert_eq!(service.generate_explanation("rust").await.unwrap(), "recovered");
+        assert_eq!(provider.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_ping_until_ready_retries_past_max_attempts_within_deadline() {
+        let provider = Arc::new(MockProvider::new("pong").with_failures(2, || NetworkError::Http {
+            status: 503,
+            message: "starting".to_string(),
+            source: None,
+        }));
+        // A single attempt would fail; the deadline, not the attempt count, ends the wait
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_retry_policy(instant_retry(1));
+
+        let mut retries = 0;
+        let result = service.ping_until_ready(Duration::from_secs(5), |_| retries += 1).await;
+
+        assert!(result.is_ok());
+        assert_eq!(retries, 2);
+        assert_eq!(provider.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stream_retries_failures_before_first_delta() {
+        let provider = Arc::new(MockProvider::new("whole answer").with_failures(2, || NetworkError::Http {
+            status: 503,
+            message: "overloaded".to_string(),
+            source: None,
+        }));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_retry_policy(instant_retry(3));
+
+        let mut deltas = Vec::new();
+        service
+            .stream_explanation("rust", &mut |delta| deltas.push(delta.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(deltas, vec!["whole answer"]);
+        assert_eq!(provider.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_injected_timeout() {
+        let provider = Arc::new(
+            MockProvider::new("unused").with_failure(NetworkError::Timeout { timeout_ms: 50 }),
+        );
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_retry_policy(RetryPolicy::none());
+
+        let result = service.generate_explanation("rust").await;
+        assert!(matches!(
+            result,
+            Err(GroundhogError::Network(NetworkError::Timeout { timeout_ms: 50 }))
+        ));
+        assert_eq!(provider.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_seed_forwarded_to_provider() {
+        let provider = Arc::new(MockProvider::new("ok"));
+        let service = AIService::new().with_provider(provider.clone()).with_seed(Some(42));
+
+        service.generate_explanation("rust").await.unwrap();
+        service.stream_explanation("rust", &mut |_| {}).await.unwrap();
+
+        let seeds: Vec<_> = provider.requests().iter().map(|request| request.seed).collect();
+        assert_eq!(seeds, vec![Some(42), Some(42)]);
+    }
+
+    #[tokio::test]
+    async fn test_params_forwarded_to_provider() {
+        let params = AiParams {
+            temperature: Some(0.2),
+            max_tokens: Some(256),
+            top_p: Some(0.9),
+        };
+        let provider = Arc::new(MockProvider::new("ok"));
+        let service = AIService::new().with_provider(provider.clone()).with_params(params.clone());
+
+        service.generate_explanation("rust").await.unwrap();
+        assert_eq!(provider.requests()[0].params, params);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_spaces_out_requests() {
+        let provider = Arc::new(MockProvider::new("ok"));
+        let service = AIService::new()
+            .with_provider(provider.clone())
+            .with_rate_limiter(Some(Arc::new(RateLimiter::new(30, 1))));
+        let start = tokio::time::Instant::now();
+
+        let results = tokio::join!(
+            service.generate_explanation("ownership"),
+            service.generate_explanation("borrowing"),
+            service.generate_explanation("lifetimes"),
+            service.generate_explanation("traits"),
+        );
+
+        assert!(results.0.is_ok() && results.1.is_ok() && results.2.is_ok() && results.3.is_ok());
+        assert_eq!(provider.requests().len(), 4);
+        // The first request goes out at once, the rest two seconds apart
+        assert!(start.elapsed() >= Duration::from_secs(6));
+    }
+
+    #[tokio::test]
+    async fn test_depth_injects_instruction() {
+        let cases = [
+            (ExplainDepth::Brief, "one sentence"),
+            (ExplainDepth::Normal, "a paragraph"),
+            (ExplainDepth::Deep, "a thorough walkthrough"),
+        ];
+        for (depth, phrase) in cases {
+            let provider = Arc::new(MockProvider::new("ok"));
+            let service = AIService::new().with_provider(provider.clone()).with_depth(Some(depth));
+
+            service.generate_explanation("rust").await.unwrap();
+
+            let prompt = &provider.requests()[0].prompt;
+            assert_eq!(*prompt, format!("{}\n\nrust", depth.instruction()));
+            assert!(prompt.contains(phrase), "{:?} prompt should ask for {}", depth, phrase);
+        }
+
+        let provider = Arc::new(MockProvider::new("ok"));
+        AIService::new().with_provider(provider.clone()).generate_explanation("rust").await.unwrap();
+        assert_eq!(provider.requests()[0].prompt, "rust");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_service_echoes_depth() {
+        let service = AIService::new().with_depth(Some(ExplainDepth::Brief));
+        assert_eq!(
+            service.generate_explanation("rust").await.unwrap(),
+            "AI service is disabled. Topic: rust (depth: brief)"
+        );
+
+        let service = service.with_language(Some("de".to_string()));
+        assert_eq!(
+            service.generate_explanation("rust").await.unwrap(),
+            "AI service is disabled. Topic: rust (language: de, depth: brief)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reply_sends_earlier_turns_as_context() {
+        let provider = Arc::new(MockProvider::new("Closures capture variables."));
+        let service = AIService::new().with_provider(provider.clone());
+        let mut conversation = Conversation::new();
+
+        conversation.push_user("What is a closure?");
+        let reply = service.generate_reply(&conversation).await.unwrap();
+        conversation.push_assistant(reply);
+        conversation.push_note("Request failed");
+        conversation.push_user("Show an example");
+        service.generate_reply(&conversation).await.unwrap();
+
+        let requests = provider.requests();
+        assert_eq!(requests[0].prompt, "What is a closure?");
+        assert_eq!(
+            requests[1].prompt,
+            "Conversation so far:\nUser: What is a closure?\nAssistant: Closures capture variables.\n\nShow an example"
+        );
+        assert!(service.generate_reply(&Conversation::new()).await.is_err());
+
+        // Turns beyond the history limit are left out
+        let service = service.with_history_limit(Some(1));
+        service.generate_reply(&conversation).await.unwrap();
+        assert_eq!(provider.requests()[2].prompt, "Show an example");
+    }
+
+    #[tokio::test]
+    async fn test_over_budget_reply_sends_latest_turns_without_notes() {
+        let provider = Arc::new(MockProvider::new("ok"));
+        let mut conversation = Conversation::new();
+        for turn in 1..=3 {
+            conversation.push_user(format!("question {}", turn));
+            conversation.push_assistant(format!("answer {}", turn));
+        }
+        conversation.push_note("Request failed: connection refused. ".repeat(20));
+        conversation.push_user("question 4");
+        let budget = conversation.messages[2..6].iter().chain(&conversation.messages[7..]).map(history::message_tokens).sum();
+        let service = AIService::new().with_provider(provider.clone()).with_history_limit(Some(budget));
+
+        service.generate_reply(&conversation).await.unwrap();
+        assert_eq!(
+            provider.requests()[0].prompt,
+            "Conversation so far:\nUser: question 2\nAssistant: answer 2\nUser: question 3\nAssistant: answer 3\n\nquestion 4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reply_sends_system_prompt_first() {
+        let provider = Arc::new(MockProvider::new("ok"));
+        let mut conversation = Conversation::new();
+        conversation.push_system("Answer like a patient teacher.");
+        for turn in 1..=3 {
+            conversation.push_user(format!("question {}", turn));
+            conversation.push_assistant(format!("answer {}", turn));
+        }
+        conversation.push_user("question 4");
+        let budget = [0, 5, 6, 7].iter().map(|&index| history::message_tokens(&conversation.messages[index])).sum();
+        let service = AIService::new().with_provider(provider.clone()).with_history_limit(Some(budget));
+
+        service.generate_reply(&conversation).await.unwrap();
+        assert_eq!(
+            provider.requests()[0].prompt,
+            "Answer like a patient teacher.\n\nConversation so far:\nUser: question 3\nAssistant: answer 3\n\nquestion 4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamed_reply_sends_earlier_turns() {
+        let provider = Arc::new(MockProvider::new("Like this."));
+        let service = AIService::new().with_provider(provider.clone());
+        let mut conversation = Conversation::new();
+        conversation.push_user("What is a closure?");
+        conversation.push_assistant("Closures capture variables.");
+        conversation.push_user("Show an example");
+
+        let mut streamed = String::new();
+        let cancel = CancellationToken::new();
+        service
+            .stream_reply_with_cancel(&conversation, &cancel, &mut |delta| streamed.push_str(delta))
+            .await
+            .unwrap();
+
+        assert_eq!(streamed, "Like this.");
+        assert_eq!(
+            provider.requests()[0].prompt,
+            "Conversation so far:\nUser: What is a closure?\nAssistant: Closures capture variables.\n\nShow an example"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_prompts_with_different_params_are_sent_separately() {
+        let provider = Arc::new(MockProvider::new("reply").with_latency(Duration::from_millis(20)));
+        let service = AIService::new().with_provider(provider.clone());
+        let request = |temperature| {
+            ExplainRequest::new(ExplainInput::Topic("rust".to_string())).with_params(AiParams {
+                temperature: Some(temperature),
+                ..AiParams::default()
+            })
+        };
+
+        let (first, second) = tokio::join!(service.explain(request(0.2)), service.explain(request(1.2)));
+
+        assert!(first.is_ok() && second.is_ok());
+        let mut temperatures: Vec<_> = provider.requests().iter().map(|request| request.params.temperature).collect();
+        temperatures.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(temperatures, vec![Some(0.2), Some(1.2)]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_prompts_share_one_call() {
+        let provider = Arc::new(MockProvider::new("shared").with_latency(Duration::from_millis(20)));
+        let service = AIService::new().with_provider(provider.clone());
+
+        let (first, second) = tokio::join!(service.generate_explanation("rust"), service.generate_explanation("rust"));
+
+        assert_eq!(first.unwrap(), "shared");
+        assert_eq!(second.unwrap(), "shared");
+        assert_eq!(provider.requests().len(), 1);
+
+        // Finished requests are not cached, and dedupe can be turned off
+        service.generate_explanation("rust").await.unwrap();
+        assert_eq!(provider.requests().len(), 2);
+
+        let provider = Arc::new(MockProvider::new("sample").with_latency(Duration::from_millis(20)));
+        let service = AIService::new().with_provider(provider.clone()).with_dedupe(false);
+        let _ = tokio::join!(service.generate_explanation("rust"), service.generate_explanation("rust"));
+        assert_eq!(provider.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_hang_and_latency() {
+        let provider = MockProvider::new("late").with_hang().with_latency(Duration::from_millis(5));
+        let request = CompletionRequest::default();
+
+        let hung = tokio::time::timeout(Duration::from_millis(50), provider.complete(request.clone())).await;
+        assert!(hung.is_err(), "hang step should not complete");
+
+        let start = Instant::now();
+        assert_eq!(provider.complete(request).await.unwrap().text, "late");
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_explanation_resolves() {
+        let service = AIService::new().with_provider(Arc::new(HangingProvider));
+        let cancel = CancellationToken::new();
+
+        let request = service.generate_explanation_with_cancel("rust", &cancel);
+        let trigger = async {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            cancel.cancel();
+        };
+        let (result, _) = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            tokio::join!(request, trigger)
+        })
+        .await
+        .expect("cancelled request should not hang");
+
+        assert!(matches!(
+            result,
+            Err(GroundhogError::Internal(InternalError::Cancelled { .. }))
+        ));
+    }
+
     #[tokio::test]
     async fn test_generate_explanation_enabled() {
         let service = AIService::new().with_enabled(true);
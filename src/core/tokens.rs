@@ -0,0 +1,100 @@
+use std::ops::Range;
+
+/// Context limit assumed when no model limit is configured
+pub const DEFAULT_CONTEXT_LIMIT: usize = 8192;
+
+/// Maximum number of word characters counted as a single token
+const WORD_CHARS_PER_TOKEN: usize = 5;
+
+/// Estimate how many tokens a model would split `text` into
+///
+/// Runs of letters and digits count one token per five characters (at least
+/// one), and every other non-whitespace character counts as its own token.
+/// This errs slightly high compared to common BPE tokenizers, which is the
+/// safe direction for prompt sizing.
+pub fn estimate_tokens(text: &str) -> usize {
+    token_spans(text).count()
+}
+
+/// Byte ranges of the estimated tokens in `text`, in order
+///
+/// Uses the same rules as [`estimate_tokens`]; whitespace is never part of a span.
+pub fn token_spans(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut chars = text.char_indices().peekable();
+
+    std::iter::from_fn(move || {
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        let (start, c) = chars.next()?;
+        let mut end = start + c.len_utf8();
+        if is_word_char(c) {
+            for _ in 1..WORD_CHARS_PER_TOKEN {
+                match chars.next_if(|&(_, c)| is_word_char(c)) {
+                    Some((i, c)) => end = i + c.len_utf8(),
+                    None => break,
+                }
+            }
+        }
+        Some(start..end)
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Check whether `text` is estimated to fit within `limit` tokens
+pub fn fits_context(text: &str, limit: usize) -> bool {
+    estimate_tokens(text) <= limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: usize, expected: usize, tolerance: usize) {
+        assert!(
+            actual.abs_diff(expected) <= tolerance,
+            "estimate {} not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_estimate_empty_and_whitespace() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("  \n\t"), 0);
+    }
+
+    #[test]
+    fn test_estimate_known_strings() {
+        assert_eq!(estimate_tokens("hello world"), 2);
+        assert_close(estimate_tokens("fn main() { println!(\"hi\"); }"), 12, 3);
+        assert_close(
+            estimate_tokens("The quick brown fox jumps over the lazy dog."),
+            10,
+            2,
+        );
+    }
+
+    #[test]
+    fn test_long_words_split_into_chunks() {
+        assert_eq!(estimate_tokens("internationalization"), 4);
+        assert_eq!(estimate_tokens("snake_case_name"), 3);
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let text = "let abcdefg = 1;";
+        let spans: Vec<&str> = token_spans(text).map(|r| &text[r]).collect();
+        assert_eq!(spans, vec!["let", "abcde", "fg", "=", "1", ";"]);
+    }
+
+    #[test]
+    fn test_fits_context() {
+        assert!(fits_context("hello world", 2));
+        assert!(!fits_context("hello world", 1));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::infrastructure::error::{FileSystemError, GroundhogError};
+
+/// A completed span in Chrome's trace event format
+#[derive(Debug, Clone, Serialize)]
+struct TraceEvent {
+    name: String,
+    cat: String,
+    ph: &'static str,
+    /// Start time in microseconds since the trace began
+    ts: u64,
+    /// Duration in microseconds
+    dur: u64,
+    pid: u32,
+    tid: u64,
+    args: Map<String, Value>,
+}
+
+/// Span data kept in the registry until the span closes
+struct SpanTiming {
+    start: Instant,
+    tid: u64,
+    args: Map<String, Value>,
+}
+
+/// Tracing layer recording span timings for Chrome's trace viewer
+///
+/// Each span becomes a complete (`"ph": "X"`) event when it closes. Events
+/// are kept in memory and written by [`ChromeTraceWriter::flush`].
+pub struct ChromeTraceLayer {
+    origin: Instant,
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+/// Handle that writes the recorded trace to a file
+#[derive(Clone)]
+pub struct ChromeTraceWriter {
+    path: PathBuf,
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+/// Create a trace layer and the writer that flushes it to `path`
+pub fn chrome_trace(path: impl Into<PathBuf>) -> (ChromeTraceLayer, ChromeTraceWriter) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let layer = ChromeTraceLayer {
+        origin: Instant::now(),
+        events: Arc::clone(&events),
+    };
+    let writer = ChromeTraceWriter {
+        path: path.into(),
+        events,
+    };
+    (layer, writer)
+}
+
+impl ChromeTraceWriter {
+    /// Path the trace is written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write every span closed so far as a Chrome trace JSON document
+    pub fn flush(&self) -> Result<(), GroundhogError> {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let document = serde_json::json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        });
+
+        std::fs::write(&self.path, format!("{:#}\n", document)).map_err(|e| {
+            match e.kind() {
+                std::io::ErrorKind::PermissionDenied => FileSystemError::NotWritable { path: self.path.clone() },
+                _ => FileSystemError::Io(e),
+            }
+            .into()
+        })
+    }
+}
+
+impl<S> Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let mut visitor = ArgsVisitor(Map::new());
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanTiming {
+            start: Instant::now(),
+            tid: current_thread_id(),
+            args: visitor.0,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+            let mut visitor = ArgsVisitor(std::mem::take(&mut timing.args));
+            values.record(&mut visitor);
+            timing.args = visitor.0;
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(timing) = span.extensions_mut().remove::<SpanTiming>() else { return };
+
+        let event = TraceEvent {
+            name: span.name().to_string(),
+            cat: span.metadata().target().to_string(),
+            ph: "X",
+            ts: timing.start.saturating_duration_since(self.origin).as_micros() as u64,
+            dur: timing.start.elapsed().as_micros() as u64,
+            pid: std::process::id(),
+            tid: timing.tid,
+            args: timing.args,
+        };
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+    }
+}
+
+/// Small stable numeric id for the current thread
+fn current_thread_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static THREAD_ID: u64 = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    }
+    THREAD_ID.with(|id| *id)
+}
+
+/// Collects span fields into trace event arguments
+struct ArgsVisitor(Map<String, Value>);
+
+impl Visit for ArgsVisitor {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_closed_spans_are_written_as_complete_events() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("trace.json");
+        let (layer, writer) = chrome_trace(&path);
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("outer", files = 2);
+            let _entered = outer.enter();
+            tracing::info_span!("inner").in_scope(|| {});
+        });
+        writer.flush().unwrap();
+
+        let trace: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let names: Vec<&str> = events.iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["inner", "outer"]);
+
+        let outer = &events[1];
+        assert_eq!(outer["ph"], "X");
+        assert_eq!(outer["args"]["files"], 2);
+        assert!(outer["dur"].as_u64().unwrap() >= events[0]["dur"].as_u64().unwrap());
+    }
+}
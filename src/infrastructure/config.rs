@@ -1,11 +1,22 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, instrument, warn};
 
-use crate::infrastructure::error::{ConfigError, GroundhogError};
+use crate::core::request::ExplainDepth;
+use crate::core::tokens::DEFAULT_CONTEXT_LIMIT;
+use crate::infrastructure::error::{ConfigError, FileSystemError, GroundhogError, ParseError};
+use crate::infrastructure::http::redact_credentials;
+use crate::infrastructure::logging::REDACTED;
+
+/// `--config` value that reads the configuration from stdin
+pub const STDIN_PATH: &str = "-";
 
 /// Main configuration structure
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
 pub struct Config {
     #[serde(default)]
     pub logging: LoggingConfig,
@@ -20,9 +31,26 @@ pub struct Config {
     
     #[serde(default)]
     pub performance: PerformanceConfig,
+    
+    #[serde(default)]
+    pub tui: TuiConfig,
+    
+    /// Named overrides selected with `--profile` or `GROUNDHOG_PROFILE`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Partial `[ai]` and `[output]` settings merged over the base sections
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai: Option<serde_json::Value>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct LoggingConfig {
     #[serde(default = "default_log_level")]
     pub level: LogLevel,
@@ -37,9 +65,23 @@ pub struct LoggingConfig {
     
     #[serde(default = "default_false")]
     pub thread_ids: bool,
+    
+    /// Optional file receiving every event as a JSON line
+    pub json_file: Option<PathBuf>,
+    
+    /// Optional file receiving span timings in Chrome trace format
+    pub trace_file: Option<PathBuf>,
+    
+    /// How often the log file is rotated
+    #[serde(default)]
+    pub rotation: LogRotation,
+    
+    /// Per-module level directives such as `tui=warn`, applied on top of `level`
+    #[serde(default)]
+    pub directives: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum LogLevel {
     Trace,
     Debug,
@@ -48,43 +90,138 @@ pub enum LogLevel {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum LogFormat {
     Pretty,
     Json,
     Compact,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    #[default]
+    Never,
+    Daily,
+    Hourly,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct CommandsConfig {
     pub default: Option<String>,
     pub explain: Option<ExplainConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ExplainConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
     
     pub format: Option<String>,
+    
+    /// Default `--depth` of explanations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<ExplainDepth>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AiConfig {
     pub provider: AiProvider,
+    
+    /// Model name; left empty, the provider's default model is used
+    #[serde(default)]
     pub model: String,
     pub api_key: Option<String>,
     pub endpoint: Option<String>,
+    
+    /// Maximum number of prompt tokens the model accepts
+    #[serde(default = "default_context_limit")]
+    pub context_limit: usize,
+    
+    /// Sampling seed for reproducible output, for providers that support it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    
+    /// Sampling parameters sent with every request; unset values use provider defaults
+    #[serde(default)]
+    pub params: AiParams,
+    
+    /// Text put ahead of every prompt except `--raw` ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_prefix: Option<String>,
+    
+    /// Text put after every prompt except `--raw` ones, e.g. "Answer with citations."
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_suffix: Option<String>,
+    
+    /// Proxy used instead of the `HTTP_PROXY`/`HTTPS_PROXY` environment variables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+    
+    /// Client-side limit on how often requests are sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+    
+    /// Tokens of chat history sent with each REPL or TUI turn; defaults to half of `context_limit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_limit: Option<usize>,
+}
+
+/// `[ai.params]` sampling parameters
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AiParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+}
+
+/// `[ai.rate_limit]` settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RateLimitConfig {
+    /// Average number of requests allowed per minute
+    pub requests_per_minute: u32,
+    /// Requests that may be sent back to back after an idle period
+    #[serde(default = "default_burst")]
+    pub burst: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `[ai.proxy]` settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct ProxyConfig {
+    /// Proxy for every provider request, e.g. "http://proxy.example.com:8080"
+    pub url: String,
+    /// Comma-separated hosts reached directly; defaults to `NO_PROXY`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum AiProvider {
     OpenAI,
     Anthropic,
     Local,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl AiProvider {
+    /// Every supported provider
+    pub const ALL: [AiProvider; 3] = [AiProvider::OpenAI, AiProvider::Anthropic, AiProvider::Local];
+}
+
+/// The name used in configuration files, e.g. `OpenAI`
+impl fmt::Display for AiProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AiProvider::OpenAI => "OpenAI",
+            AiProvider::Anthropic => "Anthropic",
+            AiProvider::Local => "Local",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct OutputConfig {
     #[serde(default = "default_output_format")]
     pub format: String,
@@ -94,10 +231,26 @@ pub struct OutputConfig {
     
     #[serde(default = "default_pager")]
     pub pager: String,
+    
+    /// Language code AI responses should be written in
+    pub language: Option<String>,
+    
+    /// Truncate printed output to this many lines; `--out` files are never truncated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    
+    /// Syntax highlighting theme for code blocks, e.g. "base16-ocean.dark"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    
+    /// Print explanations as they arrive; unset streams only to a terminal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct PerformanceConfig {
+    /// Largest input file to process, in megabytes (MiB)
     #[serde(default = "default_max_file_size")]
     pub max_file_size: u64,
     
@@ -106,6 +259,130 @@ pub struct PerformanceConfig {
     
     #[serde(default = "default_threads")]
     pub threads: usize,
+    
+    #[serde(default)]
+    pub retry: RetryConfig,
+    
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    
+    #[serde(default)]
+    pub cache: CacheConfig,
+}
+
+/// Per-command timing log, one JSON line per command
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MetricsConfig {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+    
+    /// Log file, defaulting to `~/.groundhog/metrics.jsonl`
+    pub path: Option<PathBuf>,
+}
+
+/// Cache of AI responses reused by `explain`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CacheConfig {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+    
+    /// Cache directory, defaulting to `~/.groundhog/cache`
+    pub dir: Option<PathBuf>,
+}
+
+/// Retry behaviour for failed AI requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RetryConfig {
+    /// Total attempts including the first one
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    
+    /// Delay before the first retry, doubled for each further retry
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+    
+    /// Upper bound for a single retry delay
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+    
+    /// Fraction of each delay that is randomized, from 0.0 (none) to 1.0
+    #[serde(default = "default_retry_jitter")]
+    pub jitter: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+pub struct TuiConfig {
+    #[serde(default)]
+    pub keys: KeyBindingsConfig,
+}
+
+/// Key names for TUI actions, e.g. "q", "ctrl-c", "space", "up"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct KeyBindingsConfig {
+    #[serde(default = "default_key_quit")]
+    pub quit: String,
+    
+    #[serde(default = "default_key_submit")]
+    pub submit: String,
+    
+    #[serde(default = "default_key_clear")]
+    pub clear: String,
+    
+    #[serde(default = "default_key_cancel")]
+    pub cancel: String,
+    
+    #[serde(default = "default_key_scroll_up")]
+    pub scroll_up: String,
+    
+    #[serde(default = "default_key_scroll_down")]
+    pub scroll_down: String,
+    
+    #[serde(default = "default_key_export")]
+    pub export: String,
+    
+    #[serde(default = "default_key_help")]
+    pub help: String,
+    
+    #[serde(default = "default_key_palette")]
+    pub palette: String,
+    
+    #[serde(default = "default_key_reload")]
+    pub reload: String,
+    
+    #[serde(default = "default_key_log_level")]
+    pub log_level: String,
+    
+    #[serde(default = "default_key_model")]
+    pub model: String,
+}
+
+/// File format of a configuration file, chosen by extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detect the format from a path's extension, defaulting to TOML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => Self::Yaml,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+}
+
+/// A candidate configuration file location
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigSource {
+    pub path: PathBuf,
+    /// Whether a file exists at this path
+    pub exists: bool,
+    /// Whether this is the file `load_hierarchical` would load
+    pub active: bool,
 }
 
 // Default value functions
@@ -116,6 +393,24 @@ fn default_pager() -> String { "auto".to_string() }
 fn default_max_file_size() -> u64 { 100 }
 fn default_timeout() -> u64 { 30 }
 fn default_threads() -> usize { 4 }
+fn default_context_limit() -> usize { DEFAULT_CONTEXT_LIMIT }
+fn default_burst() -> u32 { 1 }
+fn default_retry_max_attempts() -> u32 { 3 }
+fn default_retry_base_delay_ms() -> u64 { 500 }
+fn default_retry_max_delay_ms() -> u64 { 10_000 }
+fn default_retry_jitter() -> f64 { 0.5 }
+fn default_key_quit() -> String { "ctrl-c".to_string() }
+fn default_key_submit() -> String { "enter".to_string() }
+fn default_key_clear() -> String { "ctrl-l".to_string() }
+fn default_key_cancel() -> String { "esc".to_string() }
+fn default_key_scroll_up() -> String { "up".to_string() }
+fn default_key_scroll_down() -> String { "down".to_string() }
+fn default_key_export() -> String { "ctrl-s".to_string() }
+fn default_key_help() -> String { "?".to_string() }
+fn default_key_palette() -> String { "ctrl-p".to_string() }
+fn default_key_reload() -> String { "ctrl-r".to_string() }
+fn default_key_log_level() -> String { "f2".to_string() }
+fn default_key_model() -> String { "f3".to_string() }
 fn default_true() -> bool { true }
 fn default_false() -> bool { false }
 
@@ -127,8 +422,12 @@ impl Default for LoggingConfig {
             level: default_log_level(),
             format: default_log_format(),
             file: None,
+            json_file: None,
+            trace_file: None,
             timestamps: default_true(),
             thread_ids: default_false(),
+            rotation: LogRotation::default(),
+            directives: Vec::new(),
         }
     }
 }
@@ -147,6 +446,7 @@ impl Default for ExplainConfig {
         Self {
             enabled: default_true(),
             format: None,
+            depth: None,
         }
     }
 }
@@ -157,6 +457,10 @@ impl Default for OutputConfig {
             format: default_output_format(),
             color: default_true(),
             pager: default_pager(),
+            language: None,
+            max_lines: None,
+            theme: None,
+            stream: None,
         }
     }
 }
@@ -167,16 +471,335 @@ impl Default for PerformanceConfig {
             max_file_size: default_max_file_size(),
             timeout: default_timeout(),
             threads: default_threads(),
+            retry: RetryConfig::default(),
+            metrics: MetricsConfig::default(),
+            cache: CacheConfig::default(),
+        }
+    }
+}
+
+impl PerformanceConfig {
+    /// `max_file_size` converted to bytes
+    pub fn max_file_size_bytes(&self) -> u64 {
+        self.max_file_size.saturating_mul(1024 * 1024)
+    }
+}
+
+impl MetricsConfig {
+    /// File metrics are appended to, or `None` when disabled or no home directory is known
+    pub fn resolve_path(&self) -> Option<PathBuf> {
+        if !self.enabled {
+            return None;
+        }
+        self.path
+            .clone()
+            .or_else(|| dirs::home_dir().map(|home| home.join(".groundhog").join("metrics.jsonl")))
+    }
+}
+
+impl CacheConfig {
+    /// Cache directory, or `None` when no home directory is known
+    pub fn directory(&self) -> Option<PathBuf> {
+        self.dir.clone().or_else(|| dirs::home_dir().map(|home| home.join(".groundhog").join("cache")))
+    }
+    
+    /// Directory responses are cached in, or `None` when disabled or no home directory is known
+    pub fn resolve_dir(&self) -> Option<PathBuf> {
+        self.enabled.then(|| self.directory()).flatten()
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            base_delay_ms: default_retry_base_delay_ms(),
+            max_delay_ms: default_retry_max_delay_ms(),
+            jitter: default_retry_jitter(),
+        }
+    }
+}
+
+impl AiProvider {
+    /// Environment variable holding the API key for this provider, if it needs one
+    pub fn api_key_env(&self) -> Option<&'static str> {
+        match self {
+            AiProvider::OpenAI => Some("OPENAI_API_KEY"),
+            AiProvider::Anthropic => Some("ANTHROPIC_API_KEY"),
+            AiProvider::Local => None,
+        }
+    }
+    
+    /// Models this provider is known to serve, or an empty list when any name is accepted
+    ///
+    /// Dated and suffixed variants such as `gpt-4o-2024-08-06` count as
+    /// known. The lists change over time, so unknown models only warn.
+    pub fn known_models(&self) -> &'static [&'static str] {
+        match self {
+            AiProvider::OpenAI => &["gpt-4o", "gpt-4o-mini", "gpt-4-turbo", "gpt-4", "gpt-3.5-turbo", "o1", "o1-mini", "o3-mini"],
+            AiProvider::Anthropic => &[
+                "claude-3-5-sonnet",
+                "claude-3-5-haiku",
+                "claude-3-7-sonnet",
+                "claude-3-opus",
+                "claude-3-sonnet",
+                "claude-3-haiku",
+            ],
+            // Local servers run whatever models were pulled
+            AiProvider::Local => &[],
+        }
+    }
+    
+    /// Model used when `ai.model` is left empty
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            AiProvider::OpenAI => "gpt-4o-mini",
+            AiProvider::Anthropic => "claude-3-5-haiku-latest",
+            AiProvider::Local => "llama3",
+        }
+    }
+}
+
+impl AiConfig {
+    /// Settings for `model` served by `provider`, with every other setting at its default
+    pub fn new(provider: AiProvider, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            api_key: None,
+            endpoint: None,
+            context_limit: default_context_limit(),
+            seed: None,
+            params: AiParams::default(),
+            prompt_prefix: None,
+            prompt_suffix: None,
+            proxy: None,
+            rate_limit: None,
+            history_limit: None,
+        }
+    }
+    
+    /// Resolve the API key from the config, falling back to the provider's environment variable
+    pub fn resolve_api_key(&self) -> Option<String> {
+        self.resolve_api_key_with(|name| std::env::var(name).ok())
+    }
+    
+    /// Whether the provider is known to serve `model`
+    pub fn is_known_model(&self) -> bool {
+        let known = self.provider.known_models();
+        known.is_empty()
+            || known.iter().any(|name| {
+                self.model == *name || self.model.strip_prefix(name).is_some_and(|variant| variant.starts_with('-'))
+            })
+    }
+    
+    /// Warning for a model the provider is not known to serve, likely a typo
+    pub fn model_warning(&self) -> Option<String> {
+        (!self.model.trim().is_empty() && !self.is_known_model()).then(|| {
+            format!(
+                "Unknown {} model '{}' in ai.model; known models: {}",
+                self.provider,
+                self.model,
+                self.provider.known_models().join(", ")
+            )
+        })
+    }
+    
+    /// Whether requests can be sent: a model is named and any required API key resolves
+    pub fn is_usable(&self) -> bool {
+        !self.model.trim().is_empty() && (self.provider.api_key_env().is_none() || self.resolve_api_key().is_some())
+    }
+    
+    /// These settings pointed at `provider` instead
+    ///
+    /// For another provider the model becomes its default, and the API key and
+    /// endpoint are dropped, so the key comes from the provider's environment
+    /// variable. Every other setting is kept.
+    pub fn for_provider(&self, provider: &AiProvider) -> AiConfig {
+        if *provider == self.provider {
+            return self.clone();
+        }
+        AiConfig {
+            provider: provider.clone(),
+            model: provider.default_model().to_string(),
+            api_key: None,
+            endpoint: None,
+            ..self.clone()
+        }
+    }
+    
+    pub(crate) fn resolve_api_key_with(&self, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+        self.api_key
+            .clone()
+            .or_else(|| self.provider.api_key_env().and_then(env))
+            .filter(|key| !key.trim().is_empty())
+    }
+    
+    /// Check the model, endpoint and API key settings, returning every problem found
+    fn validation_errors_with(&self, env: impl Fn(&str) -> Option<String>) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        
+        if self.model.trim().is_empty() {
+            errors.push(ConfigError::InvalidValue {
+                key: "ai.model".to_string(),
+                value: self.model.clone(),
+                expected: "non-empty model name".to_string(),
+            });
+        }
+        
+        if let Some(endpoint) = &self.endpoint
+            && !is_http_url(endpoint)
+        {
+            errors.push(ConfigError::InvalidValue {
+                key: "ai.endpoint".to_string(),
+                value: endpoint.clone(),
+                expected: "http or https URL".to_string(),
+            });
+        }
+        
+        if let Some(proxy) = &self.proxy
+            && !is_http_url(&proxy.url)
+        {
+            errors.push(ConfigError::InvalidValue {
+                key: "ai.proxy.url".to_string(),
+                value: proxy.url.clone(),
+                expected: "http or https URL".to_string(),
+            });
+        }
+        
+        if let Some(rate_limit) = &self.rate_limit {
+            for (key, value) in [("requests_per_minute", rate_limit.requests_per_minute), ("burst", rate_limit.burst)] {
+                if value == 0 {
+                    errors.push(ConfigError::InvalidValue {
+                        key: format!("ai.rate_limit.{}", key),
+                        value: "0".to_string(),
+                        expected: "positive integer".to_string(),
+                    });
+                }
+            }
+        }
+        
+        if self.context_limit == 0 {
+            errors.push(ConfigError::InvalidValue {
+                key: "ai.context_limit".to_string(),
+                value: "0".to_string(),
+                expected: "positive integer".to_string(),
+            });
+        }
+        
+        if self.history_limit == Some(0) {
+            errors.push(ConfigError::InvalidValue {
+                key: "ai.history_limit".to_string(),
+                value: "0".to_string(),
+                expected: "positive integer".to_string(),
+            });
+        }
+        
+        errors.extend(self.params.validation_errors());
+        
+        if let Some(env_name) = self.provider.api_key_env()
+            && self.resolve_api_key_with(env).is_none()
+        {
+            errors.push(ConfigError::MissingKey {
+                key: format!("ai.api_key (or {})", env_name),
+            });
+        }
+        
+        errors
+    }
+}
+
+/// Whether `value` is an absolute http or https URL with a host
+fn is_http_url(value: &str) -> bool {
+    url::Url::parse(value)
+        .map(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+        .unwrap_or(false)
+}
+
+impl AiParams {
+    /// Check that temperature is within 0..=2, top_p within 0..=1 and max_tokens is positive
+    pub fn validate(&self) -> Result<(), GroundhogError> {
+        match self.validation_errors().into_iter().next() {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+    
+    /// Every out of range parameter, in the order checked by [`AiParams::validate`]
+    pub fn validation_errors(&self) -> Vec<ConfigError> {
+        let invalid = |key: &str, value: String, expected: &str| ConfigError::InvalidValue {
+            key: format!("ai.params.{}", key),
+            value,
+            expected: expected.to_string(),
+        };
+        let mut errors = Vec::new();
+        
+        if let Some(temperature) = self.temperature
+            && !(0.0..=2.0).contains(&temperature)
+        {
+            errors.push(invalid("temperature", temperature.to_string(), "number between 0 and 2"));
+        }
+        if let Some(top_p) = self.top_p
+            && !(0.0..=1.0).contains(&top_p)
+        {
+            errors.push(invalid("top_p", top_p.to_string(), "number between 0 and 1"));
+        }
+        if self.max_tokens == Some(0) {
+            errors.push(invalid("max_tokens", "0".to_string(), "positive integer"));
+        }
+        
+        errors
+    }
+}
+
+impl OutputConfig {
+    /// Resolve the response language from this config, falling back to `LANG`
+    pub fn resolve_language(&self) -> Option<String> {
+        self.language.clone().or_else(|| {
+            std::env::var("LANG")
+                .ok()
+                .and_then(|locale| language_from_locale(&locale))
+        })
+    }
+}
+
+/// Extract a language code from a locale string such as `fr_FR.UTF-8`
+fn language_from_locale(locale: &str) -> Option<String> {
+    let language = locale.split(['.', '@']).next().unwrap_or_default();
+    match language {
+        "" | "C" | "POSIX" => None,
+        language => Some(language.to_string()),
+    }
+}
+
+impl Default for KeyBindingsConfig {
+    fn default() -> Self {
+        Self {
+            quit: default_key_quit(),
+            submit: default_key_submit(),
+            clear: default_key_clear(),
+            cancel: default_key_cancel(),
+            scroll_up: default_key_scroll_up(),
+            scroll_down: default_key_scroll_down(),
+            export: default_key_export(),
+            help: default_key_help(),
+            palette: default_key_palette(),
+            reload: default_key_reload(),
+            log_level: default_key_log_level(),
+            model: default_key_model(),
         }
     }
 }
 
 impl Config {
     /// Load configuration from file with fallback to defaults
-    #[instrument(name = "config.load", fields(path = %path.as_ref().display()))]
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, GroundhogError> {
-        let path = path.as_ref();
-        
+        Self::read_file(path.as_ref()).map(Self::with_default_model)
+    }
+    
+    /// Load a configuration file as written, before profiles and default models
+    #[instrument(name = "config.load", fields(path = %path.display()))]
+    fn read_file(path: &Path) -> Result<Self, GroundhogError> {
         debug!("Loading configuration from file");
         
         if !path.exists() {
@@ -187,22 +810,97 @@ impl Config {
         let content = std::fs::read_to_string(path)
             .map_err(|_e| ConfigError::NotFound { path: path.to_path_buf() })?;
         
-        let config: Config = toml::from_str(&content)
-            .map_err(|e| ConfigError::InvalidFormat {
-                path: path.to_path_buf(),
-                line: None, // TOML errors don't provide line_col in this version
-                source: Box::new(e),
-            })?;
+        let config = Self::parse(&content, ConfigFormat::from_path(path), path)?;
         
         info!("Configuration loaded successfully");
         Ok(config)
     }
     
+    /// Parse configuration from a string in the given format
+    pub fn from_str(content: &str, format: ConfigFormat) -> Result<Self, GroundhogError> {
+        Self::parse(content, format, Path::new("<string>")).map(Self::with_default_model)
+    }
+    
+    /// Read and parse configuration from a reader such as stdin
+    pub fn from_reader<R: Read>(reader: R, format: ConfigFormat) -> Result<Self, GroundhogError> {
+        Self::read(reader, format).map(Self::with_default_model)
+    }
+    
+    /// Read configuration content as written, before profiles and default models
+    fn read<R: Read>(mut reader: R, format: ConfigFormat) -> Result<Self, GroundhogError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content).map_err(FileSystemError::Io)?;
+        Self::parse(&content, format, Path::new(STDIN_PATH))
+    }
+    
+    /// Parse configuration content, naming `source` in errors
+    fn parse(content: &str, format: ConfigFormat, source: &Path) -> Result<Self, GroundhogError> {
+        let config: Self = match format {
+            ConfigFormat::Toml => toml::from_str(content)
+                .map_err(|e| ConfigError::InvalidFormat {
+                    path: source.to_path_buf(),
+                    line: None, // TOML errors don't provide line_col in this version
+                    source: Box::new(e),
+                })?,
+            ConfigFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| ParseError::Yaml {
+                    input: source.display().to_string(),
+                    source: Box::new(e),
+                })?,
+            ConfigFormat::Json => serde_json::from_str(content)
+                .map_err(|e| ParseError::Json {
+                    input: source.display().to_string(),
+                    line: Some(e.line()),
+                    column: Some(e.column()),
+                    source: e,
+                })?,
+        };
+        Ok(config)
+    }
+    
+    /// Use the provider's default model when `ai.model` is empty
+    ///
+    /// Applied once the configuration is complete, so a profile that switches
+    /// provider gets that provider's default rather than the base one's.
+    fn with_default_model(mut self) -> Self {
+        if let Some(ai) = &mut self.ai
+            && ai.model.trim().is_empty()
+        {
+            ai.model = ai.provider.default_model().to_string();
+            debug!(provider = ?ai.provider, model = %ai.model, "Using the provider's default model");
+        }
+        self
+    }
+    
+    /// Settings that are allowed but probably wrong, such as an unknown model
+    pub fn warnings(&self) -> Vec<String> {
+        self.ai.as_ref().and_then(AiConfig::model_warning).into_iter().collect()
+    }
+    
     /// Load configuration with hierarchical search
+    ///
+    /// The selected profile, from `profile` or else `GROUNDHOG_PROFILE`, is
+    /// merged over the loaded configuration.
     #[instrument(name = "config.load_hierarchical")]
-    pub fn load_hierarchical(config_path: Option<PathBuf>) -> Result<Self, GroundhogError> {
+    pub fn load_hierarchical(config_path: Option<PathBuf>, profile: Option<String>) -> Result<Self, GroundhogError> {
+        let config = Self::load_base(config_path)?;
+        
+        let config = match profile.or_else(|| std::env::var("GROUNDHOG_PROFILE").ok()) {
+            Some(name) => config.with_profile(&name)?,
+            None => config,
+        };
+        Ok(config.with_default_model())
+    }
+    
+    /// Load the first configuration file found, without applying a profile
+    fn load_base(config_path: Option<PathBuf>) -> Result<Self, GroundhogError> {
         info!("Loading configuration with hierarchical search");
         
+        if config_path.as_deref() == Some(Path::new(STDIN_PATH)) {
+            info!("Reading TOML configuration from stdin");
+            return Self::read(std::io::stdin().lock(), ConfigFormat::Toml);
+        }
+        
         let search_paths = Self::get_config_search_paths(config_path);
         
         for path in search_paths {
@@ -210,7 +908,7 @@ impl Config {
             
             if path.exists() {
                 info!(path = %path.display(), "Found configuration file");
-                return Self::load_from_file(&path);
+                return Self::read_file(&path);
             }
         }
         
@@ -218,8 +916,52 @@ impl Config {
         Ok(Self::default())
     }
     
+    /// Merge the named `[profiles.<name>]` table over the `[ai]` and `[output]` sections
+    ///
+    /// Leaves an empty `ai.model` for [`Config::with_default_model`] to fill in.
+    fn with_profile(mut self, name: &str) -> Result<Self, GroundhogError> {
+        let profile = self.profiles.get(name).cloned().ok_or_else(|| {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            ConfigError::InvalidValue {
+                key: "profile".to_string(),
+                value: name.to_string(),
+                expected: if known.is_empty() {
+                    "a profile defined under [profiles]".to_string()
+                } else {
+                    format!("one of: {}", known.join(", "))
+                },
+            }
+        })?;
+        
+        info!(profile = name, "Applying configuration profile");
+        if let Some(ai) = profile.ai {
+            self.ai = merge_section(&self.ai, ai, &format!("profiles.{}.ai", name))?;
+        }
+        if let Some(output) = profile.output {
+            self.output = merge_section(&self.output, output, &format!("profiles.{}.output", name))?;
+        }
+        Ok(self)
+    }
+    
+    /// Resolve every candidate configuration source in order of precedence,
+    /// marking which ones exist and which one would be loaded
+    #[instrument(name = "config.resolve_sources")]
+    pub fn resolve_sources(config_path: Option<PathBuf>) -> Vec<ConfigSource> {
+        let mut found_active = false;
+        
+        Self::get_config_search_paths(config_path)
+            .into_iter()
+            .map(|path| {
+                let exists = path.exists();
+                let active = exists && !found_active;
+                found_active |= active;
+                ConfigSource { path, exists, active }
+            })
+            .collect()
+    }
+    
     /// Get configuration file search paths in order of precedence
-    fn get_config_search_paths(explicit_path: Option<PathBuf>) -> Vec<PathBuf> {
+    pub fn get_config_search_paths(explicit_path: Option<PathBuf>) -> Vec<PathBuf> {
         let mut paths = Vec::new();
         
         // 1. Explicit path from command line
@@ -235,49 +977,234 @@ impl Config {
         // 3. Current directory
         paths.push(PathBuf::from("./groundhog.toml"));
         
-        // 4. User config directory
+        // 4. Platform user config directory, e.g. ~/.config or %APPDATA%
+        if let Some(config_dir) = dirs::config_dir() {
+            paths.push(config_dir.join("groundhog").join("config.toml"));
+        }
+        
+        // 5. Home directory, kept for existing installs
         if let Some(home) = dirs::home_dir() {
             paths.push(home.join(".groundhog").join("config.toml"));
         }
         
-        // 5. System-wide config
-        paths.push(PathBuf::from("/etc/groundhog/config.toml"));
+        // 6. System-wide config
+        paths.extend(Self::system_config_path());
         
         paths
     }
     
-    /// Validate configuration values
+    /// System-wide configuration file for this platform, if it has one
+    #[cfg(unix)]
+    fn system_config_path() -> Option<PathBuf> {
+        Some(PathBuf::from("/etc/groundhog/config.toml"))
+    }
+    
+    /// System-wide configuration file for this platform, if it has one
+    #[cfg(windows)]
+    fn system_config_path() -> Option<PathBuf> {
+        std::env::var_os("PROGRAMDATA").map(|dir| PathBuf::from(dir).join("groundhog").join("config.toml"))
+    }
+    
+    /// System-wide configuration file for this platform, if it has one
+    #[cfg(not(any(unix, windows)))]
+    fn system_config_path() -> Option<PathBuf> {
+        None
+    }
+    
+    /// Validate configuration values, stopping at the first problem
+    ///
+    /// [`Config::warnings`] are logged without failing validation.
     #[instrument(name = "config.validate")]
     pub fn validate(&self) -> Result<(), GroundhogError> {
         debug!("Validating configuration");
         
+        for warning in self.warnings() {
+            warn!("{}", warning);
+        }
+        
+        if let Some(error) = self.validation_errors().into_iter().next() {
+            return Err(error.into());
+        }
+        
+        info!("Configuration validation passed");
+        Ok(())
+    }
+    
+    /// Validate configuration values, reporting every problem at once
+    ///
+    /// A single problem is returned as is; several are wrapped in
+    /// [`ConfigError::Multiple`]. [`Config::warnings`] are only logged.
+    #[instrument(name = "config.validate_all")]
+    pub fn validate_all(&self) -> Result<(), GroundhogError> {
+        for warning in self.warnings() {
+            warn!("{}", warning);
+        }
+        
+        let mut errors = self.validation_errors();
+        match errors.len() {
+            0 => {
+                info!("Configuration validation passed");
+                Ok(())
+            }
+            1 => Err(errors.remove(0).into()),
+            count => {
+                debug!(count, "Configuration has several problems");
+                Err(ConfigError::Multiple { errors }.into())
+            }
+        }
+    }
+    
+    /// Every invalid setting, in the order [`Config::validate`] checks them
+    pub(crate) fn validation_errors(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        
+        if self.output.max_lines == Some(0) {
+            errors.push(ConfigError::InvalidValue {
+                key: "output.max_lines".to_string(),
+                value: "0".to_string(),
+                expected: "positive integer".to_string(),
+            });
+        }
+        
         // Validate performance settings
         if self.performance.max_file_size == 0 {
-            return Err(ConfigError::InvalidValue {
+            errors.push(ConfigError::InvalidValue {
                 key: "performance.max_file_size".to_string(),
                 value: "0".to_string(),
                 expected: "positive integer".to_string(),
-            }.into());
+            });
         }
         
         if self.performance.timeout == 0 {
-            return Err(ConfigError::InvalidValue {
+            errors.push(ConfigError::InvalidValue {
                 key: "performance.timeout".to_string(),
                 value: "0".to_string(),
                 expected: "positive integer".to_string(),
-            }.into());
+            });
         }
         
         if self.performance.threads == 0 {
-            return Err(ConfigError::InvalidValue {
+            errors.push(ConfigError::InvalidValue {
                 key: "performance.threads".to_string(),
                 value: "0".to_string(),
                 expected: "positive integer".to_string(),
-            }.into());
+            });
         }
         
-        info!("Configuration validation passed");
-        Ok(())
+        let retry = &self.performance.retry;
+        if retry.max_attempts == 0 {
+            errors.push(ConfigError::InvalidValue {
+                key: "performance.retry.max_attempts".to_string(),
+                value: "0".to_string(),
+                expected: "positive integer".to_string(),
+            });
+        }
+        
+        if retry.base_delay_ms > retry.max_delay_ms {
+            errors.push(ConfigError::InvalidValue {
+                key: "performance.retry.base_delay_ms".to_string(),
+                value: retry.base_delay_ms.to_string(),
+                expected: format!("at most performance.retry.max_delay_ms ({})", retry.max_delay_ms),
+            });
+        }
+        
+        if !(0.0..=1.0).contains(&retry.jitter) {
+            errors.push(ConfigError::InvalidValue {
+                key: "performance.retry.jitter".to_string(),
+                value: retry.jitter.to_string(),
+                expected: "number between 0.0 and 1.0".to_string(),
+            });
+        }
+        
+        if let Some(ai) = &self.ai {
+            errors.extend(ai.validation_errors_with(|name| std::env::var(name).ok()));
+        }
+        
+        errors
+    }
+    
+    /// Context limit of the configured model, or the default when no model is set
+    pub fn context_limit(&self) -> usize {
+        self.ai.as_ref().map_or_else(default_context_limit, |ai| ai.context_limit)
+    }
+    
+    /// Sampling parameters of the configured model, or provider defaults when no model is set
+    pub fn ai_params(&self) -> AiParams {
+        self.ai.as_ref().map(|ai| ai.params.clone()).unwrap_or_default()
+    }
+    
+    /// Sampling seed of the configured model, if any
+    pub fn seed(&self) -> Option<u64> {
+        self.ai.as_ref().and_then(|ai| ai.seed)
+    }
+    
+    /// JSON Schema of the configuration file, for editors to validate and complete it
+    ///
+    /// Field documentation becomes the property descriptions, and defaults
+    /// are included.
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(Config).to_value()
+    }
+    
+    /// Settings as commands use them, with the API key and answer language resolved from the environment
+    ///
+    /// Profiles are left out, since the selected one is already merged in.
+    pub fn effective(&self) -> Config {
+        let mut config = self.clone();
+        if let Some(ai) = config.ai.as_mut() {
+            ai.api_key = ai.resolve_api_key();
+        }
+        config.output.language = config.output.resolve_language();
+        config.profiles.clear();
+        config
+    }
+    
+    /// Copy with secrets masked, safe to print or attach to a bug report
+    ///
+    /// API keys, including those in profiles, become `***`, as do passwords
+    /// in the endpoint and proxy URLs.
+    pub fn to_redacted(&self) -> Config {
+        let mut config = self.clone();
+        if let Some(ai) = config.ai.as_mut() {
+            if ai.api_key.is_some() {
+                ai.api_key = Some(REDACTED.to_string());
+            }
+            ai.endpoint = ai.endpoint.as_deref().map(redact_credentials);
+            if let Some(proxy) = ai.proxy.as_mut() {
+                proxy.url = redact_credentials(&proxy.url);
+            }
+        }
+        for profile in config.profiles.values_mut() {
+            if let Some(key) = profile.ai.as_mut().and_then(|ai| ai.get_mut("api_key")) {
+                *key = serde_json::Value::from(REDACTED);
+            }
+        }
+        config
+    }
+    
+    /// Compare two configurations setting by setting
+    ///
+    /// Returns `(key, this_value, other_value)` for every dotted key whose
+    /// value differs, sorted by key.
+    pub fn diff(&self, other: &Config) -> Vec<(String, String, String)> {
+        let this = flatten_settings(self);
+        let other = flatten_settings(other);
+        
+        let mut keys: Vec<&String> = this.keys().chain(other.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        
+        // Unset optional values are treated the same as missing ones
+        let lookup = |settings: &BTreeMap<String, serde_json::Value>, key: &str| {
+            settings.get(key).filter(|value| !value.is_null()).cloned()
+        };
+        
+        keys.into_iter()
+            .filter_map(|key| {
+                let (a, b) = (lookup(&this, key), lookup(&other, key));
+                (a != b).then(|| (key.clone(), display_setting(a.as_ref()), display_setting(b.as_ref())))
+            })
+            .collect()
     }
     
     /// Create a default configuration file
@@ -298,17 +1225,28 @@ impl Config {
         }
         
         let default_config = Self::default();
-        let toml_content = toml::to_string_pretty(&default_config)
-            .map_err(|e| ConfigError::InvalidFormat {
-                path: path.to_path_buf(),
-                line: None,
-                source: Box::new(e),
-            })?;
+        let serialized = match ConfigFormat::from_path(path) {
+            ConfigFormat::Toml => toml::to_string_pretty(&default_config)
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+            ConfigFormat::Yaml => serde_yaml::to_string(&default_config)
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+            ConfigFormat::Json => serde_json::to_string_pretty(&default_config)
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+        }
+        .map_err(|source| ConfigError::InvalidFormat {
+            path: path.to_path_buf(),
+            line: None,
+            source,
+        })?;
         
-        let content = format!(
-            "# Groundhog Configuration File\n# Version: 0.1.0\n\n{}",
-            toml_content
-        );
+        // JSON has no comment syntax, so only TOML and YAML get a header
+        let content = match ConfigFormat::from_path(path) {
+            ConfigFormat::Json => format!("{}\n", serialized),
+            _ => format!(
+                "# Groundhog Configuration File\n# Version: 0.1.0\n\n{}",
+                serialized
+            ),
+        };
         
         std::fs::write(path, content)
             .map_err(|e| ConfigError::InvalidFormat {
@@ -322,6 +1260,73 @@ impl Config {
     }
 }
 
+/// TOML, as in a configuration file, with secrets masked as by [`Config::to_redacted`]
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let toml = toml::to_string_pretty(&self.to_redacted()).map_err(|_| fmt::Error)?;
+        f.write_str(&toml)
+    }
+}
+
+/// Deep-merge `overlay` over a serialized section and deserialize the result
+fn merge_section<T>(base: &T, overlay: serde_json::Value, key: &str) -> Result<T, GroundhogError>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+{
+    fn merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+        match (base, overlay) {
+            (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+                for (key, value) in overlay {
+                    merge(base.entry(key).or_insert(serde_json::Value::Null), value);
+                }
+            }
+            (base, overlay) => *base = overlay,
+        }
+    }
+    
+    let invalid = |e: serde_json::Error| ConfigError::InvalidValue {
+        key: key.to_string(),
+        value: e.to_string(),
+        expected: "settings valid for the section they override".to_string(),
+    };
+    
+    let mut value = serde_json::to_value(base).map_err(invalid)?;
+    merge(&mut value, overlay);
+    Ok(serde_json::from_value(value).map_err(invalid)?)
+}
+
+/// Flatten a configuration into dotted keys such as `performance.threads`
+fn flatten_settings(config: &Config) -> BTreeMap<String, serde_json::Value> {
+    fn walk(prefix: &str, value: serde_json::Value, out: &mut BTreeMap<String, serde_json::Value>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                    walk(&key, value, out);
+                }
+            }
+            leaf => {
+                out.insert(prefix.to_string(), leaf);
+            }
+        }
+    }
+    
+    let mut out = BTreeMap::new();
+    if let Ok(value) = serde_json::to_value(config) {
+        walk("", value, &mut out);
+    }
+    out
+}
+
+/// Render a flattened setting for display
+fn display_setting(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None => "(unset)".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -350,6 +1355,193 @@ mod tests {
         config = Config::default();
         config.performance.threads = 0;
         assert!(config.validate().is_err());
+        
+        // Reset and test invalid retry settings
+        config = Config::default();
+        config.performance.retry.jitter = 1.5;
+        assert!(config.validate().is_err());
+        
+        config = Config::default();
+        config.performance.retry.base_delay_ms = config.performance.retry.max_delay_ms + 1;
+        assert!(config.validate().is_err());
+    }
+    
+    #[test]
+    fn test_validate_all_reports_every_problem() {
+        let mut config = Config::default();
+        config.performance.max_file_size = 0;
+        config.performance.timeout = 0;
+        config.performance.threads = 0;
+        config.performance.retry.max_attempts = 0;
+        
+        // The startup path still stops at the first problem
+        match config.validate() {
+            Err(GroundhogError::Config(ConfigError::InvalidValue { key, .. })) => assert_eq!(key, "performance.max_file_size"),
+            other => panic!("Expected ConfigError::InvalidValue, got {:?}", other),
+        }
+        
+        let error = config.validate_all().unwrap_err();
+        let GroundhogError::Config(ConfigError::Multiple { errors }) = &error else {
+            panic!("Expected ConfigError::Multiple, got {:?}", error);
+        };
+        let keys: Vec<&str> = errors
+            .iter()
+            .map(|e| match e {
+                ConfigError::InvalidValue { key, .. } => key.as_str(),
+                other => panic!("Unexpected error {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            ["performance.max_file_size", "performance.timeout", "performance.threads", "performance.retry.max_attempts"]
+        );
+        
+        let message = error.user_message();
+        assert!(message.starts_with("Found 4 configuration problems:"));
+        assert_eq!(message.lines().count(), 5);
+        assert!(Config::default().validate_all().is_ok());
+    }
+    
+    fn ai_config(provider: AiProvider) -> AiConfig {
+        AiConfig::new(provider, "model")
+    }
+    
+    #[test]
+    fn test_ai_config_empty_model() {
+        let ai = AiConfig {
+            model: "  ".to_string(),
+            ..ai_config(AiProvider::Local)
+        };
+        
+        match ai.validation_errors_with(|_| None).first() {
+            Some(ConfigError::InvalidValue { key, .. }) => assert_eq!(key, "ai.model"),
+            other => panic!("Expected ConfigError::InvalidValue, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_missing_model_uses_provider_default() {
+        let config = Config::from_str("[ai]\nprovider = \"Anthropic\"\n", ConfigFormat::Toml).unwrap();
+        assert_eq!(config.ai.unwrap().model, "claude-3-5-haiku-latest");
+        
+        let config = Config::from_str("[ai]\nprovider = \"OpenAI\"\nmodel = \" \"\n", ConfigFormat::Toml).unwrap();
+        assert_eq!(config.ai.unwrap().model, AiProvider::OpenAI.default_model());
+        
+        let config = Config::from_str("[ai]\nprovider = \"Local\"\nmodel = \"mistral\"\n", ConfigFormat::Toml).unwrap();
+        assert_eq!(config.ai.unwrap().model, "mistral");
+        
+        for provider in [AiProvider::OpenAI, AiProvider::Anthropic, AiProvider::Local] {
+            let ai = AiConfig { model: provider.default_model().to_string(), ..ai_config(provider) };
+            assert_eq!(ai.model_warning(), None, "default model of {:?}", ai.provider);
+        }
+    }
+    
+    #[test]
+    fn test_unknown_model_only_warns() {
+        let model = |provider, model: &str| AiConfig { model: model.to_string(), ..ai_config(provider) };
+        
+        assert!(model(AiProvider::OpenAI, "gpt-4o-2024-08-06").is_known_model());
+        assert!(model(AiProvider::Anthropic, "claude-3-5-sonnet-latest").is_known_model());
+        assert!(model(AiProvider::Local, "anything").is_known_model());
+        assert!(!model(AiProvider::OpenAI, "gpt-4oo").is_known_model());
+        assert!(!model(AiProvider::Anthropic, "gpt-4o").is_known_model());
+        
+        let config = Config {
+            ai: Some(AiConfig { api_key: Some("key".to_string()), ..model(AiProvider::OpenAI, "gtp-4o") }),
+            ..Config::default()
+        };
+        let warnings = config.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Unknown OpenAI model 'gtp-4o' in ai.model; known models: gpt-4o, "));
+        assert!(config.validate_all().is_ok());
+    }
+    
+    #[test]
+    fn test_ai_config_bad_endpoint() {
+        for endpoint in ["not a url", "ftp://example.com", "http://"] {
+            let ai = AiConfig {
+                endpoint: Some(endpoint.to_string()),
+                ..ai_config(AiProvider::Local)
+            };
+            
+            match ai.validation_errors_with(|_| None).first() {
+                Some(ConfigError::InvalidValue { key, .. }) => assert_eq!(key, "ai.endpoint"),
+                other => panic!("Expected ConfigError::InvalidValue for {}, got {:?}", endpoint, other),
+            }
+        }
+        
+        let ai = AiConfig {
+            endpoint: Some("http://localhost:11434/v1".to_string()),
+            ..ai_config(AiProvider::Local)
+        };
+        assert!(ai.validation_errors_with(|_| None).is_empty());
+    }
+    
+    #[test]
+    fn test_ai_config_bad_proxy_url() {
+        let ai = AiConfig {
+            proxy: Some(ProxyConfig { url: "proxy:8080".to_string(), no_proxy: None }),
+            ..ai_config(AiProvider::Local)
+        };
+        
+        match ai.validation_errors_with(|_| None).first() {
+            Some(ConfigError::InvalidValue { key, .. }) => assert_eq!(key, "ai.proxy.url"),
+            other => panic!("Expected ConfigError::InvalidValue, got {:?}", other),
+        }
+        
+        let toml = "[ai]\nprovider = \"Local\"\nmodel = \"llama\"\n\n[ai.proxy]\nurl = \"http://proxy.example.com:8080\"\n";
+        let config = Config::from_str(toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(config.ai.unwrap().proxy.unwrap().url, "http://proxy.example.com:8080");
+    }
+    
+    #[test]
+    fn test_rate_limit_defaults_and_validation() {
+        let toml = "[ai]\nprovider = \"Local\"\nmodel = \"llama\"\n\n[ai.rate_limit]\nrequests_per_minute = 0\n";
+        let ai = Config::from_str(toml, ConfigFormat::Toml).unwrap().ai.unwrap();
+        
+        assert_eq!(ai.rate_limit.as_ref().unwrap().burst, 1);
+        match ai.validation_errors_with(|_| None).as_slice() {
+            [ConfigError::InvalidValue { key, .. }] => assert_eq!(key, "ai.rate_limit.requests_per_minute"),
+            other => panic!("Expected one ConfigError::InvalidValue, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_ai_params_range_validation() {
+        let invalid = [
+            ("ai.params.temperature", AiParams { temperature: Some(2.5), ..AiParams::default() }),
+            ("ai.params.temperature", AiParams { temperature: Some(-0.1), ..AiParams::default() }),
+            ("ai.params.top_p", AiParams { top_p: Some(1.5), ..AiParams::default() }),
+            ("ai.params.max_tokens", AiParams { max_tokens: Some(0), ..AiParams::default() }),
+        ];
+        for (expected_key, params) in invalid {
+            let ai = AiConfig { params, ..ai_config(AiProvider::Local) };
+            match ai.validation_errors_with(|_| None).first() {
+                Some(ConfigError::InvalidValue { key, .. }) => assert_eq!(key, expected_key),
+                other => panic!("Expected ConfigError::InvalidValue for {}, got {:?}", expected_key, other),
+            }
+        }
+        
+        let valid = AiParams {
+            temperature: Some(2.0),
+            max_tokens: Some(1),
+            top_p: Some(0.0),
+        };
+        assert!(valid.validate().is_ok());
+    }
+    
+    #[test]
+    fn test_ai_config_missing_openai_key() {
+        let ai = ai_config(AiProvider::OpenAI);
+        
+        match ai.validation_errors_with(|_| None).first() {
+            Some(ConfigError::MissingKey { key }) => assert!(key.contains("OPENAI_API_KEY")),
+            other => panic!("Expected ConfigError::MissingKey, got {:?}", other),
+        }
+        
+        let from_env = |name: &str| (name == "OPENAI_API_KEY").then(|| "sk-test".to_string());
+        assert!(ai.validation_errors_with(from_env).is_empty());
+        assert!(ai_config(AiProvider::Local).validation_errors_with(|_| None).is_empty());
     }
     
     #[test]
@@ -375,6 +1567,297 @@ mod tests {
         assert_eq!(loaded_config.performance.max_file_size, default_config.performance.max_file_size);
     }
     
+    #[test]
+    fn test_resolve_sources_explicit_path_wins() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("explicit.toml");
+        std::fs::write(&config_path, "").unwrap();
+        
+        let sources = Config::resolve_sources(Some(config_path.clone()));
+        
+        assert_eq!(sources[0].path, config_path);
+        assert!(sources[0].exists);
+        assert!(sources[0].active);
+        assert_eq!(sources.iter().filter(|s| s.active).count(), 1);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_search_paths_on_unix() {
+        let paths = Config::get_config_search_paths(None);
+        
+        let user = dirs::config_dir().unwrap().join("groundhog").join("config.toml");
+        let legacy = dirs::home_dir().unwrap().join(".groundhog").join("config.toml");
+        let system = PathBuf::from("/etc/groundhog/config.toml");
+        let position = |path: &PathBuf| paths.iter().position(|p| p == path).unwrap();
+        assert!(position(&user) < position(&legacy));
+        assert_eq!(paths.last(), Some(&system));
+    }
+    
+    #[cfg(windows)]
+    #[test]
+    fn test_search_paths_on_windows() {
+        let paths = Config::get_config_search_paths(None);
+        
+        let user = dirs::config_dir().unwrap().join("groundhog").join("config.toml");
+        assert!(paths.contains(&user));
+        assert!(paths.iter().all(|path| !path.starts_with("/etc")));
+        if let Some(program_data) = std::env::var_os("PROGRAMDATA") {
+            assert_eq!(paths.last(), Some(&PathBuf::from(program_data).join("groundhog").join("config.toml")));
+        }
+    }
+    
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(language_from_locale("fr_FR.UTF-8"), Some("fr_FR".to_string()));
+        assert_eq!(language_from_locale("de"), Some("de".to_string()));
+        assert_eq!(language_from_locale("C.UTF-8"), None);
+        assert_eq!(language_from_locale("POSIX"), None);
+        assert_eq!(language_from_locale(""), None);
+    }
+    
+    #[test]
+    fn test_configured_language_takes_precedence() {
+        let output = OutputConfig {
+            language: Some("es".to_string()),
+            ..OutputConfig::default()
+        };
+        assert_eq!(output.resolve_language(), Some("es".to_string()));
+    }
+    
+    #[test]
+    fn test_yaml_config_matches_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml_path = temp_dir.path().join("config.toml");
+        let yaml_path = temp_dir.path().join("config.yaml");
+        
+        std::fs::write(&toml_path, r#"
+[logging]
+level = "Debug"
+directives = ["tui=warn"]
+
+[output]
+format = "markdown"
+language = "fr"
+
+[performance]
+threads = 8
+
+[tui.keys]
+quit = "q"
+"#).unwrap();
+        std::fs::write(&yaml_path, r#"
+logging:
+  level: Debug
+  directives: ["tui=warn"]
+output:
+  format: markdown
+  language: fr
+performance:
+  threads: 8
+tui:
+  keys:
+    quit: q
+"#).unwrap();
+        
+        let from_toml = Config::load_from_file(&toml_path).unwrap();
+        let from_yaml = Config::load_from_file(&yaml_path).unwrap();
+        
+        assert_eq!(from_yaml, from_toml);
+        assert_eq!(from_yaml.performance.threads, 8);
+    }
+    
+    #[test]
+    fn test_create_default_yaml_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yml");
+        
+        Config::create_default_file(&config_path).unwrap();
+        
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains("logging:"));
+        assert_eq!(Config::load_from_file(&config_path).unwrap(), Config::default());
+    }
+    
+    #[test]
+    fn test_invalid_yaml_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("invalid.yaml");
+        std::fs::write(&config_path, "performance: [unclosed").unwrap();
+        
+        let result = Config::load_from_file(&config_path);
+        assert!(matches!(result, Err(GroundhogError::Parse(ParseError::Yaml { .. }))));
+    }
+    
+    #[test]
+    fn test_from_str_parses_toml() {
+        let config = Config::from_str("[performance]\nthreads = 2\n", ConfigFormat::Toml).unwrap();
+        
+        let mut expected = Config::default();
+        expected.performance.threads = 2;
+        assert_eq!(config, expected);
+    }
+    
+    #[test]
+    fn test_from_str_rejects_invalid_toml() {
+        match Config::from_str("invalid toml [[[", ConfigFormat::Toml) {
+            Err(GroundhogError::Config(ConfigError::InvalidFormat { path, .. })) => {
+                assert_eq!(path, PathBuf::from("<string>"));
+            }
+            other => panic!("Expected ConfigError::InvalidFormat, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_from_reader_parses_json() {
+        let input = r#"{ "performance": { "timeout": 5 } }"#.as_bytes();
+        let config = Config::from_reader(input, ConfigFormat::Json).unwrap();
+        assert_eq!(config.performance.timeout, 5);
+    }
+    
+    const PROFILES_TOML: &str = r#"
+[ai]
+provider = "OpenAI"
+model = "gpt-4o"
+api_key = "personal-key"
+
+[output]
+format = "markdown"
+
+[profiles.work.ai]
+api_key = "work-key"
+model = "gpt-4o-mini"
+
+[profiles.work.output]
+language = "de"
+"#;
+    
+    #[test]
+    fn test_profile_overrides_base_sections() {
+        let config = Config::from_str(PROFILES_TOML, ConfigFormat::Toml).unwrap().with_profile("work").unwrap();
+        
+        let ai = config.ai.unwrap();
+        assert_eq!(ai.provider, AiProvider::OpenAI);
+        assert_eq!(ai.model, "gpt-4o-mini");
+        assert_eq!(ai.api_key.as_deref(), Some("work-key"));
+        assert_eq!(config.output.format, "markdown");
+        assert_eq!(config.output.language.as_deref(), Some("de"));
+    }
+    
+    #[test]
+    fn test_profile_switching_provider_gets_its_default_model() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("groundhog.toml");
+        std::fs::write(&config_path, "[ai]\nprovider = \"OpenAI\"\n\n[profiles.claude.ai]\nprovider = \"Anthropic\"\n").unwrap();
+        
+        let config = Config::load_hierarchical(Some(config_path.clone()), Some("claude".to_string())).unwrap();
+        assert_eq!(config.ai.unwrap().model, AiProvider::Anthropic.default_model());
+        
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.ai.unwrap().model, AiProvider::OpenAI.default_model());
+    }
+    
+    #[test]
+    fn test_unknown_profile_is_rejected() {
+        let config = Config::from_str(PROFILES_TOML, ConfigFormat::Toml).unwrap();
+        
+        match config.with_profile("home") {
+            Err(GroundhogError::Config(ConfigError::InvalidValue { key, expected, .. })) => {
+                assert_eq!(key, "profile");
+                assert_eq!(expected, "one of: work");
+            }
+            other => panic!("Expected ConfigError::InvalidValue, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_base_config_used_without_profile() {
+        let config = Config::from_str(PROFILES_TOML, ConfigFormat::Toml).unwrap();
+        
+        let ai = config.ai.as_ref().unwrap();
+        assert_eq!(ai.model, "gpt-4o");
+        assert_eq!(ai.api_key.as_deref(), Some("personal-key"));
+        assert!(config.output.language.is_none());
+        assert!(config.profiles.contains_key("work"));
+    }
+    
+    #[test]
+    fn test_redacted_config_masks_every_api_key() {
+        let config = Config::from_str(PROFILES_TOML, ConfigFormat::Toml).unwrap();
+        
+        let shown = config.to_string();
+        
+        assert!(shown.contains("api_key = \"***\""), "{}", shown);
+        assert!(shown.contains("model = \"gpt-4o\""), "{}", shown);
+        assert!(!shown.contains("personal-key"), "{}", shown);
+        assert!(!shown.contains("work-key"), "{}", shown);
+        assert_eq!(config.ai.unwrap().api_key.as_deref(), Some("personal-key"));
+    }
+    
+    #[test]
+    fn test_json_config_overrides_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        std::fs::write(&config_path, r#"{
+  "logging": { "level": "Warn", "rotation": "daily" },
+  "performance": { "threads": 2 }
+}"#).unwrap();
+        
+        let config = Config::load_from_file(&config_path).unwrap();
+        
+        let mut expected = Config::default();
+        expected.logging.level = LogLevel::Warn;
+        expected.logging.rotation = LogRotation::Daily;
+        expected.performance.threads = 2;
+        assert_eq!(config, expected);
+    }
+    
+    #[test]
+    fn test_malformed_json_reports_position() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("invalid.json");
+        std::fs::write(&config_path, "{\n  \"performance\": {\n    \"threads\": ,\n  }\n}").unwrap();
+        
+        match Config::load_from_file(&config_path) {
+            Err(GroundhogError::Parse(ParseError::Json { line, column, .. })) => {
+                assert_eq!(line, Some(3));
+                assert_eq!(column, Some(16));
+            }
+            other => panic!("Expected ParseError::Json, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_diff_reports_only_changed_keys() {
+        let mut config = Config::default();
+        config.performance.threads = 16;
+        config.logging.level = LogLevel::Debug;
+        
+        let diff = config.diff(&Config::default());
+        
+        assert_eq!(diff, vec![
+            ("logging.level".to_string(), "Debug".to_string(), "Info".to_string()),
+            ("performance.threads".to_string(), "16".to_string(), default_threads().to_string()),
+        ]);
+        assert!(Config::default().diff(&Config::default()).is_empty());
+    }
+    
+    #[test]
+    fn test_diff_includes_optional_sections() {
+        let config = Config {
+            ai: Some(AiConfig::new(AiProvider::Local, "llama")),
+            ..Config::default()
+        };
+        
+        let diff = config.diff(&Config::default());
+        
+        assert_eq!(diff, vec![
+            ("ai.context_limit".to_string(), DEFAULT_CONTEXT_LIMIT.to_string(), "(unset)".to_string()),
+            ("ai.model".to_string(), "llama".to_string(), "(unset)".to_string()),
+            ("ai.provider".to_string(), "Local".to_string(), "(unset)".to_string()),
+        ]);
+    }
+    
     #[test]
     fn test_invalid_toml_format() {
         let temp_dir = TempDir::new().unwrap();
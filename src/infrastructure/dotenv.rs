@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use tracing::debug;
+
+use crate::infrastructure::error::{ConfigError, FileSystemError, GroundhogError};
+
+/// Load `KEY=value` lines from `path` into the process environment
+///
+/// Variables that are already set keep their value. This must run before
+/// the configuration is loaded so API keys and `GROUNDHOG_*` settings from
+/// the file are seen by config resolution.
+pub fn load(path: &Path) -> Result<(), GroundhogError> {
+    match dotenvy::from_path(path) {
+        Ok(()) => {
+            debug!(path = %path.display(), "Loaded environment file");
+            Ok(())
+        }
+        Err(dotenvy::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(FileSystemError::NotFound { path: path.to_path_buf() }.into())
+        }
+        Err(dotenvy::Error::Io(e)) => Err(FileSystemError::Io(e).into()),
+        Err(e) => Err(ConfigError::InvalidFormat {
+            path: path.to_path_buf(),
+            line: None,
+            source: Box::new(e),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    use crate::infrastructure::config::{AiConfig, AiProvider};
+
+    #[test]
+    fn test_load_makes_api_key_visible_to_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".env");
+        std::fs::write(&path, "# local secrets\nANTHROPIC_API_KEY=sk-from-dotenv\n").unwrap();
+        // SAFETY: no other test reads or writes this variable's value
+        unsafe { std::env::remove_var("ANTHROPIC_API_KEY") };
+
+        load(&path).unwrap();
+
+        let ai = AiConfig::new(AiProvider::Anthropic, "claude");
+        assert_eq!(ai.resolve_api_key().as_deref(), Some("sk-from-dotenv"));
+    }
+
+    #[test]
+    fn test_load_reports_missing_and_malformed_files() {
+        let dir = TempDir::new().unwrap();
+        assert!(matches!(
+            load(&dir.path().join("missing.env")),
+            Err(GroundhogError::FileSystem(FileSystemError::NotFound { .. }))
+        ));
+
+        let path = dir.path().join(".env");
+        std::fs::write(&path, "NOT A VALID LINE\n").unwrap();
+        assert!(matches!(load(&path), Err(GroundhogError::Config(ConfigError::InvalidFormat { .. }))));
+    }
+}
@@ -24,6 +24,14 @@ pub enum GroundhogError {
     
     #[error("TUI error: {0}")]
     TUIError(String),
+
+    /// `source` from a command that still produced data for its machine-readable report
+    #[error("{source}")]
+    WithData {
+        data: serde_json::Value,
+        #[source]
+        source: Box<GroundhogError>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -46,6 +54,16 @@ pub enum CommandError {
     
     #[error("Permission denied for command '{command}'")]
     PermissionDenied { command: String },
+    
+    #[error("Command '{command}' failed for {failed} of {total} inputs")]
+    PartialFailure {
+        command: String,
+        failed: usize,
+        total: usize,
+    },
+
+    #[error("{}", .0.to_string().trim_start_matches("error: ").trim_end())]
+    Usage(#[from] clap::Error),
 }
 
 #[derive(Debug, Error)]
@@ -70,6 +88,21 @@ pub enum ConfigError {
         value: String,
         expected: String,
     },
+    
+    #[error("Configuration has {} problems", errors.len())]
+    Multiple { errors: Vec<ConfigError> },
+}
+
+impl ConfigError {
+    /// One-line description, used when listing several problems
+    fn summary(&self) -> String {
+        match self {
+            ConfigError::InvalidValue { key, value, expected } => {
+                format!("Invalid value '{}' for '{}', expected {}", value, key, expected)
+            }
+            other => other.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -86,6 +119,12 @@ pub enum FileSystemError {
     #[error("File '{path}' is not writable")]
     NotWritable { path: PathBuf },
     
+    #[error("File '{path}' already exists")]
+    AlreadyExists { path: PathBuf },
+    
+    #[error("File '{path}' is too large ({size} bytes, limit {limit} bytes)")]
+    TooLarge { path: PathBuf, size: u64, limit: u64 },
+    
     #[error("Directory '{path}' is not accessible")]
     DirectoryNotAccessible { path: PathBuf },
     
@@ -99,6 +138,9 @@ pub enum FileSystemError {
     
     #[error("I/O error")]
     Io(#[from] std::io::Error),
+    
+    #[error("Output closed before it was fully written")]
+    OutputClosed,
 }
 
 #[derive(Debug, Error)]
@@ -116,7 +158,10 @@ pub enum NetworkError {
     #[error("HTTP error {status}: {message}")]
     Http { 
         status: u16, 
-        message: String 
+        message: String,
+        /// Client error the status was reported by, if any
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
     
     #[error("Invalid URL: '{url}'")]
@@ -126,6 +171,29 @@ pub enum NetworkError {
     AuthenticationFailed,
 }
 
+impl From<reqwest::Error> for NetworkError {
+    /// Map a client error to a network error
+    ///
+    /// reqwest does not report the configured timeout, so `Timeout` carries
+    /// `timeout_ms: 0` and callers that know the timeout should fill it in.
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return NetworkError::Timeout { timeout_ms: 0 };
+        }
+        if let Some(status) = error.status() {
+            return NetworkError::Http {
+                status: status.as_u16(),
+                message: status.canonical_reason().unwrap_or("request failed").to_string(),
+                source: Some(Box::new(error)),
+            };
+        }
+        NetworkError::ConnectionFailed {
+            url: error.url().map(|url| url.to_string()).unwrap_or_default(),
+            source: Box::new(error),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("JSON parsing failed")]
@@ -137,6 +205,21 @@ pub enum ParseError {
         source: serde_json::Error,
     },
     
+    #[error("Invalid JSON in the response from {url}")]
+    Response {
+        url: String,
+        line: usize,
+        column: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    
+    #[error("Response does not match the JSON schema at {pointer}: {message}")]
+    SchemaMismatch {
+        pointer: String,
+        message: String,
+    },
+    
     #[error("YAML parsing failed")]  
     Yaml {
         input: String,
@@ -151,12 +234,31 @@ pub enum ParseError {
         source: toml::de::Error,
     },
     
+    #[error("Markdown processing failed at line {line}: {message}")]
+    Markdown {
+        line: usize,
+        message: String,
+    },
+    
     #[error("Invalid syntax at line {line}, column {column}")]
     Syntax { 
         line: usize, 
         column: usize,
         message: String,
     },
+    
+    #[error("Invalid diff at line {line}: {message}")]
+    Diff {
+        line: usize,
+        message: String,
+    },
+    
+    #[error("Patch does not apply to '{}': hunk {hunk}: {message}", path.display())]
+    PatchConflict {
+        path: PathBuf,
+        hunk: usize,
+        message: String,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -176,21 +278,54 @@ pub enum InternalError {
     
     #[error("Configuration validation failed: {message}")]
     ConfigValidation { message: String },
+    
+    #[error("Operation cancelled: {operation}")]
+    Cancelled { operation: String },
+    
+    #[error("Invalid log directive '{directive}': {message}")]
+    InvalidLogDirective { directive: String, message: String },
 }
 
 impl GroundhogError {
     /// Returns a user-friendly error message with suggestions
     pub fn user_message(&self) -> String {
         match self {
+            GroundhogError::WithData { source, .. } => source.user_message(),
             GroundhogError::Command(CommandError::NotFound { command }) => {
                 format!("Command '{}' not found. Run 'groundhog --help' to see available commands.", command)
             }
+            GroundhogError::Command(error @ (CommandError::PartialFailure { .. } | CommandError::Usage(_))) => error.to_string(),
+            GroundhogError::Internal(error @ InternalError::Cancelled { .. }) => error.to_string(),
             GroundhogError::Config(ConfigError::NotFound { path }) => {
                 format!(
                     "Configuration file not found at '{}'.\nTry running 'groundhog config init' to create a default configuration.",
                     path.display()
                 )
             }
+            GroundhogError::Config(ConfigError::MissingKey { key }) => {
+                format!(
+                    "Missing required configuration key '{}'.\nAdd it to your configuration file (run 'groundhog config paths' to find it); examples/groundhog.toml documents every setting.",
+                    key
+                )
+            }
+            GroundhogError::Config(ConfigError::InvalidFormat { path, source, .. }) => {
+                format!(
+                    "The configuration file at '{}' is malformed: {}\nFix the syntax or run with --no-config to ignore it.",
+                    path.display(),
+                    source.to_string().trim_end()
+                )
+            }
+            GroundhogError::Config(ConfigError::InvalidValue { key, value, expected }) => {
+                format!("Invalid value '{}' for configuration key '{}'.\nExpected {}.", value, key, expected)
+            }
+            GroundhogError::Config(ConfigError::Multiple { errors }) => {
+                let mut message = format!("Found {} configuration problems:", errors.len());
+                for error in errors {
+                    message.push_str("\n  - ");
+                    message.push_str(&error.summary());
+                }
+                message
+            }
             GroundhogError::FileSystem(FileSystemError::NotFound { path }) => {
                 format!(
                     "File not found: '{}'\nPlease check the path and try again.",
@@ -203,20 +338,182 @@ impl GroundhogError {
                     path.display()
                 )
             }
+            GroundhogError::FileSystem(FileSystemError::AlreadyExists { path }) => {
+                format!(
+                    "File '{}' already exists.\nUse --force to overwrite it.",
+                    path.display()
+                )
+            }
+            GroundhogError::FileSystem(FileSystemError::TooLarge { path, size, limit }) => {
+                format!(
+                    "File '{}' is too large ({} bytes, limit {} bytes).\nRaise performance.max_file_size to process it.",
+                    path.display(),
+                    size,
+                    limit
+                )
+            }
+            GroundhogError::FileSystem(FileSystemError::InvalidFormat { path, expected, source }) => {
+                format!(
+                    "File '{}' is not {} ({}).\nIt may be a binary file; only text files can be explained.",
+                    path.display(),
+                    expected,
+                    source
+                )
+            }
+            GroundhogError::Network(NetworkError::AuthenticationFailed) => {
+                "Authentication with the AI provider failed.\nCheck your API key: set ai.api_key or the provider's environment variable (OPENAI_API_KEY or ANTHROPIC_API_KEY), then run 'groundhog ping'.".to_string()
+            }
+            GroundhogError::Network(error) => format!("Network error: {}", error),
+            GroundhogError::Parse(ParseError::Json { input, line: Some(line), column: Some(column), source }) => {
+                format!("The file at '{}' is malformed near line {}, column {}: {}", input, line, column, source)
+            }
+            GroundhogError::Parse(ParseError::Json { input, source, .. }) => {
+                format!("The file at '{}' is not valid JSON: {}", input, source)
+            }
+            GroundhogError::Parse(ParseError::Response { url, line, column, source }) => {
+                format!(
+                    "The AI provider's response from '{}' is malformed near line {}, column {}: {}\nCheck that ai.endpoint points at an API compatible with ai.provider.",
+                    url, line, column, source
+                )
+            }
+            GroundhogError::Parse(ParseError::SchemaMismatch { pointer, message }) => {
+                format!(
+                    "The AI response does not match the JSON schema at '{}': {}\nRun the command again, or relax the schema given to --json-schema.",
+                    pointer, message
+                )
+            }
+            GroundhogError::Parse(ParseError::Yaml { input, source }) => {
+                format!("The file at '{}' is malformed YAML: {}", input, source)
+            }
+            GroundhogError::Parse(ParseError::Toml { input, source }) => {
+                format!("The file at '{}' is malformed TOML: {}", input, source.to_string().trim_end())
+            }
+            GroundhogError::Parse(ParseError::Markdown { line, message }) => {
+                format!("The generated markdown is malformed near line {}: {}\nTry --output text instead.", line, message)
+            }
+            GroundhogError::Parse(ParseError::Syntax { line, column, message }) => {
+                format!("Invalid syntax near line {}, column {}: {}", line, column, message)
+            }
+            GroundhogError::Parse(ParseError::Diff { line, message }) => {
+                format!("The generated diff is malformed near line {}: {}\nRun the command again, or ask for a smaller change.", line, message)
+            }
+            GroundhogError::Parse(ParseError::PatchConflict { path, hunk, message }) => {
+                format!(
+                    "The generated patch does not apply to '{}': hunk {} {}.\nNo files were changed; run the command again.",
+                    path.display(),
+                    hunk,
+                    message
+                )
+            }
             _ => self.to_string(),
         }
     }
     
+    /// Whether the reader of the output went away, e.g. `head` exiting after the lines it wanted
+    pub fn is_output_closed(&self) -> bool {
+        match self {
+            GroundhogError::FileSystem(FileSystemError::OutputClosed) => true,
+            GroundhogError::WithData { source, .. } => source.is_output_closed(),
+            _ => false,
+        }
+    }
+    
+    /// Short category name for this error, used in machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GroundhogError::Command(_) => "command",
+            GroundhogError::Config(_) => "config",
+            GroundhogError::FileSystem(_) => "filesystem",
+            GroundhogError::Network(_) => "network",
+            GroundhogError::Parse(_) => "parse",
+            GroundhogError::Internal(_) => "internal",
+            GroundhogError::TUIError(_) => "tui",
+            GroundhogError::WithData { source, .. } => source.kind(),
+        }
+    }
+    
+    /// Stable identifier of the specific error within its [`kind`](GroundhogError::kind), e.g. "not_found"
+    pub fn code(&self) -> &'static str {
+        match self {
+            GroundhogError::Command(error) => match error {
+                CommandError::NotFound { .. } => "not_found",
+                CommandError::InvalidArguments { .. } => "invalid_arguments",
+                CommandError::ExecutionFailed { .. } => "execution_failed",
+                CommandError::PermissionDenied { .. } => "permission_denied",
+                CommandError::PartialFailure { .. } => "partial_failure",
+                CommandError::Usage(_) => "usage",
+            },
+            GroundhogError::Config(error) => match error {
+                ConfigError::NotFound { .. } => "not_found",
+                ConfigError::InvalidFormat { .. } => "invalid_format",
+                ConfigError::MissingKey { .. } => "missing_key",
+                ConfigError::InvalidValue { .. } => "invalid_value",
+                ConfigError::Multiple { .. } => "multiple",
+            },
+            GroundhogError::FileSystem(error) => match error {
+                FileSystemError::NotFound { .. } => "not_found",
+                FileSystemError::PermissionDenied { .. } => "permission_denied",
+                FileSystemError::NotReadable { .. } => "not_readable",
+                FileSystemError::NotWritable { .. } => "not_writable",
+                FileSystemError::AlreadyExists { .. } => "already_exists",
+                FileSystemError::TooLarge { .. } => "too_large",
+                FileSystemError::DirectoryNotAccessible { .. } => "directory_not_accessible",
+                FileSystemError::InvalidFormat { .. } => "invalid_format",
+                FileSystemError::Io(_) => "io",
+                FileSystemError::OutputClosed => "output_closed",
+            },
+            GroundhogError::Network(error) => match error {
+                NetworkError::ConnectionFailed { .. } => "connection_failed",
+                NetworkError::Timeout { .. } => "timeout",
+                NetworkError::Http { .. } => "http",
+                NetworkError::InvalidUrl { .. } => "invalid_url",
+                NetworkError::AuthenticationFailed => "authentication_failed",
+            },
+            GroundhogError::Parse(error) => match error {
+                ParseError::Json { .. } => "json",
+                ParseError::Response { .. } => "response",
+                ParseError::SchemaMismatch { .. } => "schema_mismatch",
+                ParseError::Yaml { .. } => "yaml",
+                ParseError::Toml { .. } => "toml",
+                ParseError::Markdown { .. } => "markdown",
+                ParseError::Syntax { .. } => "syntax",
+                ParseError::Diff { .. } => "diff",
+                ParseError::PatchConflict { .. } => "patch_conflict",
+            },
+            GroundhogError::Internal(error) => match error {
+                InternalError::UnexpectedState { .. } => "unexpected_state",
+                InternalError::ResourceExhausted { .. } => "resource_exhausted",
+                InternalError::InitializationFailed { .. } => "initialization_failed",
+                InternalError::ConfigValidation { .. } => "config_validation",
+                InternalError::Cancelled { .. } => "cancelled",
+                InternalError::InvalidLogDirective { .. } => "invalid_log_directive",
+            },
+            GroundhogError::TUIError(_) => "tui",
+            GroundhogError::WithData { source, .. } => source.code(),
+        }
+    }
+    
     /// Returns the exit code that should be used for this error
     pub fn exit_code(&self) -> i32 {
         match self {
             GroundhogError::Command(CommandError::NotFound { .. }) => 64, // EX_USAGE
             GroundhogError::Command(CommandError::InvalidArguments { .. }) => 64, // EX_USAGE
+            GroundhogError::Command(CommandError::Usage(error)) => error.exit_code(),
             GroundhogError::Config(ConfigError::InvalidFormat { .. }) => 65, // EX_DATAERR
             GroundhogError::FileSystem(FileSystemError::NotFound { .. }) => 66, // EX_NOINPUT
+            GroundhogError::FileSystem(FileSystemError::TooLarge { .. }) => 65, // EX_DATAERR
+            GroundhogError::FileSystem(FileSystemError::InvalidFormat { .. }) => 65, // EX_DATAERR
             GroundhogError::FileSystem(FileSystemError::PermissionDenied { .. }) => 77, // EX_NOPERM
-            GroundhogError::FileSystem(FileSystemError::Io(_)) => 74, // EX_IOERR
+            GroundhogError::FileSystem(FileSystemError::NotWritable { .. }) => 73, // EX_CANTCREAT
+            GroundhogError::FileSystem(FileSystemError::AlreadyExists { .. }) => 73, // EX_CANTCREAT
+            GroundhogError::FileSystem(FileSystemError::Io(_) | FileSystemError::OutputClosed) => 74, // EX_IOERR
+            GroundhogError::Parse(
+                ParseError::Diff { .. } | ParseError::PatchConflict { .. } | ParseError::SchemaMismatch { .. },
+            ) => 65, // EX_DATAERR
+            GroundhogError::Parse(ParseError::Response { .. }) => 76, // EX_PROTOCOL
             GroundhogError::Network(_) => 69, // EX_UNAVAILABLE
+            GroundhogError::Internal(InternalError::Cancelled { .. }) => 130, // 128 + SIGINT
+            GroundhogError::WithData { source, .. } => source.exit_code(),
             _ => 1, // General error
         }
     }
@@ -225,6 +522,10 @@ impl GroundhogError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
     use std::path::PathBuf;
 
     #[test]
@@ -246,6 +547,55 @@ mod tests {
         assert!(error.to_string().contains("Invalid arguments"));
     }
 
+    #[tokio::test]
+    async fn test_reqwest_timeout_maps_to_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+        let error = client.get(server.uri()).send().await.unwrap_err();
+
+        assert!(matches!(NetworkError::from(error), NetworkError::Timeout { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_reqwest_connect_error_maps_to_connection_failed() {
+        // Nothing listens on the discard port
+        let error = reqwest::get("http://127.0.0.1:9/").await.unwrap_err();
+
+        match NetworkError::from(error) {
+            NetworkError::ConnectionFailed { url, source } => {
+                assert_eq!(url, "http://127.0.0.1:9/");
+                assert!(source.downcast_ref::<reqwest::Error>().is_some());
+            }
+            other => panic!("Expected NetworkError::ConnectionFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reqwest_status_error_maps_to_http() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let response = reqwest::get(server.uri()).await.unwrap();
+        let error = response.error_for_status().unwrap_err();
+
+        match NetworkError::from(error) {
+            NetworkError::Http { status, message, source } => {
+                assert_eq!(status, 503);
+                assert_eq!(message, "Service Unavailable");
+                assert!(source.is_some_and(|source| source.downcast_ref::<reqwest::Error>().is_some()));
+            }
+            other => panic!("Expected NetworkError::Http, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_config_error_not_found() {
         let error = ConfigError::NotFound {
@@ -322,6 +672,16 @@ mod tests {
         assert!(error.to_string().contains("TOML parsing failed"));
     }
 
+    #[test]
+    fn test_parse_error_markdown() {
+        let error = ParseError::Markdown {
+            line: 3,
+            message: "unterminated code block".to_string(),
+        };
+        assert!(error.to_string().contains("Markdown processing failed at line 3"));
+        assert!(error.to_string().contains("unterminated code block"));
+    }
+
     #[test]
     fn test_internal_error_unexpected_state() {
         let error = InternalError::UnexpectedState {
@@ -341,6 +701,75 @@ mod tests {
         assert!(user_msg.contains("groundhog --help"));
     }
 
+    #[test]
+    fn test_user_message_authentication_hint() {
+        let message = GroundhogError::from(NetworkError::AuthenticationFailed).user_message();
+        assert!(message.contains("Check your API key"));
+        assert!(message.contains("OPENAI_API_KEY"));
+    }
+
+    #[test]
+    fn test_user_message_missing_key_hint() {
+        let message = GroundhogError::from(ConfigError::MissingKey { key: "ai".to_string() }).user_message();
+        assert!(message.contains("'ai'"));
+        assert!(message.contains("groundhog config paths"));
+    }
+
+    #[test]
+    fn test_user_message_parse_errors_point_at_location() {
+        let source = serde_json::from_str::<serde_json::Value>("{\n  \"a\": }").unwrap_err();
+        let json = GroundhogError::from(ParseError::Json {
+            input: "config.json".to_string(),
+            line: Some(source.line()),
+            column: Some(source.column()),
+            source,
+        });
+        assert!(json.user_message().contains("The file at 'config.json' is malformed near line 2"));
+
+        let toml = GroundhogError::from(ParseError::Toml {
+            input: "config.toml".to_string(),
+            source: toml::from_str::<toml::Value>("a = \nb = 1").unwrap_err(),
+        });
+        let message = toml.user_message();
+        assert!(message.contains("The file at 'config.toml' is malformed"));
+        assert!(message.contains("line 1"));
+
+        let markdown = GroundhogError::from(ParseError::Markdown { line: 4, message: "unterminated code block".to_string() });
+        assert!(markdown.user_message().contains("near line 4"));
+    }
+
+    #[test]
+    fn test_user_message_names_the_provider_response() {
+        let source = serde_json::from_str::<serde_json::Value>("<html>").unwrap_err();
+        let error = GroundhogError::from(ParseError::Response {
+            url: "https://api.example.com/v1/chat/completions".to_string(),
+            line: source.line(),
+            column: source.column(),
+            source,
+        });
+
+        let message = error.user_message();
+        assert!(message.starts_with("The AI provider's response from 'https://api.example.com/v1/chat/completions' is malformed near line 1"));
+        assert!(!message.contains("The file at"));
+        assert!(message.contains("ai.endpoint"));
+        assert_eq!(error.code(), "response");
+        assert_eq!(error.exit_code(), 76);
+    }
+
+    #[test]
+    fn test_user_message_schema_mismatch() {
+        let error = GroundhogError::from(ParseError::SchemaMismatch {
+            pointer: "/summary".to_string(),
+            message: "expected string, found number".to_string(),
+        });
+
+        let message = error.user_message();
+        assert!(message.starts_with("The AI response does not match the JSON schema at '/summary': expected string"));
+        assert!(message.contains("--json-schema"));
+        assert_eq!(error.code(), "schema_mismatch");
+        assert_eq!(error.exit_code(), 65);
+    }
+
     #[test]
     fn test_groundhog_error_exit_codes() {
         let command_error = GroundhogError::Command(CommandError::NotFound {
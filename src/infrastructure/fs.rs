@@ -0,0 +1,154 @@
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+use crate::infrastructure::error::{FileSystemError, GroundhogError};
+
+/// Read a UTF-8 text file of at most `max_bytes`
+///
+/// Failures map to the matching [`FileSystemError`]: `NotFound`,
+/// `PermissionDenied`, `NotReadable` for directories, `TooLarge` and
+/// `InvalidFormat` for content that is not UTF-8. The size is checked before
+/// anything is read.
+pub fn read_text(path: &Path, max_bytes: u64) -> Result<String, GroundhogError> {
+    let metadata = std::fs::metadata(path).map_err(|e| io_error(path, e))?;
+    if metadata.is_dir() {
+        return Err(FileSystemError::NotReadable { path: path.to_path_buf() }.into());
+    }
+    if metadata.len() > max_bytes {
+        return Err(FileSystemError::TooLarge {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            limit: max_bytes,
+        }
+        .into());
+    }
+
+    let file = std::fs::File::open(path).map_err(|e| io_error(path, e))?;
+    read_text_from(file, path, max_bytes)
+}
+
+/// Read at most `max_bytes` of UTF-8 text from `reader`, reporting errors against `path`
+///
+/// For streams such as standard input, whose size is only known once read.
+pub fn read_text_from(reader: impl Read, path: &Path, max_bytes: u64) -> Result<String, GroundhogError> {
+    let mut content = Vec::new();
+    let size = reader
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut content)
+        .map_err(|e| io_error(path, e))? as u64;
+    if size > max_bytes {
+        return Err(FileSystemError::TooLarge {
+            path: path.to_path_buf(),
+            size,
+            limit: max_bytes,
+        }
+        .into());
+    }
+
+    String::from_utf8(content).map_err(|e| {
+        FileSystemError::InvalidFormat {
+            path: path.to_path_buf(),
+            expected: "UTF-8 text".to_string(),
+            source: Box::new(e.utf8_error()),
+        }
+        .into()
+    })
+}
+
+/// File system error for an I/O failure on `path`
+pub fn io_error(path: &Path, error: std::io::Error) -> FileSystemError {
+    match error.kind() {
+        ErrorKind::NotFound => FileSystemError::NotFound { path: path.to_path_buf() },
+        ErrorKind::PermissionDenied => FileSystemError::PermissionDenied { path: path.to_path_buf() },
+        ErrorKind::AlreadyExists => FileSystemError::AlreadyExists { path: path.to_path_buf() },
+        ErrorKind::IsADirectory => FileSystemError::NotReadable { path: path.to_path_buf() },
+        _ => FileSystemError::Io(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reads_text_within_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+
+        assert_eq!(read_text(&path, 64).unwrap(), "fn main() {}\n");
+        assert_eq!(read_text_from("fn main() {}".as_bytes(), &path, 12).unwrap(), "fn main() {}");
+    }
+
+    #[test]
+    fn test_missing_file_and_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing.rs");
+
+        match read_text(&missing, 64) {
+            Err(GroundhogError::FileSystem(FileSystemError::NotFound { path })) => assert_eq!(path, missing),
+            other => panic!("Expected FileSystemError::NotFound, got {:?}", other),
+        }
+        match read_text(temp_dir.path(), 64) {
+            Err(GroundhogError::FileSystem(FileSystemError::NotReadable { path })) => assert_eq!(path, temp_dir.path()),
+            other => panic!("Expected FileSystemError::NotReadable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_oversized_file_and_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("big.txt");
+        std::fs::write(&path, "x".repeat(100)).unwrap();
+
+        match read_text(&path, 10) {
+            Err(GroundhogError::FileSystem(FileSystemError::TooLarge { size, limit, .. })) => {
+                assert_eq!((size, limit), (100, 10));
+            }
+            other => panic!("Expected FileSystemError::TooLarge, got {:?}", other),
+        }
+        // Streams stop reading one byte past the limit
+        match read_text_from("x".repeat(100).as_bytes(), Path::new("-"), 10) {
+            Err(GroundhogError::FileSystem(FileSystemError::TooLarge { size, limit, .. })) => {
+                assert_eq!((size, limit), (11, 10));
+            }
+            other => panic!("Expected FileSystemError::TooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_non_utf8_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("image.png");
+        std::fs::write(&path, [0x89, b'P', b'N', b'G', 0xff, 0xfe]).unwrap();
+
+        match read_text(&path, 64) {
+            Err(GroundhogError::FileSystem(FileSystemError::InvalidFormat { path: reported, expected, .. })) => {
+                assert_eq!(reported, path);
+                assert_eq!(expected, "UTF-8 text");
+            }
+            other => panic!("Expected FileSystemError::InvalidFormat, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("secret.txt");
+        std::fs::write(&path, "secret").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Privileged users can read the file regardless of its mode
+        if std::fs::File::open(&path).is_ok() {
+            return;
+        }
+        match read_text(&path, 64) {
+            Err(GroundhogError::FileSystem(FileSystemError::PermissionDenied { path: reported })) => assert_eq!(reported, path),
+            other => panic!("Expected FileSystemError::PermissionDenied, got {:?}", other),
+        }
+    }
+}
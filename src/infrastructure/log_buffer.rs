@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+/// Bounded in-memory log of recent lines, dropping the oldest past capacity
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    capacity: usize,
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    /// Create a buffer keeping at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// Append a line, dropping the oldest one when full
+    pub fn push(&self, line: impl Into<String>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line.into());
+    }
+
+    /// Up to `count` most recent lines, oldest first
+    pub fn recent(&self, count: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect()
+    }
+
+    /// Number of lines currently kept
+    pub fn len(&self) -> usize {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Tracing layer recording every event it sees into this buffer
+    pub fn layer(&self) -> LogBufferLayer {
+        LogBufferLayer { buffer: self.clone() }
+    }
+}
+
+/// Tracing layer formatting events as `LEVEL target: message key=value`
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+
+        let target = metadata.target().strip_prefix("groundhog::").unwrap_or(metadata.target());
+        self.buffer.push(format!("{:>5} {}: {}{}", metadata.level(), target, visitor.message, visitor.fields));
+    }
+}
+
+/// Collects the message and remaining fields of an event
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_buffer_drops_oldest_past_capacity() {
+        let buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(format!("line {}", i));
+        }
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.recent(10), vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(buffer.recent(2), vec!["line 3", "line 4"]);
+    }
+
+    #[test]
+    fn test_layer_records_formatted_events() {
+        let buffer = LogBuffer::new(10);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "groundhog::tui::app", request_id = 3, "AI request failed");
+        });
+
+        assert_eq!(buffer.recent(1), vec![" WARN tui::app: AI request failed request_id=3"]);
+    }
+}
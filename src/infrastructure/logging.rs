@@ -1,43 +1,314 @@
-use tracing::Level;
-use tracing_subscriber::{EnvFilter, FmtSubscriber};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use tracing::{Level, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    EnvFilter, Layer,
+    field::RecordFields,
+    Registry,
+    filter::Targets,
+    fmt::{self, FormatFields, MakeWriter, format::{DefaultFields, Writer}},
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+};
+
+use crate::infrastructure::chrome_trace::{ChromeTraceLayer, ChromeTraceWriter, chrome_trace};
+use crate::infrastructure::config::{LogRotation, LoggingConfig};
+use crate::infrastructure::error::InternalError;
+use crate::infrastructure::log_buffer::LogBuffer;
+
+/// Handle for changing the base log level after tracing is initialized
+///
+/// Clones share the same filter, and the per-module `logging.directives`
+/// stay in effect whatever the base level.
+#[derive(Debug, Clone)]
+pub struct LogFilterHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    directives: Vec<String>,
+    level: Arc<Mutex<Level>>,
+}
+
+impl LogFilterHandle {
+    /// The base level currently applied
+    pub fn level(&self) -> Level {
+        *self.level.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the filter with one built for `level`
+    pub fn set_level(&self, level: Level) -> Result<(), InternalError> {
+        let filter = build_env_filter(level, &self.directives)?;
+        self.handle.reload(filter).map_err(|e| InternalError::UnexpectedState {
+            message: format!("Failed to change the log level: {}", e),
+        })?;
+        *self.level.lock().unwrap_or_else(|e| e.into_inner()) = level;
+        Ok(())
+    }
+}
+
+/// Tracing state the application keeps after initialization
+pub struct TracingHandles {
+    /// Writer for `logging.trace_file`, which must be flushed before exiting
+    pub trace_writer: Option<ChromeTraceWriter>,
+    /// Handle for changing the log level at runtime
+    pub log_filter: LogFilterHandle,
+}
 
 /// Initialize tracing subscriber based on verbosity and quiet flags
 pub fn init_tracing(verbose: u8, quiet: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let level = if quiet {
-        Level::ERROR
-    } else {
-        match verbose {
-            0 => Level::WARN,
-            1 => Level::INFO,
-            2 => Level::DEBUG,
-            _ => Level::TRACE,
+    init_tracing_with_writer(verbose, quiet, &LoggingConfig::default(), std::io::stderr, None).map(|_| ())
+}
+
+/// Initialize tracing subscriber writing log lines through the given writer
+///
+/// When `logging.file` is set, log lines are also written to that file,
+/// rotated according to `logging.rotation`. When `logging.json_file` is set,
+/// every event is additionally written there as one JSON object per line.
+/// Events passing the level filter are also kept in `log_buffer`, if given.
+///
+/// When `logging.trace_file` is set, span timings are recorded regardless of
+/// the log level and the returned trace writer must be flushed before exiting.
+pub fn init_tracing_with_writer<W>(
+    verbose: u8,
+    quiet: bool,
+    logging: &LoggingConfig,
+    writer: W,
+    log_buffer: Option<LogBuffer>,
+) -> Result<TracingHandles, Box<dyn std::error::Error + Send + Sync>>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let json_writer = match &logging.json_file {
+        Some(path) => Some(file_appender(path, logging.rotation)?),
+        None => None,
+    };
+    let (trace_layer, trace_writer) = match &logging.trace_file {
+        Some(path) => {
+            let (layer, writer) = chrome_trace(path);
+            (Some(layer), Some(writer))
         }
+        None => (None, None),
     };
 
-    // Create environment filter with proper level filtering
-    let env_filter = if quiet {
-        EnvFilter::from_default_env()
-            .add_directive("groundhog=error".parse()?)
-    } else {
-        EnvFilter::from_default_env()
-            .add_directive(format!("groundhog={}", level.as_str().to_lowercase()).parse()?)
-    };
+    let (subscriber, log_filter) = build_subscriber(verbose, quiet, logging, writer, json_writer, trace_layer, log_buffer)?;
+    subscriber.try_init()?;
 
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(level)
-        .with_env_filter(env_filter)
+    Ok(TracingHandles { trace_writer, log_filter })
+}
+
+/// Compose the stderr, log file, JSON and log buffer layers behind a single
+/// reloadable level filter, plus the span trace layer which records every
+/// groundhog span
+fn build_subscriber<W, J>(
+    verbose: u8,
+    quiet: bool,
+    logging: &LoggingConfig,
+    writer: W,
+    json_writer: Option<J>,
+    trace_layer: Option<ChromeTraceLayer>,
+    log_buffer: Option<LogBuffer>,
+) -> Result<(impl Subscriber + Send + Sync + 'static, LogFilterHandle), Box<dyn std::error::Error + Send + Sync>>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    J: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let level = verbosity_to_level(verbose, quiet);
+
+    let (env_filter, log_filter) = reloadable_filter(level, &logging.directives)?;
+
+    let stderr_layer = fmt::layer()
         .with_target(false)
         .with_thread_ids(false)
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_writer(std::io::stderr)
-        .finish();
+        .with_writer(writer);
+
+    let file_layer = match &logging.file {
+        Some(path) => Some(
+            fmt::layer()
+                .with_ansi(false)
+                .with_target(false)
+                .fmt_fields(FileFields::default())
+                .with_writer(file_appender(path, logging.rotation)?),
+        ),
+        None => None,
+    };
+
+    let json_layer = json_writer.map(|json_writer| {
+        fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_writer(json_writer)
+    });
+
+    let log_layers = stderr_layer
+        .and_then(file_layer)
+        .and_then(json_layer)
+        .and_then(log_buffer.map(|buffer| buffer.layer()))
+        .with_filter(env_filter);
+    let trace_layer = trace_layer.map(|layer| layer.with_filter(Targets::new().with_target("groundhog", Level::TRACE)));
+
+    let subscriber = tracing_subscriber::registry()
+        .with(log_layers)
+        .with(trace_layer);
+    Ok((subscriber, log_filter))
+}
+
+/// Build the log filter like [`build_env_filter`], along with a handle for changing its level later
+pub fn reloadable_filter(
+    level: Level,
+    directives: &[String],
+) -> Result<(reload::Layer<EnvFilter, Registry>, LogFilterHandle), InternalError> {
+    let (filter, handle) = reload::Layer::new(build_env_filter(level, directives)?);
+    let log_filter = LogFilterHandle {
+        handle,
+        directives: directives.to_vec(),
+        level: Arc::new(Mutex::new(level)),
+    };
+    Ok((filter, log_filter))
+}
+
+/// Build the log filter from the base level and per-module directives
+///
+/// Directive targets are relative to the groundhog crate, so `tui=warn`
+/// applies to `groundhog::tui`. `RUST_LOG` directives are kept as well.
+pub fn build_env_filter(level: Level, directives: &[String]) -> Result<EnvFilter, InternalError> {
+    let base = format!("groundhog={}", level.as_str().to_lowercase());
+
+    std::iter::once(base)
+        .chain(directives.iter().map(|d| qualify_directive(d)))
+        .try_fold(EnvFilter::from_default_env(), |filter, directive| {
+            let parsed = directive.parse().map_err(|e: tracing_subscriber::filter::ParseError| {
+                InternalError::InvalidLogDirective {
+                    directive: directive.clone(),
+                    message: e.to_string(),
+                }
+            })?;
+            Ok(filter.add_directive(parsed))
+        })
+}
+
+/// Prefix a directive's target with the crate name unless it already has it
+fn qualify_directive(directive: &str) -> String {
+    let directive = directive.trim();
+    match directive.split_once('=') {
+        Some((target, level)) if !target.is_empty() && target != "groundhog" && !target.starts_with("groundhog::") => {
+            format!("groundhog::{}={}", target, level)
+        }
+        _ => directive.to_string(),
+    }
+}
+
+/// Field formatter for the log file
+///
+/// Span fields are cached per formatter type, so the file layer needs its own
+/// type to avoid reusing the ANSI-styled fields formatted for stderr.
+#[derive(Debug, Default)]
+struct FileFields(DefaultFields);
+
+impl<'writer> FormatFields<'writer> for FileFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> std::fmt::Result {
+        self.0.format_fields(writer, fields)
+    }
+}
+
+/// Map the configured rotation to the rolling strategy used by the file appender
+pub fn rolling_strategy(rotation: LogRotation) -> Rotation {
+    match rotation {
+        LogRotation::Never => Rotation::NEVER,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Hourly => Rotation::HOURLY,
+    }
+}
+
+/// Create a rolling file appender for the given log file path
+///
+/// Rotated files are named after the configured file with a date suffix.
+pub fn file_appender(path: &Path, rotation: LogRotation) -> Result<RollingFileAppender, InternalError> {
+    let directory = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "groundhog.log".to_string());
+
+    RollingFileAppender::builder()
+        .rotation(rolling_strategy(rotation))
+        .filename_prefix(file_name)
+        .build(directory)
+        .map_err(|e| InternalError::InitializationFailed {
+            component: "file logger".to_string(),
+            source: Box::new(e),
+        })
+}
+
+/// Field and header names whose values are masked by [`Redacted`]
+const SENSITIVE_KEYS: [&str; 3] = ["api_key", "x-api-key", "authorization"];
+
+/// Replacement for masked values
+pub const REDACTED: &str = "***";
+
+/// Debug wrapper that masks secrets such as API keys and `Authorization`
+/// headers, for logging structs and header maps
+pub struct Redacted<'a, T: std::fmt::Debug + ?Sized>(pub &'a T);
+
+impl<T: std::fmt::Debug + ?Sized> std::fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&redact(&format!("{:?}", self.0)))
+    }
+}
 
-    tracing::subscriber::set_global_default(subscriber)
-        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+/// Mask the quoted value following each sensitive key in debug output
+///
+/// Handles both struct fields (`api_key: Some("...")`) and header maps
+/// (`"authorization": "..."`).
+pub fn redact(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut masked: Vec<(usize, usize)> = Vec::new();
 
-    Ok(())
+    for key in SENSITIVE_KEYS {
+        for (start, _) in lower.match_indices(key) {
+            let rest = &text[start + key.len()..];
+            // The key must be followed by its separator, not be part of a longer name
+            let Some(separator) = rest.find(':') else { continue };
+            if !rest[..separator].chars().all(|c| c == '"' || c.is_whitespace()) {
+                continue;
+            }
+
+            let value_start = start + key.len() + separator + 1;
+            let Some(open) = text[value_start..].find(['"', ',', '}', '\n']) else { continue };
+            if text.as_bytes()[value_start + open] != b'"' {
+                continue;
+            }
+
+            let content_start = value_start + open + 1;
+            let mut escaped = false;
+            let close = text[content_start..].char_indices().find(|&(_, c)| {
+                let end = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                end
+            });
+            if let Some((close, _)) = close {
+                masked.push((content_start, content_start + close));
+            }
+        }
+    }
+
+    masked.sort_unstable();
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (start, end) in masked {
+        if start < cursor {
+            continue;
+        }
+        output.push_str(&text[cursor..start]);
+        output.push_str(REDACTED);
+        cursor = end;
+    }
+    output.push_str(&text[cursor..]);
+    output
 }
 
 /// Convert verbosity count to log level
@@ -64,6 +335,7 @@ pub fn is_level_enabled(level: Level, verbose: u8, quiet: bool) -> bool {
 #[cfg(test)]
 pub fn init_test_tracing() {
     use std::sync::Once;
+    use tracing_subscriber::FmtSubscriber;
     
     static INIT: Once = Once::new();
     INIT.call_once(|| {
@@ -107,6 +379,162 @@ mod tests {
         assert!(!is_level_enabled(Level::TRACE, 0, true));
     }
 
+    #[test]
+    fn test_rotation_config_selects_rolling_strategy() {
+        let logging: LoggingConfig = toml::from_str("rotation = \"hourly\"").unwrap();
+        assert_eq!(logging.rotation, LogRotation::Hourly);
+        assert_eq!(rolling_strategy(logging.rotation), Rotation::HOURLY);
+
+        assert_eq!(LoggingConfig::default().rotation, LogRotation::Never);
+        assert_eq!(rolling_strategy(LogRotation::Never), Rotation::NEVER);
+        assert_eq!(rolling_strategy(LogRotation::Daily), Rotation::DAILY);
+        assert!(toml::from_str::<LoggingConfig>("rotation = \"weekly\"").is_err());
+    }
+
+    #[test]
+    fn test_file_appender_creates_log_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("groundhog.log");
+
+        assert!(file_appender(&path, LogRotation::Daily).is_ok());
+        assert!(dir.path().join("logs").is_dir());
+    }
+
+    #[test]
+    fn test_directives_adjust_module_levels() {
+        let directives = vec!["tui=error".to_string(), "core=trace".to_string()];
+        let filter = build_env_filter(Level::INFO, &directives).unwrap();
+        let subscriber = tracing_subscriber::registry().with(filter);
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(tracing::enabled!(target: "groundhog::core::services", Level::TRACE));
+            assert!(!tracing::enabled!(target: "groundhog::tui::app", Level::WARN));
+            assert!(tracing::enabled!(target: "groundhog::tui::app", Level::ERROR));
+            assert!(tracing::enabled!(target: "groundhog::cli", Level::INFO));
+            assert!(!tracing::enabled!(target: "groundhog::cli", Level::DEBUG));
+        });
+    }
+
+    #[test]
+    fn test_qualify_directive() {
+        assert_eq!(qualify_directive("tui=warn"), "groundhog::tui=warn");
+        assert_eq!(qualify_directive("groundhog::core=trace"), "groundhog::core=trace");
+        assert_eq!(qualify_directive("debug"), "debug");
+    }
+
+    #[test]
+    fn test_invalid_directive_is_rejected() {
+        let directives = vec!["tui=loud".to_string()];
+        match build_env_filter(Level::INFO, &directives) {
+            Err(InternalError::InvalidLogDirective { directive, .. }) => assert_eq!(directive, "groundhog::tui=loud"),
+            other => panic!("Expected InternalError::InvalidLogDirective, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    /// In-memory log sink shared between a layer and the test
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for SharedBuffer {
+        type Writer = SharedBuffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_event_reaches_stderr_and_json_sinks() {
+        let stderr = SharedBuffer::default();
+        let json = SharedBuffer::default();
+        let (subscriber, _) = build_subscriber(1, false, &LoggingConfig::default(), stderr.clone(), Some(json.clone()), None, None).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(answer = 42, "hello sinks");
+            tracing::debug!("filtered out at info level");
+        });
+
+        assert!(stderr.contents().contains("hello sinks"));
+        assert!(!stderr.contents().contains("filtered out"));
+
+        let lines: Vec<String> = json.contents().lines().map(String::from).collect();
+        assert_eq!(lines.len(), 1);
+        let event: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["fields"]["message"], "hello sinks");
+        assert_eq!(event["fields"]["answer"], 42);
+    }
+
+    #[test]
+    fn test_log_filter_handle_applies_new_level() {
+        let logging = LoggingConfig {
+            directives: vec!["tui=error".to_string()],
+            ..LoggingConfig::default()
+        };
+        let stderr = SharedBuffer::default();
+        let (subscriber, log_filter) = build_subscriber(0, false, &logging, stderr.clone(), None::<SharedBuffer>, None, None).unwrap();
+        assert_eq!(log_filter.level(), Level::WARN);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("before the change");
+            log_filter.set_level(Level::DEBUG).unwrap();
+            tracing::debug!("after the change");
+            tracing::warn!(target: "groundhog::tui::app", "still filtered by directive");
+        });
+
+        assert_eq!(log_filter.level(), Level::DEBUG);
+        assert!(!stderr.contents().contains("before the change"));
+        assert!(stderr.contents().contains("after the change"));
+        assert!(!stderr.contents().contains("still filtered"));
+    }
+
+    #[test]
+    fn test_redacted_masks_api_key_field() {
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Credentials {
+            model: String,
+            api_key: Option<String>,
+        }
+
+        let credentials = Credentials {
+            model: "gpt-4o".to_string(),
+            api_key: Some("sk-secret".to_string()),
+        };
+        let formatted = format!("{:?}", Redacted(&credentials));
+
+        assert!(!formatted.contains("sk-secret"));
+        assert!(formatted.contains(r#"api_key: Some("***")"#));
+        assert!(formatted.contains(r#"model: "gpt-4o""#));
+    }
+
+    #[test]
+    fn test_redact_masks_authorization_headers() {
+        let text = r#"{"content-type": "application/json", "Authorization": "Bearer sk-secret", "x-api-key": "ant-\"key"}"#;
+
+        assert_eq!(
+            redact(text),
+            r#"{"content-type": "application/json", "Authorization": "***", "x-api-key": "***"}"#
+        );
+    }
+
     #[test]
     fn test_init_test_tracing() {
         // This should not panic when called multiple times
@@ -0,0 +1,76 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::core::models::CommandResult;
+use crate::infrastructure::error::{FileSystemError, GroundhogError};
+
+/// One line of the command timing log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandMetric<'a> {
+    pub command: &'a str,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// Unix time in milliseconds when the command finished
+    pub timestamp: u64,
+}
+
+impl<'a> CommandMetric<'a> {
+    /// Metric for a finished command, timestamped now
+    pub fn new(command: &'a str, result: &CommandResult) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Self {
+            command,
+            duration_ms: result.duration_ms,
+            success: result.success,
+            timestamp,
+        }
+    }
+}
+
+/// Append `metric` as a JSON line to `path`, creating the file and its directory
+pub fn append(path: &Path, metric: &CommandMetric<'_>) -> Result<(), GroundhogError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(FileSystemError::Io)?;
+    }
+
+    let line = serde_json::to_string(metric).map_err(|e| FileSystemError::Io(e.into()))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(FileSystemError::Io)?;
+    writeln!(file, "{}", line).map_err(FileSystemError::Io)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_writes_one_line_per_command() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("metrics.jsonl");
+        let result = CommandResult::success().with_duration(Duration::from_millis(12));
+
+        append(&path, &CommandMetric::new("explain", &result)).unwrap();
+        append(&path, &CommandMetric::new("ping", &CommandResult::failure("down"))).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["command"], "explain");
+        assert_eq!(lines[0]["duration_ms"], 12);
+        assert_eq!(lines[0]["success"], true);
+        assert!(lines[0]["timestamp"].as_u64().unwrap() > 0);
+        assert_eq!(lines[1]["success"], false);
+    }
+}
@@ -1,7 +1,14 @@
+pub mod chrome_trace;
 pub mod config;
+pub mod dotenv;
 pub mod error;
+pub mod fs;
+pub mod http;
+pub mod log_buffer;
 pub mod logging;
+pub mod metrics;
+pub mod report;
 
-pub use config::Config;
+pub use config::{Config, ConfigSource};
 pub use error::GroundhogError;
 pub use logging::init_tracing; 
\ No newline at end of file
@@ -2,12 +2,14 @@
 //!
 //! A command-line AI coding assistant built in Rust.
 
+mod app;
 pub mod cli;
 pub mod core;
 pub mod infrastructure;
 pub mod tui;
 
 // Re-export main types for convenience
+pub use app::run;
 pub use cli::{Cli, Commands};
 pub use infrastructure::error::GroundhogError;
 
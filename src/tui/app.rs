@@ -1,38 +1,280 @@
 use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
-        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+        event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers},
         execute,
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     },
     Terminal,
 };
-use tracing::{info, instrument};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{Level, debug, info, instrument, warn};
 
-use crate::infrastructure::error::GroundhogError;
+use crate::cli::highlight::Highlighter;
+use crate::cli::output::{self, OutputFormat};
+use crate::core::models::Conversation;
+use crate::core::providers::HttpProvider;
+use crate::core::services::{AIService, Usage, UsageTracker};
+use crate::infrastructure::Config;
+use crate::infrastructure::error::{GroundhogError, InternalError, NetworkError};
+use crate::infrastructure::log_buffer::LogBuffer;
+use crate::infrastructure::logging::LogFilterHandle;
+use super::event::{scroll_delta, Event, EventHandler};
+use super::input::InputBuffer;
+use super::keys::KeyBindings;
+use super::palette::{Palette, PaletteAction};
+use super::picker::ModelPicker;
 use super::ui;
 
+/// Placeholder shown while waiting for the AI response
+pub const THINKING_PLACEHOLDER: &str = "thinking...";
+
+/// Note added to the conversation when a request is cancelled
+pub const CANCELLED_MESSAGE: &str = "(cancelled)";
+
+/// How long an AI request may take when no timeout is configured
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Messages that can queue up between redraws before background tasks hold back
+pub const MESSAGE_CHANNEL_CAPACITY: usize = 32;
+
+/// Messages sent from background tasks to the application
+#[derive(Debug)]
+pub enum AppMessage {
+    /// Text of an AI reply streamed since the previous delta
+    Delta {
+        request_id: u64,
+        text: String,
+    },
+    /// An AI request finished, with the reply and the tokens it used
+    Completed {
+        request_id: u64,
+        result: Result<(String, Usage), GroundhogError>,
+    },
+    /// An AI request was abandoned after `timeout`
+    TimedOut {
+        request_id: u64,
+        timeout: Duration,
+    },
+    /// Listing the provider's models for the model picker finished
+    Models {
+        result: Result<Vec<String>, GroundhogError>,
+    },
+}
+
+/// State of the current AI request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestState {
+    /// No request in flight
+    Idle,
+    /// Waiting for the response to the given request
+    Thinking { request_id: u64 },
+    /// The last request failed or timed out; input is accepted again
+    Error { message: String },
+}
+
+/// Frame timing and event counts shown in the debug overlay
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebugStats {
+    /// Time between the last two frames
+    pub frame_time: Duration,
+    pub frames: u64,
+    pub key_events: u64,
+    pub mouse_events: u64,
+    pub resize_events: u64,
+    pub ticks: u64,
+}
+
+impl DebugStats {
+    /// Frames per second based on the last frame time
+    pub fn fps(&self) -> f64 {
+        if self.frame_time.is_zero() {
+            return 0.0;
+        }
+        1.0 / self.frame_time.as_secs_f64()
+    }
+
+    /// Count an event by kind
+    pub fn record_event(&mut self, event: &Event) {
+        match event {
+            Event::Key(_) => self.key_events += 1,
+            Event::Mouse(_) => self.mouse_events += 1,
+            Event::Resize(_, _) => self.resize_events += 1,
+            Event::Tick => self.ticks += 1,
+        }
+    }
+
+    /// Record that a frame was drawn `frame_time` after the previous one
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        self.frames += 1;
+        self.frame_time = frame_time;
+    }
+}
+
+/// Loads the configuration again for the reload action
+pub type ConfigLoader = Box<dyn Fn() -> Result<Config, GroundhogError> + Send>;
+
 /// Main TUI application state
 pub struct App {
     /// Should the application quit?
     pub should_quit: bool,
     /// Current message to display
     pub message: String,
-    /// Counter for demo purposes
-    pub counter: u32,
+    /// Prompt being typed
+    pub input: InputBuffer,
+    /// Conversation with the assistant
+    pub conversation: Conversation,
+    /// Short status note, e.g. the result of an export
+    pub notice: Option<String>,
+    /// Directory conversations are exported to
+    pub export_dir: PathBuf,
+    /// Whether the help popup is open
+    pub show_help: bool,
+    /// The command palette, while it is open
+    pub palette: Option<Palette>,
+    /// The model picker, while it is open
+    pub model_picker: Option<ModelPicker>,
+    /// State of the current AI request
+    pub state: RequestState,
+    /// Vertical scroll offset of the message pane
+    pub scroll: u16,
+    /// Active key bindings
+    pub key_bindings: KeyBindings,
+    /// Syntax highlighting for code in assistant messages
+    pub highlighter: Highlighter,
+    /// Log lines shown in the debug overlay, when enabled
+    pub debug_logs: Option<LogBuffer>,
+    /// Statistics shown in the debug overlay
+    pub debug_stats: DebugStats,
+    /// Estimated token usage of the session
+    pub usage: UsageTracker,
+    /// Prompt sent on the first tick, from `tui --prompt`
+    pub pending_prompt: Option<String>,
+    /// Reply streamed so far for the request in flight
+    pub partial_reply: String,
+    service: Arc<AIService>,
+    /// Configuration the service was built from, with the picked model
+    config: Option<Config>,
+    log_filter: Option<LogFilterHandle>,
+    config_loader: Option<ConfigLoader>,
+    request_timeout: Duration,
+    cancel: Option<CancellationToken>,
+    next_request_id: u64,
+    tx: mpsc::Sender<AppMessage>,
+    rx: mpsc::Receiver<AppMessage>,
 }
 
 impl App {
     /// Create a new App instance
     pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel(MESSAGE_CHANNEL_CAPACITY);
         Self {
             should_quit: false,
             message: "Hello, Groundhog! 🐹".to_string(),
-            counter: 0,
+            input: InputBuffer::new(),
+            conversation: Conversation::new(),
+            notice: None,
+            export_dir: PathBuf::from("."),
+            show_help: false,
+            palette: None,
+            model_picker: None,
+            state: RequestState::Idle,
+            scroll: 0,
+            key_bindings: KeyBindings::default(),
+            highlighter: Highlighter::plain(),
+            debug_logs: None,
+            debug_stats: DebugStats::default(),
+            usage: UsageTracker::new(),
+            pending_prompt: None,
+            partial_reply: String::new(),
+            service: Arc::new(AIService::new()),
+            config: None,
+            log_filter: None,
+            config_loader: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            cancel: None,
+            next_request_id: 0,
+            tx,
+            rx,
+        }
+    }
+
+    /// Create an App that sends `prompt` as soon as the event loop starts
+    pub fn new_with_prompt(prompt: impl Into<String>) -> Self {
+        let prompt = prompt.into().trim().to_string();
+        Self {
+            pending_prompt: (!prompt.is_empty()).then_some(prompt),
+            ..Self::new()
         }
     }
 
+    /// Use custom key bindings
+    pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
+    /// Highlight code in assistant messages with `highlighter`
+    pub fn with_highlighter(mut self, highlighter: Highlighter) -> Self {
+        self.highlighter = highlighter;
+        self
+    }
+
+    /// Use the given AI service for prompts
+    ///
+    /// Session usage is priced as the service's model.
+    pub fn with_service(mut self, service: Arc<AIService>) -> Self {
+        self.usage = UsageTracker::new().with_model(service.model.clone());
+        self.service = service;
+        self
+    }
+
+    /// Remember the configuration the service was built from, so models can be listed and switched
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Give up on AI requests that take longer than `timeout`
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Export conversations to the given directory
+    pub fn with_export_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.export_dir = dir.into();
+        self
+    }
+
+    /// Load the configuration with `loader` when asked to reload it
+    pub fn with_config_loader(mut self, loader: ConfigLoader) -> Self {
+        self.config_loader = Some(loader);
+        self
+    }
+
+    /// Change the log level at runtime through `log_filter`
+    pub fn with_log_filter(mut self, log_filter: LogFilterHandle) -> Self {
+        self.log_filter = Some(log_filter);
+        self
+    }
+
+    /// Show the debug overlay with lines from the given log buffer
+    pub fn with_debug(mut self, logs: LogBuffer) -> Self {
+        self.debug_logs = Some(logs);
+        self
+    }
+
+    /// Whether the debug overlay is shown
+    pub fn debug_enabled(&self) -> bool {
+        self.debug_logs.is_some()
+    }
+
     /// Run the TUI application
     #[instrument(skip(self))]
     pub async fn run(&mut self) -> Result<(), GroundhogError> {
@@ -67,45 +309,1025 @@ impl App {
     /// Main application event loop
     #[instrument(skip(self, terminal))]
     async fn run_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<(), GroundhogError> {
+        let events = EventHandler::default();
+        let mut last_frame = Instant::now();
+
         loop {
+            let now = Instant::now();
+            self.debug_stats.record_frame(now - last_frame);
+            last_frame = now;
+
             // Draw the UI
             terminal
                 .draw(|f| ui::render(f, self))
                 .map_err(|e| GroundhogError::TUIError(e.to_string()))?;
 
             // Handle events
-            if event::poll(std::time::Duration::from_millis(100))
-                .map_err(|e| GroundhogError::TUIError(e.to_string()))?
-            {
-                if let Event::Key(key) = event::read().map_err(|e| GroundhogError::TUIError(e.to_string()))? {
-                    match key.code {
-                        KeyCode::Char('q') => {
-                            self.should_quit = true;
-                        }
-                        KeyCode::Char(' ') => {
-                            self.counter += 1;
-                            self.message = format!("Counter: {} (Press 'q' to quit, Space to increment)", self.counter);
-                        }
-                        KeyCode::Char('r') => {
-                            self.counter = 0;
-                            self.message = "Counter reset! 🐹".to_string();
-                        }
-                        _ => {}
-                    }
-                }
-            }
+            let event = events.next()?;
+            self.handle_event(event);
+            self.process_messages();
 
             if self.should_quit {
                 break;
             }
         }
 
+        if let Some(cancel) = self.cancel.take() {
+            cancel.cancel();
+        }
+
         Ok(())
     }
+
+    /// Apply a single event to the application state
+    pub fn handle_event(&mut self, event: Event) {
+        self.debug_stats.record_event(&event);
+        match event {
+            Event::Key(key) => self.handle_key(key),
+            Event::Mouse(kind) => {
+                if let Some(delta) = scroll_delta(kind) {
+                    self.scroll_by(delta);
+                }
+            }
+            Event::Tick => {
+                if let Some(prompt) = self.pending_prompt.take() {
+                    self.send_prompt(prompt);
+                }
+            }
+            Event::Resize(_, _) => {}
+        }
+    }
+
+    /// Handle a key press
+    ///
+    /// While the help popup is open it swallows every key except quit, and
+    /// Esc or the help key close it. A printable help key only opens the
+    /// popup while the prompt is empty, so it can still be typed.
+    fn handle_key(&mut self, key: KeyEvent) {
+        let bindings = &self.key_bindings;
+
+        if self.show_help {
+            if bindings.quit.matches(&key) {
+                self.should_quit = true;
+            } else if key.code == KeyCode::Esc || bindings.help.matches(&key) {
+                self.show_help = false;
+            }
+            return;
+        }
+
+        if self.palette.is_some() {
+            self.handle_palette_key(key);
+            return;
+        }
+
+        if self.model_picker.is_some() {
+            self.handle_model_picker_key(key);
+            return;
+        }
+
+        if bindings.palette.matches(&key) {
+            self.palette = Some(Palette::default());
+        } else if bindings.help.matches(&key) && (self.input.is_empty() || !bindings.help.is_printable()) {
+            self.show_help = true;
+        } else if bindings.quit.matches(&key) {
+            self.should_quit = true;
+        } else if bindings.submit.matches(&key) {
+            self.submit();
+        } else if bindings.cancel.matches(&key) {
+            self.cancel_request();
+        } else if bindings.clear.matches(&key) {
+            self.clear();
+        } else if bindings.scroll_up.matches(&key) {
+            self.scroll_by(-1);
+        } else if bindings.scroll_down.matches(&key) {
+            self.scroll_by(1);
+        } else if bindings.export.matches(&key) {
+            self.export_conversation();
+        } else if bindings.reload.matches(&key) {
+            self.reload_config();
+        } else if bindings.log_level.matches(&key) {
+            self.cycle_log_level();
+        } else if bindings.model.matches(&key) {
+            self.open_model_picker();
+        } else {
+            self.edit_input(key);
+        }
+    }
+
+    /// Apply a key press to the open command palette
+    fn handle_palette_key(&mut self, key: KeyEvent) {
+        let Some(palette) = self.palette.as_mut() else { return };
+
+        if self.key_bindings.quit.matches(&key) {
+            self.should_quit = true;
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Enter => {
+                let action = palette.selected_action();
+                self.palette = None;
+                if let Some(action) = action {
+                    self.run_action(action);
+                }
+            }
+            KeyCode::Up => palette.move_selection(-1),
+            KeyCode::Down => palette.move_selection(1),
+            KeyCode::Backspace => palette.pop(),
+            KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                palette.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Run an action chosen from the command palette
+    fn run_action(&mut self, action: PaletteAction) {
+        debug!(?action, "Running palette action");
+        match action {
+            PaletteAction::Clear => self.clear(),
+            PaletteAction::Export => self.export_conversation(),
+            PaletteAction::Cancel => self.cancel_request(),
+            PaletteAction::Help => self.show_help = true,
+            PaletteAction::Reload => self.reload_config(),
+            PaletteAction::LogLevel => self.cycle_log_level(),
+            PaletteAction::Model => self.open_model_picker(),
+            PaletteAction::Quit => self.should_quit = true,
+        }
+    }
+
+    /// Apply a key press to the open model picker
+    fn handle_model_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.model_picker.as_mut() else { return };
+
+        if self.key_bindings.quit.matches(&key) {
+            self.should_quit = true;
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.model_picker = None,
+            KeyCode::Enter => {
+                let model = picker.selected_model().map(str::to_string);
+                if let Some(model) = model {
+                    self.model_picker = None;
+                    self.select_model(&model);
+                }
+            }
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            _ => {}
+        }
+    }
+
+    /// Apply a key press to the prompt input
+    ///
+    /// Enter with Shift or Alt starts a new line, unless bound to submit.
+    fn edit_input(&mut self, key: KeyEvent) {
+        if InputBuffer::is_newline_key(&key) {
+            self.input.insert_newline();
+            return;
+        }
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                self.input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// Send the current input as a prompt to the AI service
+    pub fn submit(&mut self) {
+        let Some(prompt) = self.input.prompt().map(str::to_string) else { return };
+        if matches!(self.state, RequestState::Thinking { .. }) {
+            return;
+        }
+        self.input.clear();
+        self.send_prompt(prompt);
+    }
+
+    /// Add `prompt` to the conversation and request a reply in the background
+    ///
+    /// Earlier turns are sent along as context, see [`AIService::stream_reply_with_cancel`].
+    fn send_prompt(&mut self, prompt: String) {
+        self.next_request_id += 1;
+        let request_id = self.next_request_id;
+        debug!(request_id, "Submitting prompt");
+
+        self.conversation.push_user(prompt);
+        let conversation = self.conversation.clone();
+        self.partial_reply.clear();
+        self.state = RequestState::Thinking { request_id };
+
+        let cancel = CancellationToken::new();
+        self.cancel = Some(cancel.clone());
+
+        let service = Arc::clone(&self.service);
+        let tx = self.tx.clone();
+        let timeout = self.request_timeout;
+        tokio::spawn(async move {
+            let mut reply = ReplyStream::new(tx.clone(), request_id);
+            let mut on_delta = |delta: &str| reply.push(delta);
+            let request = service.stream_reply_with_cancel(&conversation, &cancel, &mut on_delta);
+            let result = tokio::time::timeout(timeout, request).await;
+            let message = match result {
+                Ok(result) => AppMessage::Completed {
+                    request_id,
+                    result: result.map(|usage| (reply.text, usage)),
+                },
+                Err(_) => AppMessage::TimedOut { request_id, timeout },
+            };
+            // The receiver is gone once the app has quit
+            let _ = tx.send(message).await;
+        });
+    }
+
+    /// Load and validate the configuration again, then switch to its AI service
+    ///
+    /// The outcome is reported through [`App::notice`]; on failure the
+    /// current service is kept.
+    pub fn reload_config(&mut self) {
+        let Some(loader) = &self.config_loader else {
+            self.notice = Some("Configuration cannot be reloaded in this session".to_string());
+            return;
+        };
+
+        match loader().and_then(|config| config.validate().map(|_| config)) {
+            Ok(config) => self.apply_config(&config),
+            Err(e) => {
+                warn!(error = %e, "Failed to reload configuration");
+                let message = e.user_message();
+                self.notice = Some(format!("Reload failed: {}", message.lines().next().unwrap_or_default()));
+            }
+        }
+    }
+
+    /// Switch to the AI service and request timeout described by `config`
+    ///
+    /// A request already in flight finishes against the previous service.
+    pub fn apply_config(&mut self, config: &Config) {
+        let service = Arc::new(AIService::for_chat(config));
+        info!(model = ?service.model, available = service.is_available(), "Configuration reloaded");
+
+        self.notice = Some(match &service.model {
+            Some(model) => format!("Configuration reloaded, using {}", model),
+            None => "Configuration reloaded".to_string(),
+        });
+        self.usage = std::mem::take(&mut self.usage).with_model(service.model.clone());
+        self.request_timeout = Duration::from_secs(config.performance.timeout);
+        self.service = service;
+        self.config = Some(config.clone());
+    }
+
+    /// Model of the AI service prompts are sent to
+    pub fn active_model(&self) -> Option<&str> {
+        self.service.model.as_deref()
+    }
+
+    /// Open the model picker and list the provider's models in the background
+    ///
+    /// The models are those `providers list` shows, and need the same API
+    /// key. Without an `[ai]` section the picker stays closed.
+    pub fn open_model_picker(&mut self) {
+        let Some(ai) = self.config.as_ref().and_then(|config| config.ai.clone()) else {
+            self.notice = Some("No AI provider is configured".to_string());
+            return;
+        };
+        debug!(provider = ?ai.provider, "Listing models for the model picker");
+
+        self.model_picker = Some(ModelPicker::loading());
+        let tx = self.tx.clone();
+        let timeout = self.request_timeout;
+        tokio::spawn(async move {
+            let result = match HttpProvider::from_config(&ai, timeout) {
+                Ok(provider) => provider.list_models().await,
+                Err(e) => Err(e),
+            };
+            // The receiver is gone once the app has quit
+            let _ = tx.send(AppMessage::Models { result }).await;
+        });
+    }
+
+    /// Send prompts to `model` for the rest of the session
+    ///
+    /// The AI service is rebuilt from the configuration with only the model
+    /// changed; a request already in flight finishes against the previous
+    /// one. Reloading the configuration switches back to its model.
+    pub fn select_model(&mut self, model: &str) {
+        let Some(mut config) = self.config.clone() else {
+            self.notice = Some("No AI provider is configured".to_string());
+            return;
+        };
+        if let Some(ai) = config.ai.as_mut() {
+            ai.model = model.to_string();
+        }
+
+        let service = Arc::new(AIService::for_chat(&config));
+        info!(model, "Switched model");
+        self.notice = Some(format!("Switched to {}", model));
+        self.usage = std::mem::take(&mut self.usage).with_model(service.model.clone());
+        self.service = service;
+        self.config = Some(config);
+    }
+
+    /// Switch to the next more verbose log level, wrapping from trace back to error
+    pub fn cycle_log_level(&mut self) {
+        let current = self.log_filter.as_ref().map_or(Level::WARN, LogFilterHandle::level);
+        let next = match current {
+            Level::ERROR => Level::WARN,
+            Level::WARN => Level::INFO,
+            Level::INFO => Level::DEBUG,
+            Level::DEBUG => Level::TRACE,
+            _ => Level::ERROR,
+        };
+        self.set_log_level(next);
+    }
+
+    /// Change the base log level, reporting the outcome through [`App::notice`]
+    pub fn set_log_level(&mut self, level: Level) {
+        let Some(log_filter) = &self.log_filter else {
+            self.notice = Some("Log level cannot be changed in this session".to_string());
+            return;
+        };
+
+        self.notice = Some(match log_filter.set_level(level) {
+            Ok(()) => {
+                info!(%level, "Log level changed");
+                format!("Log level set to {}", level.as_str().to_lowercase())
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to change the log level");
+                format!("Log level change failed: {}", GroundhogError::from(e).user_message())
+            }
+        });
+    }
+
+    /// Cancel the in-flight AI request, if any
+    pub fn cancel_request(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            info!("Cancelling AI request");
+            cancel.cancel();
+            self.conversation.push_note(CANCELLED_MESSAGE);
+            self.partial_reply.clear();
+            self.state = RequestState::Idle;
+        }
+    }
+
+    /// Clear the conversation history
+    fn clear(&mut self) {
+        self.cancel_request();
+        self.conversation = Conversation::new();
+        self.notice = None;
+        self.scroll = 0;
+    }
+
+    /// Write the conversation as markdown to the export directory
+    ///
+    /// The outcome is reported through [`App::notice`].
+    pub fn export_conversation(&mut self) {
+        let path = self.export_dir.join(format!("groundhog-{}.md", self.conversation.id));
+        let result = output::render(OutputFormat::Markdown, &output::conversation_outputs(&self.conversation))
+            .and_then(|markdown| output::write_output_file(&path, &markdown, true));
+
+        self.notice = Some(match result {
+            Ok(()) => {
+                info!(path = %path.display(), "Exported conversation");
+                format!("Exported to {}", path.display())
+            }
+            Err(e) => {
+                warn!(error = %e, "Conversation export failed");
+                format!("Export failed: {}", e.user_message())
+            }
+        });
+    }
+
+    /// Apply all pending messages from background tasks
+    ///
+    /// Deltas that queued up since the last redraw are applied as one.
+    pub fn process_messages(&mut self) {
+        let mut messages = Vec::new();
+        while let Ok(message) = self.rx.try_recv() {
+            messages.push(message);
+        }
+        for message in coalesce_deltas(messages) {
+            self.handle_message(message);
+        }
+    }
+
+    /// Apply a single message from a background task
+    ///
+    /// A timeout is reported like any other failed request, leaving the app
+    /// in [`RequestState::Error`] with input enabled.
+    pub fn handle_message(&mut self, message: AppMessage) {
+        let (request_id, result) = match message {
+            AppMessage::Delta { request_id, text } => {
+                if self.state == (RequestState::Thinking { request_id }) {
+                    self.partial_reply.push_str(&text);
+                }
+                return;
+            }
+            AppMessage::Completed { request_id, result } => (request_id, result),
+            AppMessage::TimedOut { request_id, timeout } => {
+                let error = NetworkError::Timeout {
+                    timeout_ms: timeout.as_millis() as u64,
+                };
+                (request_id, Err(error.into()))
+            }
+            AppMessage::Models { result } => {
+                self.show_models(result);
+                return;
+            }
+        };
+
+        if self.state != (RequestState::Thinking { request_id }) {
+            debug!(request_id, "Ignoring response for stale request");
+            return;
+        }
+
+        self.cancel = None;
+        self.partial_reply.clear();
+        self.state = match result {
+            Ok((text, usage)) => {
+                self.usage.record(usage);
+                self.conversation.push_assistant(text);
+                RequestState::Idle
+            }
+            Err(GroundhogError::Internal(InternalError::Cancelled { .. })) => {
+                self.conversation.push_note(CANCELLED_MESSAGE);
+                RequestState::Idle
+            }
+            Err(e) => {
+                warn!(error = %e, "AI request failed");
+                let message = format!("error: {}", e.user_message());
+                self.conversation.push_note(message.clone());
+                RequestState::Error { message }
+            }
+        };
+    }
+
+    /// Fill the model picker waiting for models, or close it with a notice when there are none
+    fn show_models(&mut self, result: Result<Vec<String>, GroundhogError>) {
+        let active = self.service.model.clone();
+        let Some(picker) = self.model_picker.as_mut().filter(|picker| picker.models.is_none()) else {
+            debug!("Ignoring models for a closed model picker");
+            return;
+        };
+
+        match result {
+            Ok(models) if !models.is_empty() => picker.show(models, active.as_deref()),
+            Ok(_) => {
+                self.model_picker = None;
+                self.notice = Some("The provider lists no models".to_string());
+            }
+            Err(e) => {
+                warn!(error = %e, "Listing models failed");
+                self.model_picker = None;
+                self.notice = Some(format!("Listing models failed: {}", e.user_message().lines().next().unwrap_or_default()));
+            }
+        }
+    }
+
+    /// Move the message pane scroll offset, clamping at the top
+    pub fn scroll_by(&mut self, delta: i32) {
+        let offset = (self.scroll as i32 + delta).clamp(0, u16::MAX as i32);
+        self.scroll = offset as u16;
+    }
 }
 
 impl Default for App {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+}
+
+/// Sends a streamed reply to the application as deltas, without ever waiting on the channel
+///
+/// While the channel is full, text accumulates and goes out with the next
+/// delta that fits, so a slow UI gets fewer, larger deltas.
+struct ReplyStream {
+    tx: mpsc::Sender<AppMessage>,
+    request_id: u64,
+    /// The whole reply so far
+    text: String,
+    /// Length of `text` already sent
+    sent: usize,
+}
+
+impl ReplyStream {
+    fn new(tx: mpsc::Sender<AppMessage>, request_id: u64) -> Self {
+        Self {
+            tx,
+            request_id,
+            text: String::new(),
+            sent: 0,
+        }
+    }
+
+    fn push(&mut self, delta: &str) {
+        self.text.push_str(delta);
+        if let Ok(permit) = self.tx.try_reserve() {
+            permit.send(AppMessage::Delta {
+                request_id: self.request_id,
+                text: self.text[self.sent..].to_string(),
+            });
+            self.sent = self.text.len();
+        }
+    }
+}
+
+/// Merge consecutive deltas of the same request into one, keeping the order of all messages
+fn coalesce_deltas(messages: Vec<AppMessage>) -> Vec<AppMessage> {
+    let mut merged: Vec<AppMessage> = Vec::with_capacity(messages.len());
+    for message in messages {
+        if let AppMessage::Delta { request_id, text } = &message
+            && let Some(AppMessage::Delta { request_id: last_id, text: last_text }) = merged.last_mut()
+            && last_id == request_id
+        {
+            last_text.push_str(text);
+            continue;
+        }
+        merged.push(message);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::MouseEventKind;
+    use crate::core::models::Role;
+    use crate::core::services::testing::{HangingProvider, RecordingProvider};
+    use crate::infrastructure::config::{ConfigFormat, KeyBindingsConfig};
+    use crate::infrastructure::logging::reloadable_filter;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_event(key(KeyCode::Char(c)));
+        }
+    }
+
+    fn transcript(app: &App) -> Vec<(Role, &str)> {
+        app.conversation.messages.iter().map(|m| (m.role, m.content.as_str())).collect()
+    }
+
+    /// Next message other than a streamed delta
+    async fn next_message(app: &mut App) -> AppMessage {
+        loop {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(1), app.rx.recv())
+                .await
+                .expect("timed out waiting for app message")
+                .expect("channel closed");
+            if !matches!(message, AppMessage::Delta { .. }) {
+                return message;
+            }
+        }
+    }
+
+    #[test]
+    fn test_mouse_scroll_changes_offset() {
+        let mut app = App::new();
+
+        app.handle_event(Event::Mouse(MouseEventKind::ScrollDown));
+        assert_eq!(app.scroll, 3);
+
+        app.handle_event(Event::Mouse(MouseEventKind::ScrollDown));
+        app.handle_event(Event::Mouse(MouseEventKind::ScrollUp));
+        assert_eq!(app.scroll, 3);
+    }
+
+    #[test]
+    fn test_debug_stats_count_events() {
+        let mut app = App::new().with_debug(LogBuffer::new(10));
+        assert!(app.debug_enabled());
+
+        app.handle_event(key(KeyCode::Char('a')));
+        app.handle_event(Event::Tick);
+        app.handle_event(Event::Tick);
+        app.handle_event(Event::Mouse(MouseEventKind::ScrollDown));
+
+        assert_eq!(app.debug_stats.key_events, 1);
+        assert_eq!(app.debug_stats.ticks, 2);
+        assert_eq!(app.debug_stats.mouse_events, 1);
+
+        app.debug_stats.record_frame(Duration::from_millis(50));
+        assert_eq!(app.debug_stats.fps(), 20.0);
+    }
+
+    #[test]
+    fn test_mouse_scroll_up_clamps_at_top() {
+        let mut app = App::new();
+
+        app.handle_event(Event::Mouse(MouseEventKind::ScrollUp));
+        assert_eq!(app.scroll, 0);
+    }
+
+    #[test]
+    fn test_custom_quit_binding_triggers_quit() {
+        let config = KeyBindingsConfig {
+            quit: "x".to_string(),
+            ..KeyBindingsConfig::default()
+        };
+        let mut app = App::new().with_key_bindings(KeyBindings::from_config(&config).unwrap());
+
+        app.handle_event(key(KeyCode::Char('q')));
+        assert!(!app.should_quit);
+
+        app.handle_event(key(KeyCode::Char('x')));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_vim_scroll_bindings() {
+        let config = KeyBindingsConfig {
+            scroll_up: "k".to_string(),
+            scroll_down: "j".to_string(),
+            ..KeyBindingsConfig::default()
+        };
+        let mut app = App::new().with_key_bindings(KeyBindings::from_config(&config).unwrap());
+
+        app.handle_event(key(KeyCode::Char('j')));
+        app.handle_event(key(KeyCode::Char('j')));
+        app.handle_event(key(KeyCode::Char('k')));
+        assert_eq!(app.scroll, 1);
+    }
+
+    #[test]
+    fn test_typing_edits_input() {
+        let mut app = App::new();
+
+        type_text(&mut app, "rusty");
+        app.handle_event(key(KeyCode::Backspace));
+        assert_eq!(app.input.as_str(), "rust");
+    }
+
+    #[test]
+    fn test_log_level_key_cycles_filter() {
+        let (filter_layer, log_filter) = reloadable_filter(Level::DEBUG, &[]).unwrap();
+        let mut app = App::new().with_log_filter(log_filter.clone());
+
+        app.handle_event(key(KeyCode::F(2)));
+        assert_eq!(log_filter.level(), Level::TRACE);
+        assert_eq!(app.notice.as_deref(), Some("Log level set to trace"));
+
+        app.handle_event(key(KeyCode::F(2)));
+        assert_eq!(log_filter.level(), Level::ERROR);
+
+        // The handle only works while the subscriber holding the filter is alive
+        drop(filter_layer);
+        app.set_log_level(Level::INFO);
+        assert_eq!(log_filter.level(), Level::ERROR);
+        assert!(app.notice.as_deref().unwrap().starts_with("Log level change failed"));
+
+        let mut app = App::new();
+        app.cycle_log_level();
+        assert_eq!(app.notice.as_deref(), Some("Log level cannot be changed in this session"));
+    }
+
+    #[tokio::test]
+    async fn test_preloaded_prompt_is_sent_on_first_tick() {
+        let provider = Arc::new(RecordingProvider::new("Ownership explained"));
+        let mut app = App::new_with_prompt("explain ownership").with_service(Arc::new(AIService::new().with_provider(provider)));
+
+        assert_eq!(app.pending_prompt.as_deref(), Some("explain ownership"));
+        assert_eq!(app.state, RequestState::Idle);
+
+        app.handle_event(Event::Tick);
+        assert_eq!(app.state, RequestState::Thinking { request_id: 1 });
+        assert_eq!(transcript(&app), vec![(Role::User, "explain ownership")]);
+        assert!(app.pending_prompt.is_none());
+
+        // Later ticks do not send it again
+        app.handle_event(Event::Tick);
+        assert_eq!(app.state, RequestState::Thinking { request_id: 1 });
+
+        let message = next_message(&mut app).await;
+        app.handle_message(message);
+        assert_eq!(app.state, RequestState::Idle);
+        assert_eq!(transcript(&app)[1], (Role::Assistant, "Ownership explained"));
+    }
+
+    fn config_from(toml: &str) -> Config {
+        Config::from_reader(toml.as_bytes(), ConfigFormat::Toml).unwrap()
+    }
+
+    #[test]
+    fn test_apply_config_swaps_service() {
+        let old = Config::default();
+        let mut app = App::new().with_service(Arc::new(AIService::for_chat(&old)));
+        assert!(!app.service.is_available());
+
+        let new = config_from("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\n\n[performance]\ntimeout = 5\n");
+        app.apply_config(&new);
+
+        assert!(app.service.is_available());
+        assert_eq!(app.service.model.as_deref(), Some("llama3"));
+        assert_eq!(app.usage, UsageTracker::new().with_model(Some("llama3".to_string())));
+        assert_eq!(app.request_timeout, Duration::from_secs(5));
+        assert_eq!(app.notice.as_deref(), Some("Configuration reloaded, using llama3"));
+
+        // Reloading back to a config without [ai] disables the service again
+        app.apply_config(&old);
+        assert!(!app.service.is_available());
+        assert_eq!(app.notice.as_deref(), Some("Configuration reloaded"));
+    }
+
+    #[tokio::test]
+    async fn test_model_picker_selection_switches_active_model() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"id": "llama3"}, {"id": "qwen2.5-coder"}, {"id": "mistral"}]
+            })))
+            .mount(&server)
+            .await;
+        let config = config_from(&format!("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\nendpoint = \"{}/v1\"\n", server.uri()));
+        let mut app = App::new().with_service(Arc::new(AIService::for_chat(&config))).with_config(config);
+        assert_eq!(app.active_model(), Some("llama3"));
+
+        app.handle_event(key(KeyCode::F(3)));
+        assert_eq!(app.model_picker, Some(ModelPicker::loading()));
+        let message = next_message(&mut app).await;
+        app.handle_message(message);
+        let picker = app.model_picker.as_ref().unwrap();
+        assert_eq!(picker.selected_model(), Some("llama3"));
+
+        app.handle_event(key(KeyCode::Down));
+        app.handle_event(key(KeyCode::Enter));
+
+        assert_eq!(app.model_picker, None);
+        assert_eq!(app.active_model(), Some("qwen2.5-coder"));
+        assert_eq!(app.usage, UsageTracker::new().with_model(Some("qwen2.5-coder".to_string())));
+        assert_eq!(app.notice.as_deref(), Some("Switched to qwen2.5-coder"));
+    }
+
+    #[test]
+    fn test_model_picker_needs_ai_config() {
+        let mut app = App::new();
+        app.handle_event(key(KeyCode::F(3)));
+
+        assert_eq!(app.model_picker, None);
+        assert_eq!(app.notice.as_deref(), Some("No AI provider is configured"));
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_current_service() {
+        let mut app = App::new().with_config_loader(Box::new(|| {
+            Ok(config_from("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\n\n[performance]\ntimeout = 0\n"))
+        }));
+        let service = Arc::clone(&app.service);
+
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)));
+
+        assert!(Arc::ptr_eq(&app.service, &service));
+        assert_eq!(
+            app.notice.as_deref(),
+            Some("Reload failed: Invalid value '0' for configuration key 'performance.timeout'.")
+        );
+    }
+
+    #[test]
+    fn test_reload_without_loader_reports_notice() {
+        let mut app = App::new();
+        app.reload_config();
+        assert_eq!(app.notice.as_deref(), Some("Configuration cannot be reloaded in this session"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_receives_response() {
+        let provider = Arc::new(RecordingProvider::new("Ownership explained"));
+        let mut app = App::new().with_service(Arc::new(AIService::new().with_provider(provider)));
+
+        type_text(&mut app, "ownership");
+        app.handle_event(key(KeyCode::Enter));
+
+        assert_eq!(app.state, RequestState::Thinking { request_id: 1 });
+        assert_eq!(transcript(&app), vec![(Role::User, "ownership")]);
+        assert!(app.input.is_empty());
+
+        let message = next_message(&mut app).await;
+        app.handle_message(message);
+
+        assert_eq!(app.state, RequestState::Idle);
+        assert_eq!(
+            transcript(&app),
+            vec![(Role::User, "ownership"), (Role::Assistant, "Ownership explained")]
+        );
+        assert_eq!(app.usage.requests(), 1);
+        assert_eq!(app.usage.total(), Usage::estimate("ownership", "Ownership explained"));
+    }
+
+    #[tokio::test]
+    async fn test_follow_up_prompt_sends_earlier_turns() {
+        let provider = Arc::new(RecordingProvider::new("Ownership explained"));
+        let mut app = App::new().with_service(Arc::new(AIService::new().with_provider(provider.clone())));
+
+        type_text(&mut app, "ownership");
+        app.handle_event(key(KeyCode::Enter));
+        let message = next_message(&mut app).await;
+        app.handle_message(message);
+        type_text(&mut app, "and borrowing?");
+        app.handle_event(key(KeyCode::Enter));
+        let message = next_message(&mut app).await;
+        app.handle_message(message);
+
+        assert_eq!(
+            provider.requests()[1].prompt,
+            "Conversation so far:\nUser: ownership\nAssistant: Ownership explained\n\nand borrowing?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modified_enter_continues_multiline_prompt() {
+        let provider = Arc::new(RecordingProvider::new("noted"));
+        let mut app = App::new().with_service(Arc::new(AIService::new().with_provider(provider.clone())));
+
+        type_text(&mut app, "fn main() {");
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT)));
+        type_text(&mut app, "}");
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)));
+        assert_eq!(app.input.as_str(), "fn main() {\n}\n");
+        assert!(app.conversation.is_empty());
+
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.input.is_empty());
+        assert_eq!(transcript(&app), vec![(Role::User, "fn main() {\n}")]);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_moves_thinking_to_error_and_reenables_input() {
+        let mut app = App::new().with_service(Arc::new(AIService::new().with_provider(Arc::new(HangingProvider))));
+
+        type_text(&mut app, "ownership");
+        app.handle_event(key(KeyCode::Enter));
+        assert_eq!(app.state, RequestState::Thinking { request_id: 1 });
+
+        app.handle_message(AppMessage::TimedOut {
+            request_id: 1,
+            timeout: Duration::from_secs(5),
+        });
+
+        let RequestState::Error { message } = &app.state else {
+            panic!("Expected an error state, got {:?}", app.state);
+        };
+        assert!(message.contains("timeout after 5000ms"), "{}", message);
+        assert_eq!(transcript(&app), vec![(Role::User, "ownership"), (Role::Note, message.as_str())]);
+
+        type_text(&mut app, "borrowing");
+        app.handle_event(key(KeyCode::Enter));
+        assert_eq!(app.state, RequestState::Thinking { request_id: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_hung_request_times_out() {
+        let mut app = App::new()
+            .with_service(Arc::new(AIService::new().with_provider(Arc::new(HangingProvider))))
+            .with_request_timeout(Duration::from_millis(10));
+
+        type_text(&mut app, "ownership");
+        app.handle_event(key(KeyCode::Enter));
+
+        let message = next_message(&mut app).await;
+        assert!(matches!(message, AppMessage::TimedOut { request_id: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_escape_cancels_pending_request() {
+        let mut app = App::new().with_service(Arc::new(AIService::new().with_provider(Arc::new(HangingProvider))));
+
+        type_text(&mut app, "ownership");
+        app.handle_event(key(KeyCode::Enter));
+        app.handle_event(key(KeyCode::Esc));
+
+        assert_eq!(app.state, RequestState::Idle);
+        assert_eq!(transcript(&app), vec![(Role::User, "ownership"), (Role::Note, CANCELLED_MESSAGE)]);
+
+        // The cancelled task still reports back, but is ignored
+        let message = next_message(&mut app).await;
+        assert!(matches!(
+            message,
+            AppMessage::Completed { result: Err(GroundhogError::Internal(InternalError::Cancelled { .. })), .. }
+        ));
+        app.handle_message(message);
+        assert_eq!(transcript(&app), vec![(Role::User, "ownership"), (Role::Note, CANCELLED_MESSAGE)]);
+    }
+
+    #[tokio::test]
+    async fn test_queued_deltas_are_coalesced_in_order() {
+        let mut app = App::new();
+        app.state = RequestState::Thinking { request_id: 2 };
+        app.tx.try_send(AppMessage::Delta { request_id: 1, text: "stale".to_string() }).unwrap();
+        for index in 0..10 {
+            app.tx.try_send(AppMessage::Delta { request_id: 2, text: index.to_string() }).unwrap();
+        }
+
+        let mut messages = Vec::new();
+        while let Ok(message) = app.rx.try_recv() {
+            messages.push(message);
+        }
+        let merged = coalesce_deltas(messages);
+        assert_eq!(merged.len(), 2);
+        assert!(matches!(&merged[1], AppMessage::Delta { request_id: 2, text } if text == "0123456789"));
+
+        for message in merged {
+            app.handle_message(message);
+        }
+        assert_eq!(app.partial_reply, "0123456789");
+    }
+
+    #[tokio::test]
+    async fn test_reply_stream_holds_text_back_while_channel_is_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut reply = ReplyStream::new(tx, 1);
+
+        reply.push("a");
+        reply.push("b");
+        reply.push("c");
+        assert!(matches!(rx.try_recv(), Ok(AppMessage::Delta { text, .. }) if text == "a"));
+        assert!(rx.try_recv().is_err());
+
+        reply.push("d");
+        assert!(matches!(rx.try_recv(), Ok(AppMessage::Delta { text, .. }) if text == "bcd"));
+        assert_eq!(reply.text, "abcd");
+    }
+
+    #[test]
+    fn test_export_key_writes_markdown() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new().with_export_dir(dir.path());
+        app.conversation.push_user("ownership");
+        app.conversation.push_assistant("Values have one owner.");
+
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)));
+
+        let path = dir.path().join(format!("groundhog-{}.md", app.conversation.id));
+        let markdown = std::fs::read_to_string(&path).unwrap();
+        assert!(markdown.starts_with("# User\n\nownership\n"));
+        assert!(markdown.contains("# Assistant\n\nValues have one owner.\n"));
+        assert!(app.notice.as_deref().unwrap().starts_with("Exported to"));
+    }
+
+    #[test]
+    fn test_help_popup_toggles_and_swallows_input() {
+        let mut app = App::new();
+
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+        assert!(app.show_help);
+
+        type_text(&mut app, "abc");
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.input.is_empty());
+        assert!(app.conversation.is_empty());
+        assert!(app.show_help);
+
+        app.handle_event(key(KeyCode::Esc));
+        assert!(!app.show_help);
+
+        // With text in the prompt the help key is typed instead
+        type_text(&mut app, "why?");
+        assert_eq!(app.input.as_str(), "why?");
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn test_palette_filters_and_runs_selected_action() {
+        let mut app = App::new();
+        app.conversation.push_user("hello");
+        type_text(&mut app, "draft");
+
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)));
+        assert!(app.palette.is_some());
+
+        // Typing goes to the palette query rather than the prompt
+        type_text(&mut app, "clear");
+        assert_eq!(app.palette.as_ref().unwrap().query, "clear");
+        assert_eq!(app.input.as_str(), "draft");
+
+        app.handle_event(key(KeyCode::Enter));
+        assert!(app.palette.is_none());
+        assert!(app.conversation.is_empty());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_palette_escape_closes_without_running() {
+        let mut app = App::new();
+        app.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)));
+        type_text(&mut app, "quit");
+
+        app.handle_event(key(KeyCode::Esc));
+        assert!(app.palette.is_none());
+        assert!(!app.should_quit);
+    }
+}
@@ -1,5 +1,5 @@
 use std::time::Duration;
-use ratatui::crossterm::event::{self, Event as CrosstermEvent, KeyEvent};
+use ratatui::crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEventKind};
 use tracing::{debug, instrument};
 
 use crate::infrastructure::error::GroundhogError;
@@ -13,8 +13,20 @@ pub enum Event {
     Tick,
     /// Resize event
     Resize(u16, u16),
-    /// Mouse event (future use)
-    Mouse,
+    /// Mouse event carrying the kind of mouse action
+    Mouse(MouseEventKind),
+}
+
+/// Lines scrolled per mouse wheel notch
+pub const SCROLL_STEP: i32 = 3;
+
+/// Convert a mouse event into a scroll delta (negative scrolls up)
+pub fn scroll_delta(kind: MouseEventKind) -> Option<i32> {
+    match kind {
+        MouseEventKind::ScrollUp => Some(-SCROLL_STEP),
+        MouseEventKind::ScrollDown => Some(SCROLL_STEP),
+        _ => None,
+    }
 }
 
 /// Event handler for TUI
@@ -42,9 +54,9 @@ impl EventHandler {
                     debug!("Resize event: {}x{}", width, height);
                     Ok(Event::Resize(width, height))
                 }
-                CrosstermEvent::Mouse(_) => {
-                    debug!("Mouse event");
-                    Ok(Event::Mouse)
+                CrosstermEvent::Mouse(mouse_event) => {
+                    debug!("Mouse event: {:?}", mouse_event.kind);
+                    Ok(Event::Mouse(mouse_event.kind))
                 }
                 _ => Ok(Event::Tick),
             }
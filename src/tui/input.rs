@@ -0,0 +1,125 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Lines the prompt box grows to before it scrolls
+pub const MAX_VISIBLE_LINES: usize = 6;
+
+/// Prompt being typed, which may span several lines
+///
+/// Text is always edited at the end, like the single-line prompt it
+/// replaces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputBuffer {
+    text: String,
+}
+
+impl InputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `key` inserts a newline rather than submitting
+    ///
+    /// Shift+Enter is only reported by terminals with enhanced keyboard
+    /// support, so Alt+Enter works as well.
+    pub fn is_newline_key(key: &KeyEvent) -> bool {
+        key.code == KeyCode::Enter && key.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    /// Delete the last character, joining lines when it is a newline
+    pub fn pop(&mut self) {
+        self.text.pop();
+    }
+
+    pub fn insert_newline(&mut self) {
+        self.text.push('\n');
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+    }
+
+    /// The text to send, trimmed, or `None` when there is nothing but whitespace
+    pub fn prompt(&self) -> Option<&str> {
+        Some(self.text.trim()).filter(|prompt| !prompt.is_empty())
+    }
+
+    /// Number of lines, at least one even when empty
+    pub fn line_count(&self) -> usize {
+        self.text.split('\n').count()
+    }
+
+    /// Lines shown in the prompt box, which grows up to [`MAX_VISIBLE_LINES`]
+    pub fn visible_lines(&self) -> usize {
+        self.line_count().min(MAX_VISIBLE_LINES)
+    }
+
+    /// Lines scrolled out of view so the line being typed stays visible
+    pub fn scroll_offset(&self) -> usize {
+        self.line_count() - self.visible_lines()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(text: &str) -> InputBuffer {
+        let mut buffer = InputBuffer::new();
+        for c in text.chars() {
+            match c {
+                '\n' => buffer.insert_newline(),
+                c => buffer.push(c),
+            }
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_newline_insertion_and_deletion() {
+        let mut input = buffer("fn main() {\n}");
+        assert_eq!(input.as_str(), "fn main() {\n}");
+        assert_eq!(input.line_count(), 2);
+
+        input.pop();
+        input.pop();
+        assert_eq!(input.as_str(), "fn main() {");
+        assert_eq!(input.line_count(), 1);
+    }
+
+    #[test]
+    fn test_only_modified_enter_inserts_newline() {
+        let enter = |modifiers| KeyEvent::new(KeyCode::Enter, modifiers);
+
+        assert!(InputBuffer::is_newline_key(&enter(KeyModifiers::SHIFT)));
+        assert!(InputBuffer::is_newline_key(&enter(KeyModifiers::ALT)));
+        assert!(!InputBuffer::is_newline_key(&enter(KeyModifiers::NONE)));
+        assert!(!InputBuffer::is_newline_key(&KeyEvent::new(KeyCode::Char('j'), KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn test_prompt_ignores_surrounding_blank_lines() {
+        assert_eq!(buffer("\n  explain\n  this\n\n").prompt(), Some("explain\n  this"));
+        assert_eq!(buffer("\n \n").prompt(), None);
+    }
+
+    #[test]
+    fn test_box_grows_then_scrolls() {
+        let input = buffer("a\nb\nc");
+        assert_eq!((input.visible_lines(), input.scroll_offset()), (3, 0));
+
+        let input = buffer(&["line"; MAX_VISIBLE_LINES + 2].join("\n"));
+        assert_eq!((input.visible_lines(), input.scroll_offset()), (MAX_VISIBLE_LINES, 2));
+    }
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::infrastructure::config::KeyBindingsConfig;
+use crate::infrastructure::error::{ConfigError, GroundhogError};
+
+/// A single key combination, e.g. `ctrl-c`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Create a binding for a key without modifiers
+    pub fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// Create a binding for a key with modifiers
+    pub fn with_modifiers(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Parse a key name such as "q", "space", "up", "f2" or "ctrl-c"
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = input.trim();
+
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            name if name.len() > 1 && name.starts_with('f') => {
+                KeyCode::F(name[1..].parse().ok().filter(|n| (1..=12).contains(n))?)
+            }
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        };
+
+        Some(Self { code, modifiers })
+    }
+
+    /// Check whether a key event triggers this binding
+    ///
+    /// Shift is already reflected in the character of a `Char` key, so a
+    /// binding for `?` also matches the Shift+/ event some terminals report.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = match key.code {
+            KeyCode::Char(_) if !self.modifiers.contains(KeyModifiers::SHIFT) => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+        self.code == key.code && self.modifiers == modifiers
+    }
+
+    /// Whether this binding is a plain character that could also be typed into the prompt
+    pub fn is_printable(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && (self.modifiers - KeyModifiers::SHIFT).is_empty()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Key bindings for the TUI actions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    pub quit: KeyBinding,
+    pub submit: KeyBinding,
+    pub clear: KeyBinding,
+    pub cancel: KeyBinding,
+    pub scroll_up: KeyBinding,
+    pub scroll_down: KeyBinding,
+    pub export: KeyBinding,
+    pub help: KeyBinding,
+    pub palette: KeyBinding,
+    pub reload: KeyBinding,
+    pub log_level: KeyBinding,
+    pub model: KeyBinding,
+}
+
+impl KeyBindings {
+    /// Build key bindings from the `[tui.keys]` configuration section
+    pub fn from_config(config: &KeyBindingsConfig) -> Result<Self, GroundhogError> {
+        Ok(Self {
+            quit: parse_config_key("tui.keys.quit", &config.quit)?,
+            submit: parse_config_key("tui.keys.submit", &config.submit)?,
+            clear: parse_config_key("tui.keys.clear", &config.clear)?,
+            cancel: parse_config_key("tui.keys.cancel", &config.cancel)?,
+            scroll_up: parse_config_key("tui.keys.scroll_up", &config.scroll_up)?,
+            scroll_down: parse_config_key("tui.keys.scroll_down", &config.scroll_down)?,
+            export: parse_config_key("tui.keys.export", &config.export)?,
+            help: parse_config_key("tui.keys.help", &config.help)?,
+            palette: parse_config_key("tui.keys.palette", &config.palette)?,
+            reload: parse_config_key("tui.keys.reload", &config.reload)?,
+            log_level: parse_config_key("tui.keys.log_level", &config.log_level)?,
+            model: parse_config_key("tui.keys.model", &config.model)?,
+        })
+    }
+
+    /// Every binding with a short description of its action
+    pub fn entries(&self) -> [(&'static str, KeyBinding); 12] {
+        [
+            ("Send prompt", self.submit),
+            ("Cancel request", self.cancel),
+            ("Clear conversation", self.clear),
+            ("Scroll up", self.scroll_up),
+            ("Scroll down", self.scroll_down),
+            ("Export conversation", self.export),
+            ("Toggle help", self.help),
+            ("Command palette", self.palette),
+            ("Reload configuration", self.reload),
+            ("Cycle log level", self.log_level),
+            ("Switch model", self.model),
+            ("Quit", self.quit),
+        ]
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            quit: KeyBinding::with_modifiers(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            submit: KeyBinding::new(KeyCode::Enter),
+            clear: KeyBinding::with_modifiers(KeyCode::Char('l'), KeyModifiers::CONTROL),
+            cancel: KeyBinding::new(KeyCode::Esc),
+            scroll_up: KeyBinding::new(KeyCode::Up),
+            scroll_down: KeyBinding::new(KeyCode::Down),
+            export: KeyBinding::with_modifiers(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            help: KeyBinding::new(KeyCode::Char('?')),
+            palette: KeyBinding::with_modifiers(KeyCode::Char('p'), KeyModifiers::CONTROL),
+            reload: KeyBinding::with_modifiers(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            log_level: KeyBinding::new(KeyCode::F(2)),
+            model: KeyBinding::new(KeyCode::F(3)),
+        }
+    }
+}
+
+fn parse_config_key(key: &str, value: &str) -> Result<KeyBinding, GroundhogError> {
+    KeyBinding::parse(value).ok_or_else(|| {
+        ConfigError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: "a key name such as 'q', 'space', 'up', 'f2' or 'ctrl-c'".to_string(),
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_names() {
+        assert_eq!(KeyBinding::parse("q"), Some(KeyBinding::new(KeyCode::Char('q'))));
+        assert_eq!(KeyBinding::parse("space"), Some(KeyBinding::new(KeyCode::Char(' '))));
+        assert_eq!(KeyBinding::parse("Down"), Some(KeyBinding::new(KeyCode::Down)));
+        assert_eq!(KeyBinding::parse("f2"), Some(KeyBinding::new(KeyCode::F(2))));
+        assert_eq!(
+            KeyBinding::parse("ctrl-c"),
+            Some(KeyBinding::with_modifiers(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn test_display_key_binding() {
+        assert_eq!(KeyBinding::parse("ctrl-l").unwrap().to_string(), "Ctrl-L");
+        assert_eq!(KeyBinding::parse("esc").unwrap().to_string(), "Esc");
+        assert_eq!(KeyBinding::parse("space").unwrap().to_string(), "Space");
+        assert_eq!(KeyBinding::parse("f3").unwrap().to_string(), "F3");
+    }
+
+    #[test]
+    fn test_shifted_character_matches_plain_binding() {
+        let help = KeyBinding::parse("?").unwrap();
+        assert!(help.matches(&KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE)));
+        assert!(help.matches(&KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+        assert!(!help.matches(&KeyEvent::new(KeyCode::Char('?'), KeyModifiers::CONTROL)));
+
+        let ctrl_c = KeyBinding::parse("ctrl-c").unwrap();
+        assert!(!ctrl_c.matches(&KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL | KeyModifiers::ALT)));
+    }
+
+    #[test]
+    fn test_parse_invalid_key_names() {
+        assert_eq!(KeyBinding::parse(""), None);
+        assert_eq!(KeyBinding::parse("nope"), None);
+        assert_eq!(KeyBinding::parse("f13"), None);
+    }
+
+    #[test]
+    fn test_default_config_matches_default_bindings() {
+        let bindings = KeyBindings::from_config(&KeyBindingsConfig::default()).unwrap();
+        assert_eq!(bindings, KeyBindings::default());
+    }
+
+    #[test]
+    fn test_invalid_config_key_is_rejected() {
+        let config = KeyBindingsConfig {
+            quit: "not-a-key".to_string(),
+            ..KeyBindingsConfig::default()
+        };
+
+        match KeyBindings::from_config(&config) {
+            Err(GroundhogError::Config(ConfigError::InvalidValue { key, .. })) => {
+                assert_eq!(key, "tui.keys.quit");
+            }
+            other => panic!("Expected ConfigError::InvalidValue, got {:?}", other),
+        }
+    }
+}
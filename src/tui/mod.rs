@@ -1,15 +1,14 @@
 pub mod app;
 pub mod ui;
 pub mod event;
+pub mod input;
+pub mod keys;
+pub mod palette;
+pub mod picker;
 
-pub use app::App;
+pub use app::{App, AppMessage, ConfigLoader, DebugStats, RequestState};
 pub use ui::render;
 pub use event::{Event, EventHandler};
-
-use crate::infrastructure::error::GroundhogError;
-
-/// Initialize and run the TUI application
-pub async fn run() -> Result<(), GroundhogError> {
-    let mut app = App::new();
-    app.run().await
-} 
\ No newline at end of file
+pub use input::InputBuffer;
+pub use keys::{KeyBinding, KeyBindings};
+ 
\ No newline at end of file
@@ -0,0 +1,177 @@
+/// Actions that can be run from the command palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAction {
+    Clear,
+    Export,
+    Cancel,
+    Help,
+    Reload,
+    LogLevel,
+    Model,
+    Quit,
+}
+
+impl PaletteAction {
+    /// Every action, in the order shown for an empty query
+    pub const ALL: [PaletteAction; 8] = [
+        PaletteAction::Clear,
+        PaletteAction::Export,
+        PaletteAction::Cancel,
+        PaletteAction::Help,
+        PaletteAction::Reload,
+        PaletteAction::LogLevel,
+        PaletteAction::Model,
+        PaletteAction::Quit,
+    ];
+
+    /// Name shown in the palette and matched against the query
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteAction::Clear => "Clear conversation",
+            PaletteAction::Export => "Export conversation",
+            PaletteAction::Cancel => "Cancel request",
+            PaletteAction::Help => "Toggle help",
+            PaletteAction::Reload => "Reload configuration",
+            PaletteAction::LogLevel => "Cycle log level",
+            PaletteAction::Model => "Switch model",
+            PaletteAction::Quit => "Quit",
+        }
+    }
+}
+
+/// Score `candidate` against `query`, or `None` if the query is not a subsequence of it
+///
+/// Matching ignores case. Characters at the start of a word and runs of
+/// consecutive characters score higher, so "cr" prefers "Cancel request" over
+/// "Clear conversation".
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let index = (position..candidate.len()).find(|&i| candidate[i].eq_ignore_ascii_case(&wanted))?;
+
+        score += 1;
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 2;
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+/// Actions matching `query`, best match first
+///
+/// Equal scores keep the order of `actions`.
+pub fn filter(query: &str, actions: &[PaletteAction]) -> Vec<PaletteAction> {
+    let mut scored: Vec<(u32, PaletteAction)> = actions
+        .iter()
+        .filter_map(|&action| fuzzy_score(query, action.label()).map(|score| (score, action)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, action)| action).collect()
+}
+
+/// State of the open command palette
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Palette {
+    /// Text typed to filter actions
+    pub query: String,
+    /// Index of the highlighted action in [`Palette::matches`]
+    pub selected: usize,
+}
+
+impl Palette {
+    /// Actions matching the current query
+    pub fn matches(&self) -> Vec<PaletteAction> {
+        filter(&self.query, &PaletteAction::ALL)
+    }
+
+    /// The highlighted action, if any action matches
+    pub fn selected_action(&self) -> Option<PaletteAction> {
+        self.matches().get(self.selected).copied()
+    }
+
+    /// Append to the query, highlighting the best match again
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+
+    /// Remove the last query character
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    /// Move the highlight by `delta`, staying within the matches
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.matches().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_requires_subsequence() {
+        assert!(fuzzy_score("xpt", "Export conversation").is_some());
+        assert!(fuzzy_score("EXP", "Export conversation").is_some());
+        assert!(fuzzy_score("tpx", "Export conversation").is_none());
+        assert_eq!(fuzzy_score("", "Quit"), Some(0));
+    }
+
+    #[test]
+    fn test_filter_orders_by_score_then_original_order() {
+        let all = PaletteAction::ALL;
+
+        assert_eq!(filter("", &all), all.to_vec());
+        // Word starts beat letters inside words; ties keep the listed order
+        assert_eq!(
+            filter("c", &all),
+            vec![
+                PaletteAction::Clear,
+                PaletteAction::Export,
+                PaletteAction::Cancel,
+                PaletteAction::Reload,
+                PaletteAction::LogLevel,
+                PaletteAction::Model,
+            ]
+        );
+        assert_eq!(
+            filter("cr", &all),
+            vec![PaletteAction::Cancel, PaletteAction::Clear, PaletteAction::Export, PaletteAction::Reload]
+        );
+        // Consecutive characters beat scattered ones
+        assert_eq!(filter("ca", &all)[0], PaletteAction::Cancel);
+        assert_eq!(filter("exp", &all), vec![PaletteAction::Export]);
+        assert!(filter("zz", &all).is_empty());
+    }
+
+    #[test]
+    fn test_palette_selection_stays_within_matches() {
+        let mut palette = Palette::default();
+        palette.move_selection(-1);
+        assert_eq!(palette.selected_action(), Some(PaletteAction::Clear));
+
+        palette.move_selection(10);
+        assert_eq!(palette.selected_action(), Some(PaletteAction::Quit));
+
+        palette.push('q');
+        assert_eq!(palette.selected, 0);
+        assert_eq!(palette.selected_action(), Some(PaletteAction::Quit));
+
+        palette.push('z');
+        assert_eq!(palette.selected_action(), None);
+    }
+}
@@ -0,0 +1,51 @@
+/// State of the open model picker
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelPicker {
+    /// Models the provider offers, or `None` while they are being listed
+    pub models: Option<Vec<String>>,
+    /// Index of the highlighted model
+    pub selected: usize,
+}
+
+impl ModelPicker {
+    /// A picker waiting for the provider's models
+    pub fn loading() -> Self {
+        Self::default()
+    }
+
+    /// Show `models`, highlighting `active` when it is among them
+    pub fn show(&mut self, models: Vec<String>, active: Option<&str>) {
+        self.selected = active.and_then(|active| models.iter().position(|model| model == active)).unwrap_or(0);
+        self.models = Some(models);
+    }
+
+    /// The highlighted model, once models are listed
+    pub fn selected_model(&self) -> Option<&str> {
+        self.models.as_ref()?.get(self.selected).map(String::as_str)
+    }
+
+    /// Move the highlight by `delta`, staying within the models
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.models.as_ref().map_or(0, Vec::len).saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picker_highlights_active_model_and_stays_within_models() {
+        let mut picker = ModelPicker::loading();
+        assert_eq!(picker.selected_model(), None);
+
+        picker.show(vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()], Some("gpt-4o-mini"));
+        assert_eq!(picker.selected_model(), Some("gpt-4o-mini"));
+
+        picker.move_selection(5);
+        assert_eq!(picker.selected_model(), Some("gpt-4o-mini"));
+        picker.move_selection(-5);
+        assert_eq!(picker.selected_model(), Some("gpt-4o"));
+    }
+}
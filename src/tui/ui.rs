@@ -1,33 +1,210 @@
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Padding, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, Padding, Paragraph, Wrap},
     Frame,
 };
 
-use super::app::App;
+use crate::cli::highlight::{self, Highlighter};
+use crate::core::markdown::code_blocks;
+use crate::core::models::Role;
+
+use super::app::{App, RequestState, THINKING_PLACEHOLDER};
+use super::palette::{Palette, PaletteAction};
+use super::picker::ModelPicker;
+
+/// Narrowest terminal the layout fits in
+pub const MIN_WIDTH: u16 = 40;
+
+/// Shortest terminal the layout fits in: the margin, header, main content,
+/// a one-line prompt and the instructions
+pub const MIN_HEIGHT: u16 = 2 + 3 + 7 + 3 + 3;
+
+/// Whether a terminal area is large enough for the layout
+pub fn fits(area: Rect) -> bool {
+    area.width >= MIN_WIDTH && area.height >= MIN_HEIGHT
+}
 
 /// Render the main UI
+///
+/// A terminal too small for the layout gets a notice instead, until it is
+/// resized.
 pub fn render(frame: &mut Frame, app: &App) {
+    if !fits(frame.area()) {
+        render_too_small(frame);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
             Constraint::Length(3),   // Header
             Constraint::Min(7),      // Main content
+            Constraint::Length(app.input.visible_lines() as u16 + 2),   // Prompt input
             Constraint::Length(3),   // Instructions
         ])
         .split(frame.area());
 
     render_header(frame, chunks[0]);
     render_main_content(frame, app, chunks[1]);
-    render_instructions(frame, chunks[2]);
+    render_input(frame, app, chunks[2]);
+    render_instructions(frame, app, chunks[3]);
+
+    if app.debug_enabled() {
+        render_debug_overlay(frame, app, chunks[1]);
+    }
+
+    if app.show_help {
+        render_help_popup(frame, app);
+    }
+
+    if let Some(palette) = &app.palette {
+        render_palette_popup(frame, palette);
+    }
+
+    if let Some(picker) = &app.model_picker {
+        render_model_picker_popup(frame, picker);
+    }
+}
+
+/// Render a centered notice asking for a larger terminal
+fn render_too_small(frame: &mut Frame) {
+    let area = frame.area();
+    let lines = vec![
+        Line::styled(format!("Terminal too small (need {}x{})", MIN_WIDTH, MIN_HEIGHT), Style::default().fg(Color::Yellow)),
+        Line::styled(format!("Currently {}x{}", area.width, area.height), Style::default().fg(Color::Gray)),
+    ];
+    let height = (lines.len() as u16).min(area.height);
+    let area = Rect {
+        y: area.y + (area.height - height) / 2,
+        height,
+        ..area
+    };
+
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true }), area);
+}
+
+/// Render the command palette as a centered popup with the query and matching actions
+fn render_palette_popup(frame: &mut Frame, palette: &Palette) {
+    let matches = palette.matches();
+    let width = 44.min(frame.area().width);
+    let height = (PaletteAction::ALL.len() as u16 + 4).min(frame.area().height);
+    let area = Rect {
+        x: frame.area().x + (frame.area().width - width) / 2,
+        y: frame.area().y + (frame.area().height - height) / 2,
+        width,
+        height,
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(palette.query.as_str()),
+        ]),
+        Line::from(""),
+    ];
+    if matches.is_empty() {
+        lines.push(Line::styled("No matching actions", Style::default().fg(Color::Gray)));
+    }
+    lines.extend(matches.iter().enumerate().map(|(index, action)| {
+        if index == palette.selected {
+            Line::styled(format!("  {}", action.label()), Style::default().fg(Color::Black).bg(Color::Cyan))
+        } else {
+            Line::raw(format!("  {}", action.label()))
+        }
+    }));
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Command Palette")
+            .title_style(Style::default().fg(Color::Yellow))
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Render the model picker as a centered popup, scrolled to keep the highlighted model visible
+fn render_model_picker_popup(frame: &mut Frame, picker: &ModelPicker) {
+    let width = 44.min(frame.area().width);
+    let height = 16.min(frame.area().height);
+    let area = Rect {
+        x: frame.area().x + (frame.area().width - width) / 2,
+        y: frame.area().y + (frame.area().height - height) / 2,
+        width,
+        height,
+    };
+
+    let lines: Vec<Line> = match &picker.models {
+        None => vec![Line::styled("Listing models...", Style::default().fg(Color::Gray))],
+        Some(models) => models
+            .iter()
+            .enumerate()
+            .map(|(index, model)| {
+                if index == picker.selected {
+                    Line::styled(format!("  {}", model), Style::default().fg(Color::Black).bg(Color::Cyan))
+                } else {
+                    Line::raw(format!("  {}", model))
+                }
+            })
+            .collect(),
+    };
+    // Two rows go to the border
+    let rows = height.saturating_sub(2) as usize;
+    let scroll = (picker.selected + 1).saturating_sub(rows) as u16;
+
+    let popup = Paragraph::new(lines).scroll((scroll, 0)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Switch Model")
+            .title_style(Style::default().fg(Color::Yellow))
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Render the key binding help as a centered popup
+fn render_help_popup(frame: &mut Frame, app: &App) {
+    let entries = app.key_bindings.entries();
+    let width = 44.min(frame.area().width);
+    let height = (entries.len() as u16 + 4).min(frame.area().height);
+    let area = Rect {
+        x: frame.area().x + (frame.area().width - width) / 2,
+        y: frame.area().y + (frame.area().height - height) / 2,
+        width,
+        height,
+    };
+
+    let mut lines: Vec<Line> = entries
+        .iter()
+        .map(|(action, binding)| {
+            Line::from(vec![
+                Span::styled(format!("{:>12}  ", binding.to_string()), Style::default().fg(Color::Cyan)),
+                Span::raw(*action),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled("Press Esc to close", Style::default().fg(Color::Gray)));
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Help")
+            .title_style(Style::default().fg(Color::Yellow))
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
 }
 
 /// Render the header section
 fn render_header(frame: &mut Frame, area: ratatui::layout::Rect) {
-    let header = Paragraph::new("🐹 Groundhog TUI - Hello World Demo")
+    let header = Paragraph::new("🐹 Groundhog TUI")
         .style(Style::default().fg(Color::Yellow))
         .alignment(Alignment::Center)
         .block(
@@ -44,8 +221,8 @@ fn render_main_content(frame: &mut Frame, app: &App, area: ratatui::layout::Rect
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Percentage(60),  // Message area
-            Constraint::Percentage(40),  // Counter and status
+            Constraint::Percentage(70),  // Conversation
+            Constraint::Percentage(30),  // Status
         ])
         .split(area);
 
@@ -53,93 +230,107 @@ fn render_main_content(frame: &mut Frame, app: &App, area: ratatui::layout::Rect
     render_status_area(frame, app, main_chunks[1]);
 }
 
-/// Render the message display area
+/// Render the conversation history
 fn render_message_area(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let message_lines = vec![
-        Line::from(vec![
-            Span::styled("Message: ", Style::default().fg(Color::Green)),
-            Span::raw(&app.message),
-        ]),
-        Line::from(""),
-        Line::from("This is a basic ratatui demonstration."),
-        Line::from("Press Space to increment the counter."),
-        Line::from("Press 'r' to reset the counter."),
-        Line::from("Press 'q' to quit the application."),
-    ];
+    let mut message_lines = Vec::new();
+
+    if app.conversation.is_empty() && app.state == RequestState::Idle {
+        message_lines.push(Line::from(Span::styled(app.message.as_str(), Style::default().fg(Color::Green))));
+        message_lines.push(Line::from(""));
+        message_lines.push(Line::from("Type a prompt below and press Enter to send it."));
+    }
+
+    for message in &app.conversation.messages {
+        let (prefix, style) = role_style(message.role);
+        match message.role {
+            Role::Assistant => message_lines.extend(highlight_code_blocks(&message.content, &app.highlighter)),
+            _ => message_lines.extend(
+                message
+                    .content
+                    .lines()
+                    .map(|line| Line::styled(format!("{}{}", prefix, line), style)),
+            ),
+        }
+        message_lines.push(Line::from(""));
+    }
+
+    if let RequestState::Thinking { .. } = app.state {
+        match app.partial_reply.is_empty() {
+            true => message_lines.push(Line::styled(THINKING_PLACEHOLDER, Style::default().fg(Color::DarkGray))),
+            false => message_lines.extend(highlight_code_blocks(&app.partial_reply, &app.highlighter)),
+        }
+    }
 
     let message = Paragraph::new(message_lines)
         .style(Style::default().fg(Color::White))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Message Display")
+                .title("Conversation")
                 .title_style(Style::default().fg(Color::Magenta))
-                .padding(Padding::uniform(1))
+                .padding(Padding::horizontal(1))
         )
-        .wrap(Wrap { trim: true });
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
 
     frame.render_widget(message, area);
 }
 
-/// Render the status and counter area
-fn render_status_area(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let status_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(5),   // Counter display
-            Constraint::Length(3),   // Progress gauge
-            Constraint::Min(3),      // Status info
-        ])
-        .split(area);
-
-    render_counter_display(frame, app, status_chunks[0]);
-    render_progress_gauge(frame, app, status_chunks[1]);
-    render_status_info(frame, status_chunks[2]);
+/// Line prefix and style used for messages from each role
+pub fn role_style(role: Role) -> (&'static str, Style) {
+    match role {
+        Role::User => ("> ", Style::default().fg(Color::Green)),
+        Role::Assistant => ("", Style::default()),
+        Role::System => ("# ", Style::default().fg(Color::Cyan)),
+        Role::Note => ("! ", Style::default().fg(Color::Yellow)),
+    }
 }
 
-/// Render the counter display
-fn render_counter_display(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let counter_text = vec![
-        Line::from(vec![
-            Span::styled("Count: ", Style::default().fg(Color::Blue)),
-            Span::styled(
-                format!("{}", app.counter),
-                Style::default().fg(Color::Yellow)
-            ),
-        ]),
-    ];
+/// Split text into lines, styling fenced code blocks distinctly from prose
+///
+/// Code is colored by `highlighter` when it has a theme; otherwise, like the
+/// fences, it is shown in a single color.
+pub fn highlight_code_blocks<'a>(text: &'a str, highlighter: &Highlighter) -> Vec<Line<'a>> {
+    let blocks = code_blocks(text);
+    let code_style = Style::default().fg(Color::Cyan);
 
-    let counter = Paragraph::new(counter_text)
-        .style(Style::default())
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Counter")
-                .title_style(Style::default().fg(Color::Red))
-        );
-
-    frame.render_widget(counter, area);
-}
-
-/// Render a progress gauge based on counter
-fn render_progress_gauge(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let progress = (app.counter % 100) as f64 / 100.0;
-    let gauge = Gauge::default()
-        .block(Block::default().borders(Borders::ALL).title("Progress"))
-        .gauge_style(Style::default().fg(Color::Green))
-        .ratio(progress);
-
-    frame.render_widget(gauge, area);
+    text.lines()
+        .zip(highlighter.highlight_lines(text))
+        .enumerate()
+        .map(|(index, (line, ranges))| match ranges {
+            Some(ranges) => Line::from(
+                ranges
+                    .into_iter()
+                    .map(|(style, text)| Span::styled(text, highlight::ratatui_style(style)))
+                    .collect::<Vec<_>>(),
+            ),
+            None if blocks.iter().any(|block| block.contains_line(index)) => Line::styled(line, code_style),
+            None => Line::raw(line),
+        })
+        .collect()
 }
 
-/// Render status information
-fn render_status_info(frame: &mut Frame, area: ratatui::layout::Rect) {
-    let status_items = vec![
-        ListItem::new("✓ TUI Active"),
-        ListItem::new("✓ Input Handling"),
-        ListItem::new("✓ Real-time Updates"),
+/// Render the status area
+fn render_status_area(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let state = match &app.state {
+        RequestState::Idle => ListItem::new("● Idle").style(Style::default().fg(Color::Green)),
+        RequestState::Thinking { .. } => ListItem::new("◌ Thinking...").style(Style::default().fg(Color::Yellow)),
+        RequestState::Error { .. } => ListItem::new("✗ Error").style(Style::default().fg(Color::Red)),
+    };
+    let mut status_items = vec![
+        state,
+        ListItem::new(format!("Messages: {}", app.conversation.len())),
     ];
+    if let Some(model) = app.active_model() {
+        status_items.push(ListItem::new(format!("Model: {}", model)));
+    }
+    if app.usage.requests() > 0 {
+        let cost = app.usage.estimated_cost().map(|cost| format!(" (~${:.4})", cost)).unwrap_or_default();
+        status_items.push(ListItem::new(format!("Tokens: {}{}", app.usage.total_tokens(), cost)));
+    }
+    if let Some(notice) = &app.notice {
+        status_items.push(ListItem::new(notice.as_str()).style(Style::default().fg(Color::Cyan)));
+    }
 
     let status_list = List::new(status_items)
         .block(
@@ -153,9 +344,29 @@ fn render_status_info(frame: &mut Frame, area: ratatui::layout::Rect) {
     frame.render_widget(status_list, area);
 }
 
+/// Render the prompt input box
+fn render_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let input = Paragraph::new(app.input.as_str())
+        .style(Style::default().fg(Color::White))
+        .scroll((app.input.scroll_offset() as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Prompt")
+                .title_style(Style::default().fg(Color::Cyan))
+        );
+
+    frame.render_widget(input, area);
+}
+
 /// Render instructions footer
-fn render_instructions(frame: &mut Frame, area: ratatui::layout::Rect) {
-    let instructions = Paragraph::new("Controls: [Space] Increment | [R] Reset | [Q] Quit")
+fn render_instructions(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let keys = &app.key_bindings;
+    let text = format!(
+        "Controls: [{}] Send | [Shift-Enter] Newline | [{}] Cancel | [{}] Clear | [{}] Export | [{}] Help | [{}] Quit",
+        keys.submit, keys.cancel, keys.clear, keys.export, keys.help, keys.quit
+    );
+    let instructions = Paragraph::new(text)
         .style(Style::default().fg(Color::Gray))
         .alignment(Alignment::Center)
         .block(
@@ -165,4 +376,127 @@ fn render_instructions(frame: &mut Frame, area: ratatui::layout::Rect) {
         );
 
     frame.render_widget(instructions, area);
-} 
\ No newline at end of file
+}
+
+/// Render the debug overlay over the lower half of the main content area
+fn render_debug_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let height = (area.height / 2).max(4).min(area.height);
+    let area = Rect {
+        y: area.y + area.height - height,
+        height,
+        ..area
+    };
+
+    let stats = &app.debug_stats;
+    let mut lines = vec![Line::styled(
+        format!(
+            "{:.1} fps ({} ms/frame) | frames: {} | keys: {} | mouse: {} | resize: {} | ticks: {}",
+            stats.fps(),
+            stats.frame_time.as_millis(),
+            stats.frames,
+            stats.key_events,
+            stats.mouse_events,
+            stats.resize_events,
+            stats.ticks
+        ),
+        Style::default().fg(Color::Yellow),
+    )];
+
+    // Two rows go to the border and one to the statistics line
+    let log_rows = area.height.saturating_sub(3) as usize;
+    if let Some(logs) = &app.debug_logs {
+        lines.extend(logs.recent(log_rows).into_iter().map(Line::raw));
+    }
+
+    let overlay = Paragraph::new(lines)
+        .style(Style::default().fg(Color::Gray))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Debug")
+                .title_style(Style::default().fg(Color::Red))
+        );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(overlay, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use crate::infrastructure::log_buffer::LogBuffer;
+
+    #[test]
+    fn test_highlight_code_blocks_styles_only_code() {
+        let lines = highlight_code_blocks("prose\n```rust\nlet x = 1;\n```\nmore prose", &Highlighter::plain());
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].style, Style::default());
+        assert_eq!(lines[2].style.fg, Some(Color::Cyan));
+        assert_eq!(lines[3].style.fg, Some(Color::Cyan));
+        assert_eq!(lines[4].style, Style::default());
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_with_theme_colors_code_spans() {
+        let highlighter = Highlighter::new(highlight::DEFAULT_THEME);
+        let lines = highlight_code_blocks("```rust\nlet x = 1;\n```", &highlighter);
+
+        assert_eq!(lines[0].style.fg, Some(Color::Cyan));
+        assert!(lines[1].spans.len() > 1);
+        assert!(lines[1].spans.iter().all(|span| matches!(span.style.fg, Some(Color::Rgb(..)))));
+        assert_eq!(lines[1].spans.iter().map(|span| span.content.as_ref()).collect::<String>(), "let x = 1;");
+    }
+
+    #[test]
+    fn test_role_style_distinguishes_roles() {
+        assert_eq!(role_style(Role::User), ("> ", Style::default().fg(Color::Green)));
+        assert_eq!(role_style(Role::Assistant), ("", Style::default()));
+        assert_eq!(role_style(Role::System), ("# ", Style::default().fg(Color::Cyan)));
+        assert_eq!(role_style(Role::Note), ("! ", Style::default().fg(Color::Yellow)));
+    }
+
+    #[test]
+    fn test_fits_at_minimum_size_boundaries() {
+        let area = |width, height| Rect::new(0, 0, width, height);
+
+        assert!(fits(area(MIN_WIDTH, MIN_HEIGHT)));
+        assert!(fits(area(200, 60)));
+        assert!(!fits(area(MIN_WIDTH - 1, MIN_HEIGHT)));
+        assert!(!fits(area(MIN_WIDTH, MIN_HEIGHT - 1)));
+        assert!(!fits(area(0, 0)));
+    }
+
+    #[test]
+    fn test_small_terminal_shows_notice_until_resized() {
+        let app = App::new();
+        let screen = |terminal: &Terminal<TestBackend>| -> String {
+            terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(MIN_WIDTH, MIN_HEIGHT - 1)).unwrap();
+        terminal.draw(|f| render(f, &app)).unwrap();
+        assert!(screen(&terminal).contains(&format!("Terminal too small (need {}x{})", MIN_WIDTH, MIN_HEIGHT)));
+
+        terminal.backend_mut().resize(MIN_WIDTH, MIN_HEIGHT);
+        terminal.draw(|f| render(f, &app)).unwrap();
+        assert!(!screen(&terminal).contains("too small"));
+        assert!(screen(&terminal).contains("Prompt"));
+    }
+
+    #[test]
+    fn test_debug_overlay_shows_recent_logs() {
+        let logs = LogBuffer::new(10);
+        logs.push(" INFO tui::app: overlay line");
+        let app = App::new().with_debug(logs);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| render(f, &app)).unwrap();
+
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Debug"));
+        assert!(screen.contains("overlay line"));
+    }
+}
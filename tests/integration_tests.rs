@@ -131,6 +131,20 @@ fn test_missing_subcommand() {
         .stderr(predicate::str::contains("error:"));
 }
 
+/// Test that a configured default command runs without a subcommand
+#[test]
+fn test_missing_subcommand_runs_configured_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "[commands]\ndefault = \"explain\"\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("hello world\n");
+}
+
 /// Test explain command help
 #[test]
 fn test_explain_help() {
@@ -141,6 +155,18 @@ fn test_explain_help() {
         .stdout(predicate::str::contains("Provides explanations and demonstrations"));
 }
 
+/// Test that subcommand help includes the long description
+#[test]
+fn test_explain_help_shows_long_description() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-color", "explain", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("explains each file, using the topic"))
+        .stdout(predicate::str::contains("a placeholder"))
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
 /// Test multiple verbosity flags
 #[test]
 fn test_multiple_verbosity_flags() {
@@ -278,4 +304,1031 @@ fn test_memory_usage() {
             .success()
             .stdout("hello world\n");
     }
-} 
\ No newline at end of file
+} 
+
+/// Test config paths lists GROUNDHOG_CONFIG as the active source
+#[test]
+fn test_config_paths_with_env_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("env_config.toml");
+    fs::write(&config_path, "[performance]\ntimeout = 60\n").unwrap();
+    
+    // Run from an empty directory and home so no stray config file is found
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.env("GROUNDHOG_CONFIG", config_path.to_str().unwrap())
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path())
+        .current_dir(temp_dir.path())
+        .args(["config", "paths"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    
+    let stdout = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], format!("1. {} [active]", config_path.display()));
+    assert_eq!(lines[1], "2. ./groundhog.toml [missing]");
+}
+
+/// Test explaining multiple files prints results in input order
+#[test]
+fn test_explain_multiple_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("a.rs");
+    let second = temp_dir.path().join("b.rs");
+    fs::write(&first, "fn a() {}").unwrap();
+    fs::write(&second, "fn b() {}").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["explain", "--file", first.to_str().unwrap(), "--file", second.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(format!(
+            "==> {0} <==\nAI service is disabled. File: {0}\n==> {1} <==\nAI service is disabled. File: {1}\n",
+            first.display(),
+            second.display()
+        ));
+}
+
+/// Test markdown output wraps the explanation with a heading
+#[test]
+fn test_explain_markdown_output() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--output", "markdown", "explain", "--topic", "rust"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("# rust\n"))
+        .stdout(predicate::str::contains("hello world - explaining: rust"));
+}
+
+/// Test config diff prints only the customized settings
+#[test]
+fn test_config_diff_shows_changed_settings() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("diff.toml");
+    fs::write(&config_path, "[logging]\nlevel = \"Debug\"\n\n[performance]\nthreads = 16\n").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "config", "diff"])
+        .assert()
+        .success()
+        .stdout("logging.level = Debug (default: Info)\nperformance.threads = 16 (default: 4)\n");
+}
+
+/// Test config validate lists every invalid setting, which would stop other commands at startup
+#[test]
+fn test_config_validate_reports_all_problems() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("invalid.toml");
+    fs::write(&config_path, "[performance]\ntimeout = 0\nthreads = 0\n").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "config", "validate"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Found 2 configuration problems:"))
+        .stderr(predicate::str::contains("'performance.timeout'"))
+        .stderr(predicate::str::contains("'performance.threads'"));
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "config", "validate"])
+        .assert()
+        .success()
+        .stdout("Configuration is valid\n");
+}
+
+/// Test --report writes the error and configuration of a failed command without the API key
+#[test]
+fn test_report_written_on_failure_without_api_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(&config_path, "[ai]\nprovider = \"OpenAI\"\napi_key = \"sk-integration-secret\"\n").unwrap();
+    let report_path = temp_dir.path().join("report.md");
+    let missing = temp_dir.path().join("missing.rs");
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "--report", report_path.to_str().unwrap()])
+        .args(["explain", "--file", missing.to_str().unwrap()])
+        .assert()
+        .code(66)
+        .stderr(predicate::str::contains("Bug report written to"));
+
+    let report = fs::read_to_string(&report_path).unwrap();
+    assert!(report.contains("Kind: filesystem (not_found)"), "{}", report);
+    assert!(report.contains("\"provider\": \"OpenAI\""), "{}", report);
+    assert!(!report.contains("sk-integration-secret"), "{}", report);
+
+    // Nothing is written when the command succeeds
+    let success_report = temp_dir.path().join("success.md");
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "--report", success_report.to_str().unwrap(), "explain", "--topic", "rust"])
+        .assert()
+        .success();
+    assert!(!success_report.exists());
+}
+
+/// Test config schema describes the configuration file, including nested sections
+#[test]
+fn test_config_schema_describes_settings() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--no-config", "config", "schema"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let schema: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    // Sections are references into `$defs`
+    let performance = &schema["properties"]["performance"]["$ref"];
+    let name = performance.as_str().unwrap().trim_start_matches("#/$defs/");
+    let threads = &schema["$defs"][name]["properties"]["threads"];
+    assert_eq!(threads["type"], "integer");
+    assert_eq!(threads["default"], 4);
+}
+
+/// Test config show --effective masks the API key and reflects file and flag overrides
+#[test]
+fn test_config_show_effective_redacts_api_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(&config_path, "[ai]\nprovider = \"OpenAI\"\nmodel = \"gpt-4o-mini\"\napi_key = \"sk-show-secret\"\n\n[performance]\nthreads = 8\n").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "--no-color", "config", "show", "--effective"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("api_key = \"***\""))
+        .stdout(predicate::str::contains("threads = 8"))
+        .stdout(predicate::str::contains("color = false"))
+        .stdout(predicate::str::contains("sk-show-secret").not());
+}
+
+/// Test explain --out writes rendered output to a new file
+#[test]
+fn test_explain_out_writes_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let out_path = temp_dir.path().join("docs").join("explanation.md");
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--output", "markdown", "explain", "--topic", "rust", "--out", out_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("");
+    
+    let content = fs::read_to_string(&out_path).unwrap();
+    assert!(content.starts_with("# rust\n"));
+    assert!(content.contains("hello world - explaining: rust"));
+}
+
+/// Test explain --out refuses to overwrite an existing file
+#[test]
+fn test_explain_out_refuses_overwrite() {
+    let temp_dir = TempDir::new().unwrap();
+    let out_path = temp_dir.path().join("explanation.md");
+    fs::write(&out_path, "keep me").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["explain", "--topic", "rust", "--out", out_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"))
+        .stderr(predicate::str::contains("--force"));
+    
+    assert_eq!(fs::read_to_string(&out_path).unwrap(), "keep me");
+}
+
+/// Test explain --out --force replaces an existing file
+#[test]
+fn test_explain_out_force_overwrites() {
+    let temp_dir = TempDir::new().unwrap();
+    let out_path = temp_dir.path().join("explanation.txt");
+    fs::write(&out_path, "old").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["explain", "--topic", "rust", "--out", out_path.to_str().unwrap(), "--force"])
+        .assert()
+        .success();
+    
+    assert_eq!(fs::read_to_string(&out_path).unwrap(), "hello world - explaining: rust\n");
+}
+
+/// Test explain --append adds timestamped entries to a session file
+#[test]
+fn test_explain_append_twice() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("session.md");
+    
+    for topic in ["rust", "tokio"] {
+        let mut cmd = Command::cargo_bin("groundhog").unwrap();
+        cmd.args(["--no-config", "explain", "--topic", topic, "--append", log_path.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout("");
+    }
+    
+    let content = fs::read_to_string(&log_path).unwrap();
+    let first = content.find("hello world - explaining: rust").unwrap();
+    let second = content.find("hello world - explaining: tokio").unwrap();
+    assert!(first < second);
+    assert_eq!(content.lines().filter(|line| line.starts_with("--- ") && line.ends_with(" ---")).count(), 2);
+    assert!(content.contains("rust\n\n--- "));
+}
+
+/// Test ping fails clearly when no AI provider is configured
+#[test]
+fn test_ping_without_ai_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("empty.toml");
+    fs::write(&config_path, "").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "ping"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'ai'"));
+}
+
+/// Test doctor prints every check and fails when the API key is missing
+#[test]
+fn test_doctor_reports_missing_api_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(&config_path, "[ai]\nprovider = \"OpenAI\"\nmodel = \"gpt-4o\"\n").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "doctor"])
+        .env_remove("OPENAI_API_KEY")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("[pass] Configuration: Loaded"))
+        .stdout(predicate::str::contains("[FAIL] API key: Missing required configuration key 'ai.api_key (or OPENAI_API_KEY)'"))
+        .stdout(predicate::str::contains("[warn] Provider: Skipped until an API key is set"))
+        .stdout(predicate::str::contains("[warn] Terminal:"));
+}
+
+/// Test config and cache inspection commands work without the API key explain would need
+#[test]
+fn test_inspection_commands_work_without_api_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(&config_path, "[ai]\nprovider = \"OpenAI\"\nmodel = \"gpt-4o\"\n").unwrap();
+    
+    for args in [["config", "paths"], ["config", "show"], ["cache", "stats"]] {
+        let mut cmd = Command::cargo_bin("groundhog").unwrap();
+        cmd.args(["--config", config_path.to_str().unwrap()])
+            .args(args)
+            .env_remove("OPENAI_API_KEY")
+            .assert()
+            .success();
+    }
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "ping"])
+        .env_remove("OPENAI_API_KEY")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("OPENAI_API_KEY"));
+}
+
+/// Test doctor --output json reports every check and still fails
+#[test]
+fn test_doctor_json_output_lists_checks() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(&config_path, "[ai]\nprovider = \"OpenAI\"\nmodel = \"gpt-4o\"\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--config", config_path.to_str().unwrap(), "--output", "json", "doctor"])
+        .env_remove("OPENAI_API_KEY")
+        .assert()
+        .failure()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["success"], false);
+    assert_eq!(report["error"]["code"], "missing_key");
+    let checks = report["data"]["checks"].as_array().unwrap();
+    let api_key = checks.iter().find(|check| check["name"] == "API key").unwrap();
+    assert_eq!(api_key["status"], "fail");
+    assert!(api_key["detail"].as_str().unwrap().contains("OPENAI_API_KEY"));
+    assert!(checks.iter().all(|check| check["status"].is_string()));
+}
+
+/// Test repl answers piped prompts until :quit
+#[test]
+fn test_repl_answers_piped_prompts() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "repl"])
+        .write_stdin("closures\nownership\n:quit\nlifetimes\n")
+        .assert()
+        .success()
+        .stdout("AI service is disabled. Topic: closures\nAI service is disabled. Topic: ownership\n");
+}
+
+/// Test --output json reports a successful command result with data
+#[test]
+fn test_json_output_success() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--output", "json", "explain", "--topic", "rust"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(result["success"], true);
+    assert!(result["duration_ms"].is_u64());
+    assert_eq!(result["data"][0]["title"], "rust");
+    assert!(result.get("error").is_none());
+}
+
+/// Test --output jsonl streams delta lines followed by a final done line
+#[test]
+fn test_jsonl_output_streams_deltas() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--no-config", "--output", "jsonl", "explain", "--topic", "rust"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let (last, deltas) = lines.split_last().unwrap();
+    assert!(!deltas.is_empty());
+    assert!(deltas.iter().all(|line| line["delta"].is_string()));
+    assert_eq!(last["done"], true);
+    assert!(last["usage"]["completion_tokens"].is_u64());
+}
+
+/// Test that an out-of-range --temperature is rejected
+#[test]
+fn test_explain_rejects_invalid_temperature() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--temperature", "3", "--topic", "rust"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("ai.params.temperature"));
+}
+
+/// Write a config whose metrics log lives in `dir`
+fn metrics_config(dir: &TempDir, enabled: bool) -> (std::path::PathBuf, std::path::PathBuf) {
+    let config_path = dir.path().join("config.toml");
+    let metrics_path = dir.path().join("metrics").join("metrics.jsonl");
+    let content = format!(
+        "[performance.metrics]\nenabled = {}\npath = {:?}\n",
+        enabled,
+        metrics_path.to_str().unwrap()
+    );
+    fs::write(&config_path, content).unwrap();
+    (config_path, metrics_path)
+}
+
+/// Test that each command appends one line to the metrics log
+#[test]
+fn test_metrics_log_appends_one_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let (config_path, metrics_path) = metrics_config(&temp_dir, true);
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "explain"])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&metrics_path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 1);
+
+    let metric: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(metric["command"], "explain");
+    assert_eq!(metric["success"], true);
+    assert!(metric["duration_ms"].is_u64());
+    assert!(metric["timestamp"].is_u64());
+}
+
+/// Test that a disabled metrics log writes nothing
+#[test]
+fn test_metrics_log_disabled_writes_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let (config_path, metrics_path) = metrics_config(&temp_dir, false);
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "explain"])
+        .assert()
+        .success();
+
+    assert!(!metrics_path.exists());
+}
+
+/// Test --max-output-lines truncates printed output but not --out files
+#[test]
+fn test_max_output_lines_truncates_stdout_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("a.rs");
+    let second = temp_dir.path().join("b.rs");
+    fs::write(&first, "fn a() {}\n").unwrap();
+    fs::write(&second, "fn b() {}\n").unwrap();
+    let files = ["--file", first.to_str().unwrap(), "--file", second.to_str().unwrap()];
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--no-config", "--max-output-lines", "1", "explain"])
+        .args(files)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.ends_with("... (truncated, 3 more lines)\n"));
+
+    let out_path = temp_dir.path().join("full.txt");
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "--max-output-lines", "1", "explain", "--out", out_path.to_str().unwrap()])
+        .args(files)
+        .assert()
+        .success();
+    let full = fs::read_to_string(&out_path).unwrap();
+    assert_eq!(full.lines().count(), 4);
+    assert!(!full.contains("truncated"));
+}
+
+/// Test --output json reports failures with error details
+#[test]
+fn test_json_output_failure() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--output", "json", "explain", "--file", "/nonexistent/input.rs"])
+        .assert()
+        .failure()
+        .code(66)
+        .get_output()
+        .stdout
+        .clone();
+    
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(result["success"], false);
+    assert!(result["message"].as_str().unwrap().contains("File not found"));
+    assert!(result["duration_ms"].is_u64());
+    assert_eq!(result["error"]["kind"], "filesystem");
+    assert_eq!(result["error"]["code"], "not_found");
+    assert_eq!(result["error"]["exit_code"], 66);
+}
+
+/// Test explain refuses input files above performance.max_file_size
+#[test]
+fn test_explain_file_too_large() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    let input_path = temp_dir.path().join("big.txt");
+    fs::write(&config_path, "[performance]\nmax_file_size = 1\n").unwrap();
+    fs::write(&input_path, vec![b'a'; 1024 * 1024 + 1]).unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "explain", "--file", input_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("1048577 bytes, limit 1048576 bytes"));
+}
+
+/// Test explain reports binary input files as not being UTF-8 text
+#[test]
+fn test_explain_binary_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("data.bin");
+    fs::write(&input_path, [0x00, 0xff, 0xfe, 0x80]).unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--file", input_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains("is not UTF-8 text"))
+        .stderr(predicate::str::contains("binary file"));
+}
+
+/// Test explain rejects a --json-schema file that is not valid JSON
+#[test]
+fn test_explain_malformed_json_schema() {
+    let temp_dir = TempDir::new().unwrap();
+    let schema_path = temp_dir.path().join("schema.json");
+    fs::write(&schema_path, "{ not json").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--topic", "rust", "--json-schema", schema_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("schema.json' is malformed near line 1"));
+}
+
+/// Test variables from --env-file are set before configuration is resolved
+#[test]
+fn test_env_file_sets_config_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    let env_path = temp_dir.path().join(".env");
+    fs::write(&config_path, "[commands]\ndefault = \"explain --topic dotenv\"\n").unwrap();
+    fs::write(&env_path, format!("GROUNDHOG_CONFIG={}\n", config_path.display())).unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.env_remove("GROUNDHOG_CONFIG")
+        .args(["--env-file", env_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("hello world - explaining: dotenv\n");
+}
+
+/// Test --silent prints only the result
+#[test]
+fn test_silent_explain_prints_only_result() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "--silent", "-vv", "explain", "--topic", "quiet"])
+        .assert()
+        .success()
+        .stdout("hello world - explaining: quiet\n")
+        .stderr("");
+}
+
+/// Test --quiet still allows explain output
+#[test]
+fn test_quiet_explain_prints_result() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "--quiet", "explain", "--topic", "quiet"])
+        .assert()
+        .success()
+        .stdout("hello world - explaining: quiet\n")
+        .stderr("");
+}
+
+/// Test `--file -` explains standard input
+#[test]
+fn test_explain_file_from_stdin() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--file", "-"])
+        .write_stdin("fn main() {}\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("File: -"));
+}
+
+/// Test --output json works for non-explain commands
+#[test]
+fn test_json_output_config_paths() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--output", "json", "config", "paths"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(result["success"], true);
+    assert!(result["data"].as_array().unwrap().iter().any(|s| s["path"] == "./groundhog.toml"));
+}
+
+/// Test --trace-file records command spans in Chrome trace format
+#[test]
+fn test_trace_file_contains_command_span() {
+    let temp_dir = TempDir::new().unwrap();
+    let trace_path = temp_dir.path().join("trace.json");
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--trace-file", trace_path.to_str().unwrap(), "explain", "--topic", "rust"])
+        .assert()
+        .success();
+    
+    let trace: serde_json::Value = serde_json::from_str(&fs::read_to_string(&trace_path).unwrap()).unwrap();
+    let events = trace["traceEvents"].as_array().unwrap();
+    let command_span = events.iter().find(|e| e["name"] == "command.execute").unwrap();
+    assert_eq!(command_span["ph"], "X");
+    assert!(events.iter().any(|e| e["name"] == "command.explain"));
+}
+
+/// Test --no-config ignores an invalid config file in the current directory
+#[test]
+fn test_no_config_ignores_invalid_config() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("groundhog.toml"), "invalid toml content [[[").unwrap();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env_remove("GROUNDHOG_CONFIG")
+        .args(["explain"])
+        .assert()
+        .failure();
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env_remove("GROUNDHOG_CONFIG")
+        .args(["--no-config", "explain"])
+        .assert()
+        .success()
+        .stdout("hello world\n");
+}
+
+/// Test a profile cannot be applied when --no-config skips the files defining it
+#[test]
+fn test_no_config_rejects_profile() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "--profile", "nonexistent", "config", "show"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.env("GROUNDHOG_PROFILE", "nonexistent")
+        .args(["--no-config", "config", "show"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Ignoring GROUNDHOG_PROFILE"));
+}
+
+/// Test --config - reads the configuration from stdin
+#[test]
+fn test_config_from_stdin() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", "-", "explain"])
+        .write_stdin("[output]\nformat = \"json\"\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"success\": true"));
+}
+
+/// Test providers list prints the models served by the configured endpoint
+#[tokio::test(flavor = "multi_thread")]
+async fn test_providers_list_prints_models() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "id": "llama3" }, { "id": "mistral" }]
+        })))
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(
+        &config_path,
+        format!("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\nendpoint = \"{}/v1\"\n", server.uri()),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "providers", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OpenAI"))
+        .stdout(predicate::str::contains("* Local"))
+        .stdout(predicate::str::contains("  llama3\n  mistral\n"));
+}
+
+/// Test cached explain responses are keyed by model and counted by cache stats
+#[tokio::test(flavor = "multi_thread")]
+async fn test_explain_cache_misses_on_model_change() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{ "message": { "content": "Closures capture their environment." } }]
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    let cache_dir = temp_dir.path().join("cache");
+    let write_config = |model: &str| {
+        fs::write(
+            &config_path,
+            format!(
+                "[ai]\nprovider = \"Local\"\nmodel = \"{}\"\nendpoint = \"{}/v1\"\n\n[performance.cache]\nenabled = true\ndir = {:?}\n",
+                model,
+                server.uri(),
+                cache_dir.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+    };
+    let explain = || {
+        Command::cargo_bin("groundhog")
+            .unwrap()
+            .args(["--config", config_path.to_str().unwrap(), "explain", "--topic", "closures"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Closures capture their environment."));
+    };
+
+    write_config("llama3");
+    explain();
+    explain();
+    write_config("mistral");
+    explain();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--config", config_path.to_str().unwrap(), "--output", "json", "cache", "stats"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["data"]["entries"], 2);
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "cache", "clear"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 2 cached responses"));
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "cache", "stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Entries: 0"));
+}
+
+/// Test --explain-format patch prints the diff and only changes the file with --apply
+#[tokio::test(flavor = "multi_thread")]
+async fn test_explain_patch_applies_only_with_apply() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let diff = "--- a/main.rs\n+++ b/main.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    run();\n+    run().expect(\"run failed\");\n }\n";
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{ "message": { "content": diff } }]
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(
+        &config_path,
+        format!("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\nendpoint = \"{}/v1\"\n", server.uri()),
+    )
+    .unwrap();
+    let source = temp_dir.path().join("main.rs");
+    fs::write(&source, "fn main() {\n    run();\n}\n").unwrap();
+    let args = [
+        "--config",
+        config_path.to_str().unwrap(),
+        "explain",
+        "--file",
+        source.to_str().unwrap(),
+        "--explain-format",
+        "patch",
+        "--topic",
+        "handle errors",
+    ];
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(args)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+    run().expect(\"run failed\");"));
+    assert_eq!(fs::read_to_string(&source).unwrap(), "fn main() {\n    run();\n}\n");
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(args)
+        .arg("--apply")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("main.rs.orig"));
+    assert_eq!(fs::read_to_string(&source).unwrap(), "fn main() {\n    run().expect(\"run failed\");\n}\n");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("main.rs.orig")).unwrap(), "fn main() {\n    run();\n}\n");
+}
+
+/// Test explain --cite asks for sources and gathers them into footnotes
+#[tokio::test]
+async fn test_explain_cite_collects_footnotes() {
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let reply = "Ownership is checked at compile time [1] and moves transfer it [2].\n\n## Sources\n[1] https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html\n[2] https://doc.rust-lang.org/rust-by-example/scope/move.html";
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_string_contains("Cite your sources"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{ "message": { "content": reply } }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(
+        &config_path,
+        format!("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\nendpoint = \"{}/v1\"\n", server.uri()),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "explain", "--topic", "ownership", "--cite"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "moves transfer it [2].\n\nFootnotes:\n[1] https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html\n[2] https://doc.rust-lang.org/rust-by-example/scope/move.html\n",
+        ))
+        .stdout(predicate::str::contains("## Sources").not());
+}
+
+/// Test --depth only accepts the known levels of detail
+#[test]
+fn test_explain_depth_rejects_unknown_level() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--topic", "rust", "--depth", "extreme"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("[possible values: brief, normal, deep]"));
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--topic", "rust", "--depth", "deep"])
+        .assert()
+        .success();
+}
+
+/// Test --compare only accepts known providers, needs a topic and only prints
+#[test]
+fn test_explain_compare_rejects_unknown_provider() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--topic", "rust", "--compare", "openai,gemini"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown provider 'gemini'"));
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--compare", "openai,anthropic"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--topic"));
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--topic", "rust", "--compare", "openai", "--out", "answers.md"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+/// Test --output table lists providers in aligned columns without color codes
+#[test]
+fn test_providers_list_table() {
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "--output", "table", "providers", "list"])
+        .assert()
+        .success()
+        .stdout("Provider   Configured\n---------  ----------\nOpenAI\nAnthropic\nLocal\n");
+}
+
+/// Test --keep-going explains the remaining files and reports failures
+#[test]
+fn test_explain_keep_going_reports_failures() {
+    let temp_dir = TempDir::new().unwrap();
+    let good = temp_dir.path().join("good.rs");
+    fs::write(&good, "fn good() {}").unwrap();
+    let missing = temp_dir.path().join("missing.rs");
+    
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "explain", "--keep-going"])
+        .args(["--file", missing.to_str().unwrap(), "--file", good.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains("good.rs"))
+        .stderr(predicate::str::contains("Explained 1 of 2 files"))
+        .stderr(predicate::str::contains("failed for 1 of 2 inputs"));
+}
+
+/// Test ping --wait keeps retrying while the provider is unavailable
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ping_wait_retries_until_provider_is_ready() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": [] })))
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\nendpoint = \"{}/v1\"\n\n[performance.retry]\nbase_delay_ms = 10\n",
+            server.uri()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--config", config_path.to_str().unwrap(), "ping", "--wait", "10s"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Model: llama3"))
+        .stderr(predicate::str::contains("..\n"));
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    cmd.args(["--no-config", "ping", "--wait", "3h"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("unknown duration unit 'h'"));
+}
+
+/// Test ping --output json reports the model and latency
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ping_json_output_reports_latency() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": [] })))
+        .mount(&server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("groundhog.toml");
+    fs::write(
+        &config_path,
+        format!("[ai]\nprovider = \"Local\"\nmodel = \"llama3\"\nendpoint = \"{}/v1\"\n", server.uri()),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("groundhog").unwrap();
+    let output = cmd.args(["--config", config_path.to_str().unwrap(), "--output", "json", "ping"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["success"], true);
+    assert_eq!(report["data"]["model"], "llama3");
+    assert!(report["data"]["latency_ms"].is_u64());
+}
+
+/// Test output larger than the pipe buffer ends cleanly when the reader closes early, as with `| head`
+#[test]
+fn test_closed_stdout_exits_cleanly() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    // Larger than a pipe buffer, so writing blocks until the reader goes away
+    let topic = "ownership ".repeat(10_000);
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("groundhog"))
+        .args(["--no-config", "explain", "--topic", &topic])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdout = child.stdout.take().unwrap();
+    let mut head = [0u8; 16];
+    stdout.read_exact(&mut head).unwrap();
+    drop(stdout);
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(&head, b"hello world - ex");
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("error") && !stderr.contains("panicked"), "{}", stderr);
+}